@@ -1,7 +1,8 @@
 use crate::{
     cursor::Cursor,
+    lsp::{InlayHint, Location, Symbol},
     rope::{RopeExt, Text, WordClass, WordCursor},
-    syntax::{Capture, Theme},
+    syntax::{Capture, SemanticToken, Theme},
 };
 use ropey::Rope;
 use std::{
@@ -10,8 +11,9 @@ use std::{
     io::{BufReader, BufWriter, Write},
     ops::Range,
     path::{Path, PathBuf},
+    sync::OnceLock,
 };
-use tree_sitter::{InputEdit, Node, Parser, Point, Query, Tree};
+use tree_sitter::{InputEdit, Node, Parser, Point, Query, QueryCursor, Tree};
 use virus_graphics::text::{Cluster, Context, FontFamilyKey, FontSize, Line};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -99,6 +101,11 @@ pub struct Document {
     tree: Tree,
     is_tree_dirty: bool,
     version: usize,
+    changes: Vec<(Range<(usize, usize)>, String)>,
+    /// Sorted, and kept in place on edits until the server sends new ones.
+    semantic_tokens: Vec<SemanticToken>,
+    /// Sorted, and kept in place on edits until the server sends new ones.
+    inlay_hints: Vec<InlayHint>,
     cached_shaping: Option<CachedShaping>,
 }
 
@@ -111,10 +118,21 @@ impl Document {
             panic!("File type not supported");
         }
 
+        let rope = Rope::from_reader(&mut BufReader::new(File::open(&path)?))?;
+
+        Ok(Self::with_rope(path, rope))
+    }
+
+    /// Returns an empty document without a path (which cannot be saved), e.g. when the last
+    /// document's file is deleted.
+    pub fn scratch() -> Self {
+        Self::with_rope(PathBuf::new(), Rope::new())
+    }
+
+    fn with_rope(path: PathBuf, rope: Rope) -> Self {
         const HIGHLIGHTS_QUERY: &str = include_str!("../treesitter/rust/highlights.scm");
         let language = tree_sitter_rust::language();
 
-        let rope = Rope::from_reader(&mut BufReader::new(File::open(&path)?))?;
         let highlights =
             Query::new(&language, HIGHLIGHTS_QUERY).expect("Cannot create highlights query");
         let mut parser = Parser::new();
@@ -123,7 +141,7 @@ impl Document {
             .expect("Cannot set parser's language");
         let tree = Self::parse_with(&rope, &mut parser, None);
 
-        Ok(Self {
+        Self {
             path,
             rope,
             selection: Default::default(),
@@ -132,8 +150,11 @@ impl Document {
             tree,
             is_tree_dirty: false,
             version: 0,
+            changes: Vec::new(),
+            semantic_tokens: Vec::new(),
+            inlay_hints: Vec::new(),
             cached_shaping: None,
-        })
+        }
     }
 
     // NOTE: good enough for now
@@ -153,6 +174,29 @@ impl Document {
         Ok(())
     }
 
+    /// Moves the document to `path`, e.g. after its file has been renamed.
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+
+    /// Returns the `(line, column)` changes made since the last call, in order.
+    pub fn take_changes(&mut self) -> Vec<(Range<(usize, usize)>, String)> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Sets the semantic tokens, highlighted over the `tree-sitter` captures.
+    pub fn set_semantic_tokens(&mut self, semantic_tokens: Vec<SemanticToken>) {
+        self.semantic_tokens = semantic_tokens;
+        self.cached_shaping = None;
+    }
+
+    /// Sets the inlay hints, shown as virtual text.
+    pub fn set_inlay_hints(&mut self, mut inlay_hints: Vec<InlayHint>) {
+        inlay_hints.sort_by_key(|hint| (hint.line, hint.column));
+        self.inlay_hints = inlay_hints;
+        self.cached_shaping = None;
+    }
+
     /// Reparses the AST.
     ///
     /// Call this function after your edits to the document to update the AST.
@@ -217,10 +261,145 @@ impl Document {
     pub fn version(&self) -> usize {
         self.version
     }
+
+    pub fn semantic_tokens(&self) -> &[SemanticToken] {
+        &self.semantic_tokens
+    }
+
+    pub fn inlay_hints(&self) -> &[InlayHint] {
+        &self.inlay_hints
+    }
+
+    /// Returns a cursor at `(line, column)`, clamped to the document.
+    pub fn cursor_at(&self, line: usize, column: usize) -> Cursor {
+        let line = line.min(self.rope.len_lines().saturating_sub(1));
+        let len = self
+            .rope
+            .line(line)
+            .to_string()
+            .trim_end_matches('\n')
+            .len();
+
+        self.rope.cursor().at_line_column(line, column.min(len))
+    }
+
+    /// Returns the start of the arguments of the call around the head, and the argument index
+    /// of the head in this call.
+    pub fn call_at_head(&self) -> Option<(usize, usize)> {
+        let index = self.selection.head.index();
+        let mut node = self
+            .tree
+            .root_node()
+            .descendant_for_byte_range(index, index)?;
+
+        loop {
+            if node.kind() == "arguments" && node.start_byte() < index && index < node.end_byte() {
+                let mut cursor = node.walk();
+                let argument = node
+                    .children(&mut cursor)
+                    .filter(|child| child.kind() == "," && child.end_byte() <= index)
+                    .count();
+
+                return Some((node.start_byte(), argument));
+            }
+
+            // Unclosed calls, as when typing, do not parse as `arguments`
+            if node.is_error() {
+                let mut cursor = node.walk();
+                let children = node
+                    .children(&mut cursor)
+                    .filter(|child| child.end_byte() <= index)
+                    .collect::<Vec<_>>();
+                let mut depth = 0;
+                let mut argument = 0;
+
+                for child in children.iter().rev() {
+                    match child.kind() {
+                        ")" => depth += 1,
+                        "(" if depth == 0 => return Some((child.start_byte(), argument)),
+                        "(" => depth -= 1,
+                        "," if depth == 0 => argument += 1,
+                        _ => {}
+                    }
+                }
+            }
+
+            node = node.parent()?;
+        }
+    }
+
+    /// Returns the text in the `(line, column)` `range`, clamped to the document.
+    pub fn text(&self, range: Range<(usize, usize)>) -> String {
+        let start = self.cursor_at(range.start.0, range.start.1).index();
+        let end = self.cursor_at(range.end.0, range.end.1).index();
+
+        self.rope.byte_slice(start..end.max(start)).to_string()
+    }
+
+    /// Returns the items (types, functions, modules...) of the document, from `tree-sitter`.
+    ///
+    /// Used as an outline when no language server is running, empty if the query is invalid.
+    pub fn items(&self) -> Vec<Symbol> {
+        const ITEMS_QUERY: &str = include_str!("../treesitter/rust/items.scm");
+        static QUERY: OnceLock<Option<Query>> = OnceLock::new();
+
+        let Some(query) =
+            QUERY.get_or_init(|| Query::new(&tree_sitter_rust::language(), ITEMS_QUERY).ok())
+        else {
+            return Vec::new();
+        };
+        let text = |node: Node| self.rope.byte_slice(node.byte_range()).to_string();
+        // The nearest impl, trait or module around the item
+        let container = |node: Node| {
+            let mut parent = node.parent()?.parent();
+
+            while let Some(node) = parent {
+                match node.kind() {
+                    "impl_item" => {
+                        return Some(format!("impl {}", text(node.child_by_field_name("type")?)))
+                    }
+                    "trait_item" | "mod_item" => {
+                        return Some(text(node.child_by_field_name("name")?))
+                    }
+                    _ => parent = node.parent(),
+                }
+            }
+
+            None
+        };
+
+        QueryCursor::new()
+            .matches(query, self.tree.root_node(), |node: Node| {
+                self.rope
+                    .byte_slice(node.byte_range())
+                    .chunks()
+                    .map(|chunk| chunk.as_bytes())
+            })
+            .flat_map(|captures| captures.captures)
+            .map(|capture| {
+                let node = capture.node;
+                let start = node.start_position();
+                let end = node.end_position();
+
+                Symbol {
+                    name: text(node),
+                    container: container(node),
+                    location: Location {
+                        path: self.path.clone(),
+                        range: (start.row, start.column)..(end.row, end.column),
+                    },
+                }
+            })
+            .collect()
+    }
 }
 
 /// Movements.
 impl Document {
+    pub fn set_selection(&mut self, selection: Selection) {
+        self.selection = selection;
+    }
+
     pub fn move_anchor_to_head(&mut self) {
         self.selection = self.selection.head.clone().into();
     }
@@ -337,6 +516,14 @@ impl Document {
         let removing = start_index != old_end_index;
         let inserting = !text.is_empty();
 
+        if removing || inserting {
+            self.changes.push((
+                (start.line(&self.rope), start.column(&self.rope))
+                    ..(old_end.line(&self.rope), old_end.column(&self.rope)),
+                text.chunks().collect(),
+            ));
+        }
+
         fn insert(rope: &mut Rope, mut char_index: usize, text: &Text) {
             for str in text.chunks() {
                 rope.insert(char_index, str);
@@ -440,6 +627,44 @@ impl Document {
         }
     }
 
+    /// Applies non-overlapping `(line, column)` edits, all relative to the current text.
+    ///
+    /// The selection follows the text around it.
+    pub fn apply_edits(
+        &mut self,
+        edits: impl IntoIterator<Item = (Range<(usize, usize)>, String)>,
+    ) {
+        let mut edits = edits
+            .into_iter()
+            .map(|(range, text)| {
+                let start = self.cursor_at(range.start.0, range.start.1).index();
+                let end = self.cursor_at(range.end.0, range.end.1).index();
+
+                (start..end.max(start), text)
+            })
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let anchor = remap(&edits, self.selection.anchor.index());
+        let head = remap(&edits, self.selection.head.index());
+
+        for (range, text) in edits.into_iter().rev() {
+            self.selection = Selection::new(
+                self.rope.cursor().at_index(range.start),
+                self.rope.cursor().at_index(range.end),
+            );
+            self.edit(&text.as_str().into());
+        }
+
+        let cursor = |index: usize| {
+            let index = index.min(self.rope.len_bytes());
+            self.rope
+                .cursor()
+                .at_index(self.rope.char_to_byte(self.rope.byte_to_char(index)))
+        };
+        self.selection = Selection::new(cursor(anchor), cursor(head));
+    }
+
     // TODO: convenient for now but does not feel good
     pub fn backspace(&mut self) {
         if self.selection.is_empty() {
@@ -463,37 +688,31 @@ impl Document {
         debug_assert!(lines.start <= lines.end);
         debug_assert!(lines.end <= self.rope.len_lines());
 
-        if self.cached_shaping.is_none() {
-            self.cached_shaping = Some(CachedShaping::new(
-                context,
-                &self.rope,
-                self.tree.root_node(),
-                &self.highlights,
-                lines.clone(),
+        let inputs = ShapingInputs {
+            rope: &self.rope,
+            root: self.tree.root_node(),
+            query: &self.highlights,
+            semantic_tokens: &self.semantic_tokens,
+            inlay_hints: &self.inlay_hints,
+            anchor: (
                 self.selection.anchor.line(&self.rope),
                 self.selection.anchor.column(&self.rope),
+            ),
+            head: (
                 self.selection.head.line(&self.rope),
                 self.selection.head.column(&self.rope),
-                family,
-                theme,
-                font_size,
-            ));
-        }
-
-        self.cached_shaping.as_mut().expect("Just created it").get(
-            context,
-            &self.rope,
-            self.tree.root_node(),
-            &self.highlights,
-            lines,
-            self.selection.anchor.line(&self.rope),
-            self.selection.anchor.column(&self.rope),
-            self.selection.head.line(&self.rope),
-            self.selection.head.column(&self.rope),
+            ),
             family,
             theme,
             font_size,
-        )
+        };
+
+        match &mut self.cached_shaping {
+            Some(cached_shaping) => cached_shaping.get(context, &inputs, lines),
+            cached_shaping => cached_shaping
+                .insert(CachedShaping::new(context, &inputs, lines.clone()))
+                .get(context, &inputs, lines),
+        }
     }
 }
 
@@ -543,16 +762,101 @@ impl Document {
         self.is_tree_dirty = true;
         self.version += 1;
         self.cached_shaping = None;
+
+        // Tokens touching the edit are dropped, the following ones are moved with the text
+        let start = (start_line, start_column);
+        let old_end = (old_end_line, old_end_column);
+        let new_end = (new_end_line, new_end_column);
+
+        self.semantic_tokens.retain_mut(|token| {
+            if (token.line, token.end_column) <= start {
+                true
+            } else if (token.line, token.start_column) < old_end {
+                false
+            } else {
+                (token.line, token.start_column) =
+                    moved((token.line, token.start_column), old_end, new_end);
+                (_, token.end_column) = moved((token.line, token.end_column), old_end, new_end);
+                true
+            }
+        });
+
+        // Same for hints, which are positions between characters
+        self.inlay_hints.retain_mut(|hint| {
+            let position = (hint.line, hint.column);
+
+            if position <= start {
+                true
+            } else if position < old_end {
+                false
+            } else {
+                (hint.line, hint.column) = moved(position, old_end, new_end);
+                true
+            }
+        });
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Moves the `(line, column)` `position`, after an edit ending at `old_end`, with the text now
+/// ending at `new_end`.
+fn moved(
+    (line, column): (usize, usize),
+    old_end: (usize, usize),
+    new_end: (usize, usize),
+) -> (usize, usize) {
+    if line == old_end.0 {
+        (new_end.0, column - old_end.1 + new_end.1)
+    } else {
+        (line - old_end.0 + new_end.0, column)
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Maps `index` through sorted, non-overlapping byte `edits`.
+///
+/// Indices inside a replaced range keep their offset, clamped to the new text.
+fn remap(edits: &[(Range<usize>, String)], index: usize) -> usize {
+    let mut delta = 0;
+
+    for (range, text) in edits {
+        if index <= range.start {
+            break;
+        }
+
+        if index < range.end {
+            return (range.start as isize + delta) as usize + (index - range.start).min(text.len());
+        }
+
+        delta += text.len() as isize - range.len() as isize;
     }
+
+    (index as isize + delta) as usize
 }
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
+/// What lines are shaped from.
+struct ShapingInputs<'a> {
+    rope: &'a Rope,
+    root: Node<'a>,
+    query: &'a Query,
+    semantic_tokens: &'a [SemanticToken],
+    inlay_hints: &'a [InlayHint],
+    /// Line and column of the selection's anchor.
+    anchor: (usize, usize),
+    /// Line and column of the selection's head.
+    head: (usize, usize),
+    family: FontFamilyKey,
+    theme: Theme,
+    font_size: FontSize,
+}
+
 struct CachedShaping {
-    anchor_line: usize,
-    anchor_column: usize,
-    head_line: usize,
-    head_column: usize,
+    anchor: (usize, usize),
+    head: (usize, usize),
     family: FontFamilyKey,
     theme: Theme,
     font_size: FontSize,
@@ -561,49 +865,21 @@ struct CachedShaping {
 }
 
 impl CachedShaping {
-    fn new(
-        context: &mut Context,
-        rope: &Rope,
-        root: Node,
-        query: &Query,
-        line_range: Range<usize>,
-        anchor_line: usize,
-        anchor_column: usize,
-        head_line: usize,
-        head_column: usize,
-        family: FontFamilyKey,
-        theme: Theme,
-        font_size: FontSize,
-    ) -> Self {
+    fn new(context: &mut Context, inputs: &ShapingInputs, line_range: Range<usize>) -> Self {
         let line_range = {
             let margin = line_range.len() / 2;
             let start = line_range.start.saturating_sub(margin);
-            let end = (line_range.end + margin).min(rope.len_lines());
+            let end = (line_range.end + margin).min(inputs.rope.len_lines());
             start..end
         };
-        let lines = Self::shape(
-            context,
-            rope,
-            root,
-            query,
-            line_range.clone(),
-            anchor_line,
-            anchor_column,
-            head_line,
-            head_column,
-            family,
-            theme,
-            font_size,
-        );
+        let lines = Self::shape(context, inputs, line_range.clone());
 
         Self {
-            anchor_line,
-            anchor_column,
-            head_line,
-            head_column,
-            family,
-            theme,
-            font_size,
+            anchor: inputs.anchor,
+            head: inputs.head,
+            family: inputs.family,
+            theme: inputs.theme,
+            font_size: inputs.font_size,
             line_range,
             lines,
         }
@@ -612,75 +888,32 @@ impl CachedShaping {
     fn get(
         &mut self,
         context: &mut Context,
-        rope: &Rope,
-        root: Node,
-        query: &Query,
+        inputs: &ShapingInputs,
         line_range: Range<usize>,
-        anchor_line: usize,
-        anchor_column: usize,
-        head_line: usize,
-        head_column: usize,
-        family: FontFamilyKey,
-        theme: Theme,
-        font_size: FontSize,
     ) -> &[Line] {
-        let in_cache = self.family == family
-            && self.theme == theme
-            && self.font_size == font_size
+        let in_cache = self.family == inputs.family
+            && self.theme == inputs.theme
+            && self.font_size == inputs.font_size
             && self.line_range.start <= line_range.start
             && line_range.end <= self.line_range.end;
 
         if !in_cache {
-            *self = Self::new(
-                context,
-                rope,
-                root,
-                query,
-                line_range.clone(),
-                anchor_line,
-                anchor_column,
-                head_line,
-                head_column,
-                family,
-                theme,
-                font_size,
-            );
-        } else if (
-            self.anchor_line,
-            self.anchor_column,
-            self.head_line,
-            self.head_column,
-        ) != (anchor_line, anchor_column, head_line, head_column)
-        {
-            let mut lines = vec![self.anchor_line, self.head_line, anchor_line, head_line];
+            *self = Self::new(context, inputs, line_range.clone());
+        } else if (self.anchor, self.head) != (inputs.anchor, inputs.head) {
+            let mut lines = vec![self.anchor.0, self.head.0, inputs.anchor.0, inputs.head.0];
             lines.sort();
             lines.dedup();
 
             for line in lines {
                 if self.line_range.contains(&line) {
-                    let lines = Self::shape(
-                        context,
-                        rope,
-                        root,
-                        query,
-                        line..line + 1,
-                        anchor_line,
-                        anchor_column,
-                        head_line,
-                        head_column,
-                        family,
-                        theme,
-                        font_size,
-                    );
+                    let lines = Self::shape(context, inputs, line..line + 1);
 
                     self.lines[line - self.line_range.start] = lines.into_iter().next().unwrap();
                 }
             }
 
-            self.anchor_line = anchor_line;
-            self.anchor_column = anchor_column;
-            self.head_line = head_line;
-            self.head_column = head_column;
+            self.anchor = inputs.anchor;
+            self.head = inputs.head;
         }
 
         let start = line_range.start - self.line_range.start;
@@ -688,20 +921,19 @@ impl CachedShaping {
         &self.lines[start..end]
     }
 
-    fn shape(
-        context: &mut Context,
-        rope: &Rope,
-        root: Node,
-        query: &Query,
-        line_range: Range<usize>,
-        anchor_line: usize,
-        anchor_column: usize,
-        head_line: usize,
-        head_column: usize,
-        family: FontFamilyKey,
-        theme: Theme,
-        font_size: FontSize,
-    ) -> Vec<Line> {
+    fn shape(context: &mut Context, inputs: &ShapingInputs, line_range: Range<usize>) -> Vec<Line> {
+        let &ShapingInputs {
+            rope,
+            root,
+            query,
+            semantic_tokens,
+            inlay_hints,
+            anchor: (anchor_line, anchor_column),
+            head: (head_line, head_column),
+            family,
+            theme,
+            font_size,
+        } = inputs;
         let mut lines = rope
             .lines_at(line_range.start)
             .take(line_range.len())
@@ -771,6 +1003,22 @@ impl CachedShaping {
             }
         }
 
+        // Semantic tokens take precedence over captures
+        let start = semantic_tokens.partition_point(|token| token.line < line_range.start);
+
+        for token in semantic_tokens[start..]
+            .iter()
+            .take_while(|token| token.line < line_range.end)
+        {
+            let (_, line) = &mut lines[token.line - line_range.start];
+
+            for cluster in line.clusters_mut() {
+                if (token.start_column..token.end_column).contains(&cluster.range().start) {
+                    *cluster.styles_mut() = theme[token.key];
+                }
+            }
+        }
+
         lines
             .into_iter()
             .enumerate()
@@ -794,14 +1042,78 @@ impl CachedShaping {
                     start..=end
                 };
 
-                line.shape(
+                let mut line = line.shape(
                     context,
                     family,
                     font_size,
                     (i == anchor_line).then(|| word(anchor_column)),
                     (i == head_line).then(|| word(head_column)),
-                )
+                );
+                let start = inlay_hints.partition_point(|hint| hint.line < i);
+
+                for hint in inlay_hints[start..]
+                    .iter()
+                    .take_while(|hint| hint.line == i)
+                {
+                    let text = Line::shaper(&hint.label, usize::MAX, theme.inlay_hint)
+                        .shape(context, family, font_size, None, None);
+
+                    line.insert_virtual(hint.column, text);
+                }
+
+                line
             })
             .collect()
     }
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remap_indices() {
+        // "hello world" -> "hi world!"
+        let edits = [(0..5, String::from("hi")), (11..11, String::from("!"))];
+
+        assert_eq!(remap(&edits, 0), 0);
+        assert_eq!(remap(&edits, 1), 1);
+        assert_eq!(remap(&edits, 4), 2);
+        assert_eq!(remap(&edits, 5), 2);
+        assert_eq!(remap(&edits, 6), 3);
+        assert_eq!(remap(&edits, 11), 8);
+    }
+
+    #[test]
+    fn items() {
+        let file = std::env::temp_dir().join("virus-document-items.rs");
+        std::fs::write(&file, "struct A;\nimpl A {\n    fn new() {}\n}\n").unwrap();
+        let document = Document::open(file).unwrap();
+        let items = document
+            .items()
+            .into_iter()
+            .map(|item| (item.name, item.container, item.location.range.start))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            items,
+            [
+                (String::from("A"), None, (0, 7)),
+                (String::from("new"), Some(String::from("impl A")), (2, 7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn move_positions() {
+        // "ab\ncd" -> "aXY\nZ\ncd": "b" replaced by "XY\nZ"
+        let (old_end, new_end) = ((0, 2), (1, 1));
+
+        assert_eq!(moved((0, 2), old_end, new_end), (1, 1));
+        assert_eq!(moved((1, 1), old_end, new_end), (2, 1));
+    }
+}
@@ -1,17 +1,61 @@
-use crate::{async_actor::AsyncActorSender, document::Document, lsp::Lsp, rope::Text};
+use crate::{
+    async_actor::AsyncActorSender,
+    document::Document,
+    lsp::{self, CodeAction, Location, Lsp, Signature, Symbol},
+    rope::Text,
+};
 use ignore::WalkBuilder;
+use ropey::Rope;
 use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
     future::Future,
+    io::{BufReader, BufWriter},
+    ops::Range,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
+use virus_lsp::{
+    structures::{
+        CreateFileOptions, DeleteFileOptions, Diagnostic, OptionalVersionedTextDocumentIdentifier,
+        OptionalVersionedTextDocumentIdentifierVersion, RenameFileOptions, SemanticTokens,
+        TextDocumentEdit, TextDocumentEditEdits, TextDocumentIdentifier, TextEdit, WorkspaceEdit,
+        WorkspaceEditDocumentChanges,
+    },
+    Integer, LspClient, LspClients, LspServerConfig, LspServerId, Null, ProgressState,
 };
-use tokio::{process::Command, sync::mpsc::unbounded_channel};
-use virus_lsp::LspClients;
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
 #[derive(Debug)]
-pub enum EventLoopMessage {}
+pub enum EventLoopMessage {
+    /// References, sorted by path and position, with their line previews.
+    References(Vec<(Location, String)>),
+    /// Code actions available for the selection.
+    CodeActions(Vec<CodeAction>),
+    /// The signature of the call around the head.
+    SignatureHelp(Signature),
+    /// The symbol under the head can be renamed, with this placeholder.
+    Rename(String),
+    /// Symbols of the active document, flattened.
+    Symbols(Vec<Symbol>),
+    /// Symbols of the workspace matching the last search.
+    WorkspaceSymbols(Vec<Symbol>),
+    /// Documents changed outside of the event loop.
+    Redraw,
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// A failed [`Editor::apply_workspace_edit`].
+#[derive(Debug)]
+pub struct WorkspaceEditError {
+    /// The index of the failed change.
+    pub index: usize,
+    pub reason: String,
+}
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
@@ -24,33 +68,60 @@ pub struct Editor {
     documents: Vec<Document>,
     active_document: usize,
     clipboard: Text,
+    /// Diagnostics by path, by server.
+    diagnostics: HashMap<PathBuf, HashMap<LspServerId, Vec<Diagnostic>>>,
+    format_on_save: Option<Duration>,
     pub(crate) lsps: LspClients,
+    /// Roots of the started servers.
+    lsp_roots: HashMap<LspServerId, PathBuf>,
+    /// Work done progresses of the initialized servers.
+    lsp_progress: BTreeMap<LspServerId, watch::Receiver<Vec<ProgressState>>>,
+    /// Last semantic tokens by path, which deltas apply to.
+    pub(crate) lsp_semantic_tokens: HashMap<PathBuf, SemanticTokens>,
+    /// Lines of the last inlay hints request by path.
+    lsp_inlay_hints: HashMap<PathBuf, Range<usize>>,
+    /// Pending requests which are superseded by the next one of the same method and path.
+    lsp_requests: HashMap<(&'static str, PathBuf), oneshot::Sender<()>>,
     pub(crate) async_actor: AsyncActorSender,
-    _event_loop: EventLoopSender,
+    event_loop: EventLoopSender,
 }
 
 impl Editor {
-    pub fn new(
-        root: PathBuf,
-        (rust_lsp,): (Command,),
-        async_actor: AsyncActorSender,
-        event_loop: EventLoopSender,
-    ) -> Self {
-        let (rust_server_message_sender, rust_server_message_receiver) = unbounded_channel();
+    pub fn new(root: PathBuf, async_actor: AsyncActorSender, event_loop: EventLoopSender) -> Self {
+        let (server_message_sender, server_message_receiver) = unbounded_channel();
         let mut editor = Self {
             root,
             documents: Default::default(),
             active_document: 0,
             clipboard: Text::default(),
-            lsps: LspClients::new((rust_lsp, rust_server_message_sender)),
+            diagnostics: Default::default(),
+            format_on_save: None,
+            lsps: LspClients::new(server_message_sender),
+            lsp_roots: Default::default(),
+            lsp_progress: Default::default(),
+            lsp_semantic_tokens: Default::default(),
+            lsp_inlay_hints: Default::default(),
+            lsp_requests: Default::default(),
             async_actor,
-            _event_loop: event_loop,
+            event_loop,
         };
 
-        editor.lsp().init(rust_server_message_receiver);
+        editor.lsp().init(server_message_receiver);
         editor
     }
 
+    /// Adds a language server for `language_id`, started when the first document of that
+    /// language opens.
+    ///
+    /// Requests go to the first server added for a language.
+    pub fn add_language_server(
+        &mut self,
+        language_id: impl Into<String>,
+        config: LspServerConfig,
+    ) -> LspServerId {
+        self.lsps.add(language_id.into(), config)
+    }
+
     pub fn root(&self) -> &Path {
         self.root.as_path()
     }
@@ -63,6 +134,22 @@ impl Editor {
         self.documents.get_mut(self.active_document).unwrap()
     }
 
+    pub fn documents(&self) -> impl '_ + Iterator<Item = &Document> {
+        self.documents.iter()
+    }
+
+    pub fn document(&self, path: &Path) -> Option<&Document> {
+        self.documents
+            .iter()
+            .find(|document| document.path() == path)
+    }
+
+    pub fn document_mut(&mut self, path: &Path) -> Option<&mut Document> {
+        self.documents
+            .iter_mut()
+            .find(|document| document.path() == path)
+    }
+
     pub fn open(&mut self, path: PathBuf) -> std::io::Result<()> {
         if let Some(active_document) = self
             .documents
@@ -73,6 +160,9 @@ impl Editor {
         } else {
             let mut document = Document::open(path)?;
             document.parse();
+            self.lsp()
+                .open_document(&document)
+                .semantic_tokens(&document);
 
             self.active_document = self.documents.len();
             self.documents.push(document);
@@ -81,6 +171,42 @@ impl Editor {
         Ok(())
     }
 
+    /// Closes the document at `path`, if opened.
+    ///
+    /// The last document cannot be closed.
+    pub fn close(&mut self, path: &Path) {
+        if self.documents.len() == 1 {
+            return;
+        }
+
+        if let Some(index) = self
+            .documents
+            .iter()
+            .position(|document| document.path() == path)
+        {
+            self.remove_document(index);
+        }
+    }
+
+    /// Formats documents before saving them, waiting at most `timeout` for the server.
+    ///
+    /// `None` disables formatting on save.
+    pub fn set_format_on_save(&mut self, timeout: Option<Duration>) {
+        self.format_on_save = timeout;
+    }
+
+    /// Saves the active document.
+    ///
+    /// When formatting on save, the document is formatted and saved asynchronously.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        if let Some(timeout) = self.format_on_save {
+            self.lsp().format_and_save(timeout);
+            Ok(())
+        } else {
+            self.active_document_mut().save()
+        }
+    }
+
     pub fn files(&self, hidden: bool, ignored: bool) -> impl '_ + Iterator<Item = PathBuf> {
         let walker = WalkBuilder::new(&self.root)
             .hidden(!hidden)
@@ -132,6 +258,262 @@ impl Editor {
             .edit(&self.clipboard);
     }
 
+    /// Finds references to the symbol under the active document's head.
+    ///
+    /// Results are sent as [`EventLoopMessage::References`].
+    pub fn references(&mut self) {
+        self.lsp().references();
+    }
+
+    /// Returns the last diagnostics published for `path`, by all servers.
+    pub fn diagnostics(&self, path: &Path) -> impl '_ + Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .get(path)
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, diagnostics)| diagnostics)
+    }
+
+    /// Returns the last lines of `server`'s stderr.
+    pub fn lsp_log(&self, server: LspServerId) -> Vec<String> {
+        self.lsps.log(server)
+    }
+
+    /// Returns the ongoing work done progresses, e.g. `rust-analyzer: Indexing 43%`.
+    pub fn progress(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+
+        for (server, progress) in &self.lsp_progress {
+            let name = self.lsps.name(*server);
+
+            for progress in progress.borrow().iter() {
+                let mut label = format!("{name}: {}", progress.title);
+
+                if let Some(message) = &progress.message {
+                    label += &format!(" {message}");
+                }
+                if let Some(percentage) = progress.percentage {
+                    label += &format!(" {percentage}%");
+                }
+
+                labels.push(label);
+            }
+        }
+
+        labels
+    }
+
+    /// Shuts the language servers down, waiting at most `timeout` for each.
+    ///
+    /// The returned receiver is notified when finished.
+    pub fn shutdown(&mut self, timeout: Duration) -> std::sync::mpsc::Receiver<()> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.lsp().shutdown(timeout, sender);
+        receiver
+    }
+
+    /// Formats the active document, or only its selection if not empty.
+    pub fn format(&mut self) {
+        let selection = !self.active_document().selection().is_empty();
+        self.lsp().format(selection);
+    }
+
+    /// Requests the signature of the call around the active document's head.
+    ///
+    /// Results are sent as [`EventLoopMessage::SignatureHelp`].
+    pub fn signature_help(&mut self) {
+        self.lsp().signature_help();
+    }
+
+    /// Requests code actions for the active document's selection.
+    ///
+    /// Results are sent as [`EventLoopMessage::CodeActions`].
+    pub fn code_actions(&mut self) {
+        self.lsp().code_actions();
+    }
+
+    /// Applies `action`, as offered in [`EventLoopMessage::CodeActions`].
+    pub fn apply_code_action(&mut self, action: CodeAction) {
+        self.lsp().apply_code_action(action);
+    }
+
+    /// Requests the symbols of the active document, for an outline.
+    ///
+    /// Results are sent as [`EventLoopMessage::Symbols`].
+    pub fn document_symbols(&mut self) {
+        self.lsp().document_symbols();
+    }
+
+    /// Searches the symbols of the workspace matching `query`.
+    ///
+    /// Results are sent as [`EventLoopMessage::WorkspaceSymbols`].
+    pub fn workspace_symbols(&mut self, query: String) {
+        self.lsp().workspace_symbols(query);
+    }
+
+    /// Requests the inlay hints of the active document on `lines`, e.g. the visible ones.
+    ///
+    /// Does nothing if `lines` did not change: hints are requested again on edits.
+    pub fn inlay_hints(&mut self, lines: Range<usize>) {
+        let document = self.active_document();
+        let end = lines.end.min(document.rope().len_lines());
+        let lines = lines.start.min(end)..end;
+        let path = document.path().to_owned();
+
+        if self.lsp_inlay_hints.get(&path) == Some(&lines) {
+            return;
+        }
+
+        self.lsp_inlay_hints.insert(path, lines.clone());
+        let document = self.documents.remove(self.active_document);
+        self.lsp().inlay_hints(&document, lines);
+        self.documents.insert(self.active_document, document);
+    }
+
+    /// Checks that the symbol under the active document's head can be renamed.
+    ///
+    /// The placeholder is sent as [`EventLoopMessage::Rename`].
+    pub fn prepare_rename(&mut self) {
+        self.lsp().prepare_rename();
+    }
+
+    /// Renames the symbol under the active document's head to `new_name`.
+    pub fn rename(&mut self, new_name: String) {
+        self.lsp().rename(new_name);
+    }
+
+    /// Notifies the language server of the changes made to documents since the last call.
+    pub fn flush_changes(&mut self) {
+        for index in 0..self.documents.len() {
+            let changes = self.documents[index].take_changes();
+
+            if !changes.is_empty() {
+                let document = self.documents.remove(index);
+                let lines = self.lsp_inlay_hints.get(document.path()).cloned();
+                let lsp = self
+                    .lsp()
+                    .change_document(&document, changes)
+                    .semantic_tokens(&document);
+
+                if let Some(lines) = lines {
+                    lsp.inlay_hints(&document, lines);
+                }
+
+                self.documents.insert(index, document);
+            }
+        }
+    }
+
+    /// Applies `edit` to opened documents, or to files on disk.
+    ///
+    /// Versions of opened documents are checked before anything is applied. Changes are then
+    /// applied in order, stopping at the first failure.
+    pub fn apply_workspace_edit(&mut self, edit: WorkspaceEdit) -> Result<(), WorkspaceEditError> {
+        let changes = match (edit.document_changes, edit.changes) {
+            (Some(changes), _) => changes,
+            (None, Some(changes)) => changes
+                .into_iter()
+                .map(|(uri, edits)| {
+                    WorkspaceEditDocumentChanges::TextDocumentEdit(TextDocumentEdit {
+                        text_document: OptionalVersionedTextDocumentIdentifier {
+                            text_document_identifier: TextDocumentIdentifier { uri },
+                            version: OptionalVersionedTextDocumentIdentifierVersion::Null(Null),
+                        },
+                        edits: edits
+                            .into_iter()
+                            .map(TextDocumentEditEdits::TextEdit)
+                            .collect(),
+                    })
+                })
+                .collect(),
+            (None, None) => Vec::new(),
+        };
+
+        for (index, change) in changes.iter().enumerate() {
+            let WorkspaceEditDocumentChanges::TextDocumentEdit(edit) = change else {
+                continue;
+            };
+            let OptionalVersionedTextDocumentIdentifierVersion::Integer(version) =
+                edit.text_document.version
+            else {
+                continue;
+            };
+            let path = lsp::path(&edit.text_document.text_document_identifier.uri);
+
+            if let Some(document) = self.document(&path) {
+                if document.version() as Integer != version {
+                    return Err(WorkspaceEditError {
+                        index,
+                        reason: format!(
+                            "{} is at version {}, not {version}",
+                            path.display(),
+                            document.version(),
+                        ),
+                    });
+                }
+            }
+        }
+
+        let result = changes
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(index, change)| {
+                self.apply_document_change(change)
+                    .map_err(|error| WorkspaceEditError {
+                        index,
+                        reason: error.to_string(),
+                    })
+            });
+
+        self.flush_changes();
+        result
+    }
+
+    /// Returns `locations` sorted by path and position, with their first line as preview.
+    ///
+    /// Previews are read from opened documents, or from disk.
+    pub fn previews(
+        &self,
+        locations: impl IntoIterator<Item = Location>,
+    ) -> Vec<(Location, String)> {
+        let mut locations = locations.into_iter().collect::<Vec<_>>();
+        locations.sort_by(|a, b| {
+            (&a.path, a.range.start, a.range.end).cmp(&(&b.path, b.range.start, b.range.end))
+        });
+
+        let mut previews = Vec::with_capacity(locations.len());
+        let mut file = Option::<(PathBuf, String)>::None;
+
+        for location in locations {
+            let line = location.range.start.0;
+            let preview = if let Some(document) = self.document(&location.path) {
+                let rope = document.rope();
+
+                if line < rope.len_lines() {
+                    rope.line(line).to_string()
+                } else {
+                    String::new()
+                }
+            } else {
+                if file.as_ref().map(|(path, _)| path) != Some(&location.path) {
+                    file = Some((
+                        location.path.clone(),
+                        std::fs::read_to_string(&location.path).unwrap_or_default(),
+                    ));
+                }
+
+                file.as_ref()
+                    .and_then(|(_, string)| string.lines().nth(line))
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+
+            previews.push((location, preview.trim().to_owned()));
+        }
+
+        previews
+    }
+
     pub fn find_git_root(path: PathBuf) -> Option<PathBuf> {
         let mut current = match std::fs::canonicalize(path) {
             Ok(path) => Some(path),
@@ -152,10 +534,274 @@ impl Editor {
 
 /// Private.
 impl Editor {
-    fn lsp(&mut self) -> Lsp {
+    pub(crate) fn lsp(&mut self) -> Lsp {
         Lsp { editor: self }
     }
 
+    fn remove_document(&mut self, index: usize) {
+        let document = self.documents.remove(index);
+        self.lsp().close_document(&document);
+        self.lsp_semantic_tokens.remove(document.path());
+        self.lsp_inlay_hints.remove(document.path());
+
+        if self.active_document > index
+            || (self.active_document == index && index == self.documents.len())
+        {
+            self.active_document -= 1;
+        }
+    }
+
+    /// Closes the documents at or under `path` and forgets the state of those paths.
+    ///
+    /// Unlike [`Self::close`], the last document is replaced by a scratch document.
+    fn discard(&mut self, path: &Path) {
+        while let Some(index) = self
+            .documents
+            .iter()
+            .position(|document| document.path().starts_with(path))
+        {
+            if self.documents.len() == 1 {
+                self.documents.push(Document::scratch());
+            }
+
+            self.remove_document(index);
+        }
+
+        self.diagnostics.retain(|key, _| !key.starts_with(path));
+        self.lsp_requests
+            .retain(|(_, key), _| !key.starts_with(path));
+    }
+
+    /// Moves the documents at or under `old_path` to `new_path`, with the state of those paths.
+    fn move_documents(&mut self, old_path: &Path, new_path: &Path) {
+        for index in 0..self.documents.len() {
+            if let Ok(suffix) = self.documents[index].path().strip_prefix(old_path) {
+                let path = new_path.join(suffix);
+                let mut document = self.documents.remove(index);
+
+                self.lsp().close_document(&document);
+                self.lsp_semantic_tokens.remove(document.path());
+                document.set_path(path);
+                self.lsp()
+                    .open_document(&document)
+                    .semantic_tokens(&document);
+                self.documents.insert(index, document);
+            }
+        }
+
+        rename_keys(&mut self.diagnostics, old_path, new_path);
+        rename_keys(&mut self.lsp_inlay_hints, old_path, new_path);
+        self.lsp_requests
+            .retain(|(_, key), _| !key.starts_with(old_path));
+    }
+
+    fn apply_document_change(
+        &mut self,
+        change: WorkspaceEditDocumentChanges,
+    ) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        match change {
+            WorkspaceEditDocumentChanges::TextDocumentEdit(edit) => {
+                let path = lsp::path(&edit.text_document.text_document_identifier.uri);
+                let edits = edit.edits.into_iter().map(|edit| {
+                    let TextEdit { range, new_text } = match edit {
+                        TextDocumentEditEdits::TextEdit(edit) => edit,
+                        TextDocumentEditEdits::AnnotatedTextEdit(edit) => edit.text_edit,
+                    };
+
+                    (lsp::range(range), new_text)
+                });
+
+                if let Some(document) = self.document_mut(&path) {
+                    document.apply_edits(edits);
+                    document.parse();
+                    Ok(())
+                } else {
+                    edit_file(&path, edits)
+                }
+            }
+            WorkspaceEditDocumentChanges::CreateFile(create) => {
+                let path = lsp::path(&create.uri);
+                let options = create.options.unwrap_or(CreateFileOptions {
+                    overwrite: None,
+                    ignore_if_exists: None,
+                });
+
+                if path.exists() && options.overwrite != Some(true) {
+                    return if options.ignore_if_exists == Some(true) {
+                        Ok(())
+                    } else {
+                        Err(Error::new(
+                            ErrorKind::AlreadyExists,
+                            format!("{} already exists", path.display()),
+                        ))
+                    };
+                }
+
+                File::create(&path)?;
+                self.discard(&path);
+                Ok(())
+            }
+            WorkspaceEditDocumentChanges::RenameFile(rename) => {
+                let old_path = lsp::path(&rename.old_uri);
+                let new_path = lsp::path(&rename.new_uri);
+                let options = rename.options.unwrap_or(RenameFileOptions {
+                    overwrite: None,
+                    ignore_if_exists: None,
+                });
+
+                if new_path.exists() && options.overwrite != Some(true) {
+                    return if options.ignore_if_exists == Some(true) {
+                        Ok(())
+                    } else {
+                        Err(Error::new(
+                            ErrorKind::AlreadyExists,
+                            format!("{} already exists", new_path.display()),
+                        ))
+                    };
+                }
+
+                std::fs::rename(&old_path, &new_path)?;
+                self.discard(&new_path);
+                self.move_documents(&old_path, &new_path);
+                Ok(())
+            }
+            WorkspaceEditDocumentChanges::DeleteFile(delete) => {
+                let path = lsp::path(&delete.uri);
+                let options = delete.options.unwrap_or(DeleteFileOptions {
+                    recursive: None,
+                    ignore_if_not_exists: None,
+                });
+
+                if !path.exists() {
+                    return if options.ignore_if_not_exists == Some(true) {
+                        Ok(())
+                    } else {
+                        Err(Error::new(
+                            ErrorKind::NotFound,
+                            format!("{} does not exist", path.display()),
+                        ))
+                    };
+                }
+
+                if path.is_dir() {
+                    if options.recursive == Some(true) {
+                        std::fs::remove_dir_all(&path)?;
+                    } else {
+                        std::fs::remove_dir(&path)?;
+                    }
+                } else {
+                    std::fs::remove_file(&path)?;
+                }
+
+                self.discard(&path);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn set_diagnostics(
+        &mut self,
+        path: PathBuf,
+        server: LspServerId,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        self.diagnostics
+            .entry(path)
+            .or_default()
+            .insert(server, diagnostics);
+    }
+
+    /// Sets the work done progresses of `server`, or clears them when it exits.
+    pub(crate) fn set_lsp_progress(
+        &mut self,
+        server: LspServerId,
+        progress: Option<watch::Receiver<Vec<ProgressState>>>,
+    ) {
+        match progress {
+            Some(progress) => self.lsp_progress.insert(server, progress),
+            None => self.lsp_progress.remove(&server),
+        };
+    }
+
+    /// Returns the root of `server`, if started.
+    pub(crate) fn lsp_root(&self, server: LspServerId) -> Option<&Path> {
+        self.lsp_roots.get(&server).map(PathBuf::as_path)
+    }
+
+    /// Marks `server` as started for the document at `path`.
+    ///
+    /// Its root is the nearest ancestor of `path` with one of its root markers, or the editor's
+    /// root.
+    pub(crate) fn start_lsp(&mut self, server: LspServerId, path: &Path) {
+        let markers = self.lsps.root_markers(server);
+        let root = path
+            .ancestors()
+            .skip(1)
+            .find(|directory| markers.iter().any(|marker| directory.join(marker).exists()))
+            .map(Path::to_owned)
+            .unwrap_or_else(|| self.root.clone());
+
+        self.lsp_roots.insert(server, root);
+    }
+
+    /// Supersedes the pending `method` request for `path`: the returned receiver resolves when
+    /// the next one is made.
+    pub(crate) fn supersede(&mut self, method: &'static str, path: &Path) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.lsp_requests.insert((method, path.to_owned()), sender);
+        receiver
+    }
+
+    /// Requests the semantic tokens of all documents again, e.g. when the server asks for it.
+    pub(crate) fn refresh_semantic_tokens(&mut self) {
+        for index in 0..self.documents.len() {
+            let document = self.documents.remove(index);
+            self.lsp().semantic_tokens(&document);
+            self.documents.insert(index, document);
+        }
+    }
+
+    /// Requests the inlay hints of all documents again, on their last requested lines.
+    pub(crate) fn refresh_inlay_hints(&mut self) {
+        for index in 0..self.documents.len() {
+            let document = self.documents.remove(index);
+
+            if let Some(lines) = self.lsp_inlay_hints.get(document.path()).cloned() {
+                self.lsp().inlay_hints(&document, lines);
+            }
+
+            self.documents.insert(index, document);
+        }
+    }
+
+    /// Returns the clients of the started servers of `language_id`, main server first.
+    pub(crate) fn lsp_clients(
+        &mut self,
+        language_id: &str,
+    ) -> Vec<(LspServerId, Arc<tokio::sync::Mutex<LspClient>>)> {
+        self.lsps
+            .servers(language_id)
+            .to_vec()
+            .into_iter()
+            .filter(|server| self.lsp_roots.contains_key(server))
+            .map(|server| (server, self.lsps.client(server)))
+            .collect()
+    }
+
+    /// Returns the client of the main server of `language_id`, if started.
+    pub(crate) fn lsp_client(
+        &mut self,
+        language_id: &str,
+    ) -> Option<(LspServerId, Arc<tokio::sync::Mutex<LspClient>>)> {
+        self.lsp_clients(language_id).into_iter().next()
+    }
+
+    pub(crate) fn event_loop(&self, message: EventLoopMessage) {
+        (self.event_loop)(message);
+    }
+
     pub(crate) fn async_actor<F, Fut>(&self, function: F)
     where
         F: 'static + Send + FnOnce(Arc<Mutex<Editor>>) -> Fut,
@@ -166,3 +812,55 @@ impl Editor {
             .expect("Failed to send to async actor");
     }
 }
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Applies non-overlapping `(line, column)` `edits` to the file at `path`.
+fn edit_file(
+    path: &Path,
+    edits: impl IntoIterator<Item = (Range<(usize, usize)>, String)>,
+) -> std::io::Result<()> {
+    let mut rope = Rope::from_reader(BufReader::new(File::open(path)?))?;
+    let index = |rope: &Rope, (line, column): (usize, usize)| {
+        if line < rope.len_lines() {
+            rope.line_to_byte(line) + column.min(rope.line(line).len_bytes())
+        } else {
+            rope.len_bytes()
+        }
+    };
+
+    let mut edits = edits
+        .into_iter()
+        .map(|(range, text)| {
+            let start = index(&rope, range.start);
+            let end = index(&rope, range.end);
+
+            (start..end.max(start), text)
+        })
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|(range, _)| range.start);
+
+    for (range, text) in edits.into_iter().rev() {
+        let start = rope.byte_to_char(range.start);
+        let end = rope.byte_to_char(range.end);
+
+        rope.remove(start..end);
+        rope.insert(start, &text);
+    }
+
+    rope.write_to(BufWriter::new(File::create(path)?))
+}
+
+/// Moves the entries of `map` at or under `old_path` to `new_path`.
+fn rename_keys<T>(map: &mut HashMap<PathBuf, T>, old_path: &Path, new_path: &Path) {
+    let keys = map
+        .keys()
+        .filter(|key| key.starts_with(old_path))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for key in keys {
+        let value = map.remove(&key).unwrap();
+        map.insert(new_path.join(key.strip_prefix(old_path).unwrap()), value);
+    }
+}
@@ -21,8 +21,10 @@ pub mod rope {
 }
 pub mod syntax {
     pub use capture::*;
+    pub use semantic::*;
     pub use theme::*;
 
     mod capture;
+    mod semantic;
     mod theme;
 }
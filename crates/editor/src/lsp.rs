@@ -1,26 +1,242 @@
-use crate::{document::Document, editor::Editor};
+use crate::{
+    document::Document,
+    editor::{Editor, EventLoopMessage},
+    syntax::SemanticToken,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 use serde_json::Value;
 use std::{
+    fmt::Display,
     ops::Range,
-    path::Component,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use virus_lsp::{
-    enumerations::{PositionEncodingKind, TraceValues},
+    enumerations::{
+        CodeActionKind, CodeActionTriggerKind, FailureHandlingKind, PositionEncodingKind,
+        ResourceOperationKind, TokenFormat, TraceValues,
+    },
+    notifications::{
+        NotificationTrait, TextDocumentDidChange, TextDocumentDidClose, TextDocumentDidOpen,
+    },
+    requests::{
+        self, CodeActionResolve, RequestTrait, TextDocumentCodeAction,
+        TextDocumentCodeActionResult, TextDocumentCodeActionResultCommandOrCodeAction,
+        TextDocumentDocumentSymbol, TextDocumentDocumentSymbolResult, TextDocumentFormatting,
+        TextDocumentFormattingResult, TextDocumentInlayHint, TextDocumentInlayHintResult,
+        TextDocumentPrepareRename, TextDocumentPrepareRenameResult, TextDocumentRangeFormatting,
+        TextDocumentRangeFormattingResult, TextDocumentReferences, TextDocumentReferencesResult,
+        TextDocumentRename, TextDocumentRenameResult, TextDocumentSemanticTokensFull,
+        TextDocumentSemanticTokensFullDelta, TextDocumentSemanticTokensFullDeltaResult,
+        TextDocumentSemanticTokensFullResult, TextDocumentSignatureHelp,
+        TextDocumentSignatureHelpResult, WorkspaceExecuteCommand, WorkspaceSymbolResult,
+    },
     structures::{
-        ClientCapabilities, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
-        DidOpenTextDocumentParams, GeneralClientCapabilities, InitializeParams,
-        InitializeParamsProcessId, InitializeParamsWorkspaceFolders, InitializedParams, Position,
-        Range as LspRange, TextDocumentIdentifier, TextDocumentItem,
+        ApplyWorkspaceEditResult, ClientCapabilities, CodeActionClientCapabilities,
+        CodeActionClientCapabilitiesCodeActionLiteralSupport,
+        CodeActionClientCapabilitiesCodeActionLiteralSupportCodeActionKind,
+        CodeActionClientCapabilitiesResolveSupport, CodeActionContext, CodeActionParams, Command,
+        DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+        DocumentFormattingParams, DocumentRangeFormattingParams, DocumentSymbol,
+        DocumentSymbolClientCapabilities, DocumentSymbolParams, ExecuteCommandParams,
+        FormattingOptions, GeneralClientCapabilities, InitializeParams, InitializeParamsProcessId,
+        InitializeParamsWorkspaceFolders, InitializeResult, InitializedParams,
+        InlayHint as LspInlayHint, InlayHintClientCapabilities, InlayHintLabel, InlayHintParams,
+        InlayHintWorkspaceClientCapabilities, Location as LspLocation, ParameterInformationLabel,
+        PartialResultParams, Position, PrepareRenameParams, Range as LspRange, ReferenceContext,
+        ReferenceParams, RenameClientCapabilities, RenameParams, SemanticTokensClientCapabilities,
+        SemanticTokensClientCapabilitiesRequests, SemanticTokensClientCapabilitiesRequestsFull,
+        SemanticTokensClientCapabilitiesRequestsFullOptionalDelta, SemanticTokensDeltaParams,
+        SemanticTokensOptionsFull, SemanticTokensOptionsFullOptionalDelta, SemanticTokensParams,
+        SemanticTokensWorkspaceClientCapabilities, ServerCapabilitiesSemanticTokensProvider,
+        SignatureHelpParams, SymbolInformation, TextDocumentClientCapabilities,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, TextEdit,
         VersionedTextDocumentIdentifier, WindowClientCapabilities, WorkDoneProgressParams,
-        WorkspaceFolder,
+        WorkspaceClientCapabilities, WorkspaceEditClientCapabilities, WorkspaceFolder,
+        WorkspaceSymbol, WorkspaceSymbolClientCapabilities, WorkspaceSymbolLocation,
+        WorkspaceSymbolParams,
     },
     type_aliases::{
-        ProgressToken, TextDocumentContentChangeEvent, TextDocumentContentChangeEventRangeAndText,
+        PrepareRenameResult, ProgressToken, TextDocumentContentChangeEvent,
+        TextDocumentContentChangeEventRangeAndText,
     },
-    Integer, ServerMessage, ServerMessageReceiver, ServerNotification, ServerRequest, UInteger,
+    Integer, LspClient, LspServerId, LspServerMessageReceiver, Null, ProtocolError, ServerMessage,
+    ServerNotification, ServerRequest, UInteger,
 };
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Location                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A `(line, column)` range in the file at `path`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Location {
+    pub path: PathBuf,
+    pub range: Range<(usize, usize)>,
+}
+
+impl From<LspLocation> for Location {
+    fn from(location: LspLocation) -> Self {
+        Self {
+            path: path(&location.uri),
+            range: range(location.range),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Symbol                                             //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A symbol (type, function...), named in its `container` if any.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub container: Option<String>,
+    pub location: Location,
+}
+
+impl Symbol {
+    /// Flattens hierarchical document `symbols` of the file at `path`, children after parents.
+    fn flatten(path: &Path, symbols: Vec<DocumentSymbol>, container: Option<&str>) -> Vec<Self> {
+        symbols
+            .into_iter()
+            .flat_map(|symbol| {
+                let children = Self::flatten(
+                    path,
+                    symbol.children.unwrap_or_default(),
+                    Some(&symbol.name),
+                );
+
+                std::iter::once(Self {
+                    name: symbol.name,
+                    container: container.map(String::from),
+                    location: Location {
+                        path: path.to_owned(),
+                        range: range(symbol.selection_range),
+                    },
+                })
+                .chain(children)
+            })
+            .collect()
+    }
+}
+
+impl From<SymbolInformation> for Symbol {
+    fn from(symbol: SymbolInformation) -> Self {
+        Self {
+            name: symbol.base_symbol_information.name,
+            container: symbol.base_symbol_information.container_name,
+            location: symbol.location.into(),
+        }
+    }
+}
+
+impl From<WorkspaceSymbol> for Symbol {
+    fn from(symbol: WorkspaceSymbol) -> Self {
+        Self {
+            name: symbol.base_symbol_information.name,
+            container: symbol.base_symbol_information.container_name,
+            location: match symbol.location {
+                WorkspaceSymbolLocation::Location(location) => location.into(),
+                WorkspaceSymbolLocation::Uri(location) => Location {
+                    path: path(&location.uri),
+                    range: (0, 0)..(0, 0),
+                },
+            },
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           InlayHint                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A hint shown as virtual text before the `(line, column)` position, e.g. an inferred type.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct InlayHint {
+    pub line: usize,
+    pub column: usize,
+    pub label: String,
+}
+
+impl From<LspInlayHint> for InlayHint {
+    fn from(hint: LspInlayHint) -> Self {
+        let label = match hint.label {
+            InlayHintLabel::String(label) => label,
+            InlayHintLabel::InlayHintLabelPartList(parts) => {
+                parts.into_iter().map(|part| part.value).collect()
+            }
+        };
+        let left = if hint.padding_left == Some(true) {
+            " "
+        } else {
+            ""
+        };
+        let right = if hint.padding_right == Some(true) {
+            " "
+        } else {
+            ""
+        };
+
+        Self {
+            line: hint.position.line as usize,
+            column: hint.position.character as usize,
+            label: format!("{left}{label}{right}"),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           CodeAction                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A code action (or a bare command) offered by the language server.
+#[derive(Clone, Debug)]
+pub struct CodeAction {
+    pub title: String,
+    /// The server which offered the action.
+    server: LspServerId,
+    action: TextDocumentCodeActionResultCommandOrCodeAction,
+}
+
+impl CodeAction {
+    fn new(server: LspServerId, action: TextDocumentCodeActionResultCommandOrCodeAction) -> Self {
+        Self {
+            title: match &action {
+                TextDocumentCodeActionResultCommandOrCodeAction::Command(command) => {
+                    command.title.clone()
+                }
+                TextDocumentCodeActionResultCommandOrCodeAction::CodeAction(action) => {
+                    action.title.clone()
+                }
+            },
+            server,
+            action,
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           Signature                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A function signature, with the byte ranges of its parameters in `label`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Signature {
+    pub label: String,
+    pub parameters: Vec<Range<usize>>,
+    pub active_parameter: usize,
+}
+
+impl Signature {
+    /// Returns the byte range of the active parameter in `label`, if any.
+    pub fn active_parameter(&self) -> Option<Range<usize>> {
+        self.parameters.get(self.active_parameter).cloned()
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                              Lsp                                               //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -30,91 +246,144 @@ pub struct Lsp<'editor> {
 }
 
 impl<'editor> Lsp<'editor> {
-    pub fn init(self, rust_receiver: ServerMessageReceiver) -> Self {
-        let process_id = std::process::id() as Integer;
-
+    pub fn init(self, receiver: LspServerMessageReceiver) -> Self {
         self.editor
-            .async_actor(|editor| rust_lsp_handler(editor, rust_receiver));
+            .async_actor(|editor| lsp_handler(editor, receiver));
+
+        self
+    }
+
+    /// Initializes `server`, re-opening its opened documents if `reopen` (e.g. after a restart).
+    pub fn initialize(self, server: LspServerId, reopen: bool) -> Self {
+        let process_id = std::process::id() as Integer;
 
         self.editor.async_actor(move |editor| async move {
-            let (client, folder) = {
+            let (client, folder, options, documents) = {
                 let mut editor = editor.lock().unwrap();
-                let root = editor.root();
+                let root = editor.lsp_root(server).expect("Server root");
                 let folder = WorkspaceFolder {
-                    uri: format!("file://{}", root.to_owned().to_str().unwrap()),
-                    name: match root.components().last() {
-                        Some(Component::Normal(str)) => str.to_str().unwrap().to_owned(),
-                        _ => panic!(),
-                    },
+                    uri: uri(root),
+                    name: root
+                        .file_name()
+                        .unwrap_or(root.as_os_str())
+                        .to_string_lossy()
+                        .into_owned(),
+                };
+                let options = editor.lsps.initialization_options(server).cloned();
+                let documents = if reopen {
+                    editor
+                        .documents()
+                        .filter(|document| {
+                            editor
+                                .lsps
+                                .servers(&language_id(document.path()))
+                                .contains(&server)
+                        })
+                        .map(text_document_item)
+                        .collect()
+                } else {
+                    Vec::new()
                 };
 
-                (editor.lsps.rust(), folder)
+                (editor.lsps.client(server), folder, options, documents)
             };
 
-            let mut client = client.lock().await;
-            let result = client
-                .request()
-                .initialize(initialize_params(
-                    process_id,
-                    String::from("Initialize work done progress token"),
-                    Some(serde_json::json!({
-                        "rustfmt": {
-                            "rangeFormatting": {
-                                "enable": true, // Requires nightly...
-                            },
-                        },
-                    })),
-                    folder,
-                ))
-                .await
-                .unwrap()
-                .await
-                .unwrap()
-                .unwrap();
-            client
-                .notification()
-                .initialized(InitializedParams {})
-                .await
-                .unwrap();
-            client.wait_for_work_done().await;
-            client.init(result);
+            let params = initialize_params(
+                process_id,
+                String::from("Initialize work done progress token"),
+                options,
+                folder,
+            );
+
+            // Not held while waiting: the server may send requests before answering
+            let response = {
+                let mut client = client.lock().await;
+                editor
+                    .lock()
+                    .unwrap()
+                    .set_lsp_progress(server, Some(client.progress()));
+
+                client.request().initialize(params).await
+            };
+            let result = match response {
+                Ok(response) => response.await,
+                Err(error) => Err(error),
+            };
+            let result = match result {
+                Ok(Ok(result)) => result,
+                Ok(Err(error)) => return fail(&client, error.message).await,
+                Err(error) => return fail(&client, error).await,
+            };
+
+            // Only UTF-8 positions are supported, and servers default to UTF-16
+            if result.capabilities.position_encoding != Some(PositionEncodingKind::Utf8) {
+                return fail(&client, "UTF-8 positions are not supported").await;
+            }
+
+            let sent = async {
+                let mut client = client.lock().await;
+                client
+                    .notification()
+                    .initialized(InitializedParams {})
+                    .await?;
+
+                for text_document in documents {
+                    client
+                        .notification()
+                        .text_document_did_open(DidOpenTextDocumentParams { text_document })
+                        .await?;
+                }
+
+                std::io::Result::Ok(())
+            };
+
+            if let Err(error) = sent.await {
+                return fail(&client, error).await;
+            }
+
+            let work_done = client.lock().await.wait_for_work_done();
+            work_done.await;
+            client.lock().await.init(result);
         });
 
         self
     }
 
-    pub fn open_document(self, document: &Document) -> Self {
-        let uri = document.path().as_os_str().to_str().unwrap();
-        let uri = format!("file://{uri}");
-        let language_id = document.path().extension().unwrap().to_str().unwrap();
-        let language_id = (language_id == "rs")
-            .then(|| "rust")
-            .unwrap_or(language_id)
-            .to_owned();
-        let text = document.rope().to_string();
-        let version = document.version() as Integer;
+    /// Shuts the language servers down, waiting at most `timeout` for each.
+    ///
+    /// `done` is notified when finished.
+    pub fn shutdown(self, timeout: Duration, done: std::sync::mpsc::Sender<()>) -> Self {
+        let shutdown = self.editor.lsps.shutdown(timeout);
 
-        self.editor.async_actor(move |editor| async move {
-            let client = {
-                let mut editor = editor.lock().unwrap();
+        self.editor.async_actor(move |_| async move {
+            shutdown.await;
+            let _ = done.send(());
+        });
 
-                editor.lsps.rust()
-            };
+        self
+    }
 
-            let mut client = client.lock().await;
-            client.initied().await;
-            client
-                .notification()
-                .text_document_did_open(DidOpenTextDocumentParams {
-                    text_document: TextDocumentItem {
-                        uri,
-                        language_id,
-                        version,
-                        text,
-                    },
-                })
-                .await
-                .unwrap();
+    /// Opens `document` in the servers of its language, starting them if needed.
+    pub fn open_document(mut self, document: &Document) -> Self {
+        let language_id = language_id(document.path());
+        let text_document = text_document_item(document);
+
+        for server in self.editor.lsps.servers(&language_id).to_vec() {
+            if self.editor.lsp_root(server).is_none() {
+                self.editor.start_lsp(server, document.path());
+                self = self.initialize(server, false);
+            }
+        }
+
+        self.editor.async_actor(move |editor| async move {
+            let clients = editor.lock().unwrap().lsp_clients(&language_id);
+
+            for (_, client) in clients {
+                let params = DidOpenTextDocumentParams {
+                    text_document: text_document.clone(),
+                };
+                notify::<TextDocumentDidOpen>(&client, params).await;
+            }
         });
 
         self
@@ -125,74 +394,819 @@ impl<'editor> Lsp<'editor> {
         document: &Document,
         changes: impl IntoIterator<Item = (Range<(usize, usize)>, String)> + Send + 'static,
     ) -> Self {
-        let uri = document.path().as_os_str().to_str().unwrap();
-        let uri = format!("file://{uri}");
-        let version = document.version() as Integer;
+        let language_id = language_id(document.path());
+        let params = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                text_document_identifier: TextDocumentIdentifier {
+                    uri: uri(document.path()),
+                },
+                version: document.version() as Integer,
+            },
+            content_changes: changes
+                .into_iter()
+                .map(|(range, text)| {
+                    TextDocumentContentChangeEvent::RangeAndText(
+                        TextDocumentContentChangeEventRangeAndText {
+                            range: LspRange {
+                                start: Position {
+                                    line: range.start.0 as UInteger,
+                                    character: range.start.1 as UInteger,
+                                },
+                                end: Position {
+                                    line: range.end.0 as UInteger,
+                                    character: range.end.1 as UInteger,
+                                },
+                            },
+                            text,
+                        },
+                    )
+                })
+                .collect(),
+        };
 
         self.editor.async_actor(move |editor| async move {
-            let client = {
-                let mut editor = editor.lock().unwrap();
+            let clients = editor.lock().unwrap().lsp_clients(&language_id);
+
+            for (_, client) in clients {
+                notify::<TextDocumentDidChange>(&client, params.clone()).await;
+            }
+        });
 
-                editor.lsps.rust()
+        self
+    }
+
+    pub fn close_document(self, document: &Document) -> Self {
+        let language_id = language_id(document.path());
+        let uri = uri(document.path());
+
+        self.editor.async_actor(move |editor| async move {
+            let clients = editor.lock().unwrap().lsp_clients(&language_id);
+
+            for (_, client) in clients {
+                let params = DidCloseTextDocumentParams {
+                    text_document: TextDocumentIdentifier { uri: uri.clone() },
+                };
+                notify::<TextDocumentDidClose>(&client, params).await;
+            }
+        });
+
+        self
+    }
+
+    /// Finds references to the symbol under the active document's head.
+    ///
+    /// Sends [`EventLoopMessage::References`] with the references and their line previews.
+    pub fn references(self) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let position = Position {
+            line: document.head_line() as UInteger,
+            character: document.head_column() as UInteger,
+        };
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
             };
 
-            let mut client = client.lock().await;
-            client.initied().await;
-            client
-                .notification()
-                .text_document_did_change(DidChangeTextDocumentParams {
-                    text_document: VersionedTextDocumentIdentifier {
-                        text_document_identifier: TextDocumentIdentifier { uri },
-                        version,
-                    },
-                    content_changes: changes
-                        .into_iter()
-                        .map(|(range, text)| {
-                            TextDocumentContentChangeEvent::RangeAndText(
-                                TextDocumentContentChangeEventRangeAndText {
-                                    range: LspRange {
-                                        start: Position {
-                                            line: range.start.0 as UInteger,
-                                            character: range.start.1 as UInteger,
-                                        },
-                                        end: Position {
-                                            line: range.end.0 as UInteger,
-                                            character: range.end.1 as UInteger,
-                                        },
-                                    },
-                                    text,
-                                },
-                            )
-                        })
-                        .collect(),
+            let params = ReferenceParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    position,
+                },
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                partial_result_params: PartialResultParams {
+                    partial_result_token: None,
+                },
+                context: ReferenceContext {
+                    include_declaration: true,
+                },
+            };
+            let locations = match request::<TextDocumentReferences>(&client, params).await {
+                Some(TextDocumentReferencesResult::LocationList(locations)) => locations,
+                Some(TextDocumentReferencesResult::Null(_)) => Vec::new(),
+                None => return,
+            };
+
+            if !is_current(&editor.lock().unwrap(), &path, version) {
+                return;
+            }
+
+            let editor = editor.lock().unwrap();
+            let references = editor.previews(locations.into_iter().map(Location::from));
+
+            editor.event_loop(EventLoopMessage::References(references));
+        });
+
+        self
+    }
+
+    /// Requests code actions for the active document's selection, with its diagnostics.
+    ///
+    /// Sends [`EventLoopMessage::CodeActions`] with the enabled actions.
+    pub fn code_actions(self) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let range = {
+            let selection = document.selection().range();
+            let rope = document.rope();
+
+            LspRange {
+                start: Position {
+                    line: selection.start.line(rope) as UInteger,
+                    character: selection.start.column(rope) as UInteger,
+                },
+                end: Position {
+                    line: selection.end.line(rope) as UInteger,
+                    character: selection.end.column(rope) as UInteger,
+                },
+            }
+        };
+        let diagnostics = self
+            .editor
+            .diagnostics(document.path())
+            .filter(|diagnostic| {
+                let (start, end) = (&diagnostic.range.start, &diagnostic.range.end);
+
+                (start.line, start.character) <= (range.end.line, range.end.character)
+                    && (range.start.line, range.start.character) <= (end.line, end.character)
+            })
+            .cloned()
+            .collect();
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((server, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let params = CodeActionParams {
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                partial_result_params: PartialResultParams {
+                    partial_result_token: None,
+                },
+                text_document: TextDocumentIdentifier { uri },
+                range,
+                context: CodeActionContext {
+                    diagnostics,
+                    only: None,
+                    trigger_kind: Some(CodeActionTriggerKind::Invoked),
+                },
+            };
+            let actions = match request::<TextDocumentCodeAction>(&client, params).await {
+                Some(TextDocumentCodeActionResult::CommandOrCodeActionList(actions)) => actions,
+                Some(TextDocumentCodeActionResult::Null(_)) => Vec::new(),
+                None => return,
+            };
+
+            if !is_current(&editor.lock().unwrap(), &path, version) {
+                return;
+            }
+
+            let actions = actions
+                .into_iter()
+                .filter(|action| match action {
+                    TextDocumentCodeActionResultCommandOrCodeAction::Command(_) => true,
+                    TextDocumentCodeActionResultCommandOrCodeAction::CodeAction(action) => {
+                        action.disabled.is_none()
+                    }
                 })
-                .await
-                .unwrap();
+                .map(|action| CodeAction::new(server, action))
+                .collect();
+
+            editor
+                .lock()
+                .unwrap()
+                .event_loop(EventLoopMessage::CodeActions(actions));
         });
 
         self
     }
 
-    pub fn close_document(self, document: &Document) -> Self {
-        let uri = document.path().as_os_str().to_str().unwrap();
-        let uri = format!("file://{uri}");
+    /// Applies `action`'s edit, resolving it first if needed, then runs its command.
+    pub fn apply_code_action(self, action: CodeAction) -> Self {
+        self.editor.async_actor(move |editor| async move {
+            let client = editor.lock().unwrap().lsps.client(action.server);
+
+            let (edit, command) = match action.action {
+                TextDocumentCodeActionResultCommandOrCodeAction::Command(command) => {
+                    (None, Some(command))
+                }
+                TextDocumentCodeActionResultCommandOrCodeAction::CodeAction(action)
+                    if action.edit.is_none() && action.data.is_some() =>
+                {
+                    let Some(action) = request::<CodeActionResolve>(&client, action).await else {
+                        return;
+                    };
+
+                    (action.edit, action.command)
+                }
+                TextDocumentCodeActionResultCommandOrCodeAction::CodeAction(action) => {
+                    (action.edit, action.command)
+                }
+            };
+
+            if let Some(edit) = edit {
+                let mut editor = editor.lock().unwrap();
+
+                if let Err(error) = editor.apply_workspace_edit(edit) {
+                    eprintln!("Cannot apply code action: {}", error.reason);
+                    return;
+                }
+
+                editor.event_loop(EventLoopMessage::Redraw);
+            }
+
+            if let Some(Command {
+                command, arguments, ..
+            }) = command
+            {
+                let params = ExecuteCommandParams {
+                    work_done_progress_params: WorkDoneProgressParams {
+                        work_done_token: None,
+                    },
+                    command,
+                    arguments,
+                };
+                request::<WorkspaceExecuteCommand>(&client, params).await;
+            }
+        });
+
+        self
+    }
+
+    /// Formats the active document, or only its selection if `selection`.
+    ///
+    /// Edits are applied in one batch, unless the document changed in the meantime.
+    pub fn format(self, selection: bool) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let range = selection.then(|| {
+            let selection = document.selection().range();
+            let rope = document.rope();
+
+            LspRange {
+                start: Position {
+                    line: selection.start.line(rope) as UInteger,
+                    character: selection.start.column(rope) as UInteger,
+                },
+                end: Position {
+                    line: selection.end.line(rope) as UInteger,
+                    character: selection.end.column(rope) as UInteger,
+                },
+            }
+        });
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let Some(edits) = format(client, uri(&path), range).await else {
+                return;
+            };
+
+            let mut editor = editor.lock().unwrap();
+            apply_formatting(&mut editor, &path, version, edits);
+            editor.event_loop(EventLoopMessage::Redraw);
+        });
+
+        self
+    }
+
+    /// Formats the active document, then saves it.
+    ///
+    /// The document is saved unformatted if the server does not answer within `timeout`.
+    pub fn format_and_save(self, timeout: Duration) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+
+        self.editor.async_actor(move |editor| async move {
+            let client = editor.lock().unwrap().lsp_client(&language_id);
 
-        self.editor.async_actor(|editor| async move {
-            let client = {
+            // Saves unformatted without a server
+            if let Some((_, client)) = client {
+                let edits = tokio::time::timeout(timeout, format(client, uri(&path), None)).await;
                 let mut editor = editor.lock().unwrap();
 
-                editor.lsps.rust()
+                match edits {
+                    Ok(Some(edits)) => apply_formatting(&mut editor, &path, version, edits),
+                    Ok(None) => {}
+                    Err(_) => eprintln!("Formatting timed out, saving unformatted"),
+                }
+            }
+
+            let mut editor = editor.lock().unwrap();
+
+            if let Some(document) = editor.document_mut(&path) {
+                if let Err(error) = document.save() {
+                    eprintln!("Cannot save {}: {error}", path.display());
+                }
+            }
+
+            editor.event_loop(EventLoopMessage::Redraw);
+        });
+
+        self
+    }
+
+    /// Requests the signature of the call around the active document's head.
+    ///
+    /// Sends [`EventLoopMessage::SignatureHelp`] with the active signature, if any. Cancels the
+    /// previous request.
+    pub fn signature_help(self) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let position = Position {
+            line: document.head_line() as UInteger,
+            character: document.head_column() as UInteger,
+        };
+
+        let superseded = self.editor.supersede("textDocument/signatureHelp", &path);
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
             };
 
-            let mut client = client.lock().await;
-            client.initied().await;
-            client
-                .notification()
-                .text_document_did_close(DidCloseTextDocumentParams {
+            let params = SignatureHelpParams {
+                text_document_position_params: TextDocumentPositionParams {
                     text_document: TextDocumentIdentifier { uri },
+                    position,
+                },
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                context: None,
+            };
+            let help = tokio::select! {
+                help = request::<TextDocumentSignatureHelp>(&client, params) => help,
+                _ = superseded => return,
+            };
+            let help = match help {
+                Some(TextDocumentSignatureHelpResult::SignatureHelp(help)) => help,
+                Some(TextDocumentSignatureHelpResult::Null(_)) | None => return,
+            };
+
+            if !is_current(&editor.lock().unwrap(), &path, version) {
+                return;
+            }
+
+            let active_signature = help.active_signature.unwrap_or_default() as usize;
+            let Some(signature) = help.signatures.into_iter().nth(active_signature) else {
+                return;
+            };
+
+            let mut start = 0;
+            let parameters = signature
+                .parameters
+                .unwrap_or_default()
+                .into_iter()
+                .map(|parameter| match parameter.label {
+                    ParameterInformationLabel::String(label) => {
+                        let range = signature.label[start..]
+                            .find(&label)
+                            .map(|index| start + index..start + index + label.len())
+                            .unwrap_or(start..start);
+                        start = range.end;
+                        range
+                    }
+                    ParameterInformationLabel::UIntegerAndUInteger((from, to)) => {
+                        let byte = |char: UInteger| {
+                            signature
+                                .label
+                                .char_indices()
+                                .nth(char as usize)
+                                .map(|(index, _)| index)
+                                .unwrap_or(signature.label.len())
+                        };
+
+                        byte(from)..byte(to)
+                    }
                 })
+                .collect();
+            let active_parameter = signature
+                .active_parameter
+                .or(help.active_parameter)
+                .unwrap_or_default() as usize;
+
+            editor
+                .lock()
+                .unwrap()
+                .event_loop(EventLoopMessage::SignatureHelp(Signature {
+                    label: signature.label,
+                    parameters,
+                    active_parameter,
+                }));
+        });
+
+        self
+    }
+
+    /// Requests the semantic tokens of `document`, as a delta of the previous ones if supported.
+    ///
+    /// Tokens are set on the document if it did not change meanwhile. Cancels the previous
+    /// request for this document.
+    pub fn semantic_tokens(self, document: &Document) -> Self {
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let previous_result_id = self
+            .editor
+            .lsp_semantic_tokens
+            .get(&path)
+            .and_then(|tokens| tokens.result_id.clone());
+
+        let superseded = self.editor.supersede("textDocument/semanticTokens", &path);
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let Some(options) = initied(&client)
                 .await
-                .unwrap();
+                .and_then(|result| result.capabilities.semantic_tokens_provider)
+                .map(|provider| match provider {
+                    ServerCapabilitiesSemanticTokensProvider::SemanticTokensOptions(options) => {
+                        options
+                    }
+                    ServerCapabilitiesSemanticTokensProvider::SemanticTokensRegistrationOptions(
+                        options,
+                    ) => options.semantic_tokens_options,
+                })
+            else {
+                return;
+            };
+            let supports_delta = matches!(
+                options.full,
+                Some(SemanticTokensOptionsFull::OptionalDelta(
+                    SemanticTokensOptionsFullOptionalDelta { delta: Some(true) }
+                ))
+            );
+            let text_document = TextDocumentIdentifier { uri };
+            let work_done_progress_params = WorkDoneProgressParams {
+                work_done_token: None,
+            };
+            let partial_result_params = PartialResultParams {
+                partial_result_token: None,
+            };
+            let response = async {
+                match previous_result_id.filter(|_| supports_delta) {
+                    Some(previous_result_id) => {
+                        let params = SemanticTokensDeltaParams {
+                            work_done_progress_params,
+                            partial_result_params,
+                            text_document,
+                            previous_result_id,
+                        };
+
+                        request::<TextDocumentSemanticTokensFullDelta>(&client, params).await
+                    }
+                    None => {
+                        let params = SemanticTokensParams {
+                            work_done_progress_params,
+                            partial_result_params,
+                            text_document,
+                        };
+
+                        // As a delta result, which may be full tokens
+                        request::<TextDocumentSemanticTokensFull>(&client, params)
+                            .await
+                            .map(|result| match result {
+                                TextDocumentSemanticTokensFullResult::SemanticTokens(tokens) => {
+                                    TextDocumentSemanticTokensFullDeltaResult::SemanticTokens(
+                                        tokens,
+                                    )
+                                }
+                                TextDocumentSemanticTokensFullResult::Null(null) => {
+                                    TextDocumentSemanticTokensFullDeltaResult::Null(null)
+                                }
+                            })
+                    }
+                }
+            };
+            let result = tokio::select! {
+                result = response => result,
+                _ = superseded => return,
+            };
+            let Some(result) = result else {
+                return;
+            };
+
+            let mut editor = editor.lock().unwrap();
+            let tokens = match result {
+                TextDocumentSemanticTokensFullDeltaResult::SemanticTokens(tokens) => tokens,
+                TextDocumentSemanticTokensFullDeltaResult::SemanticTokensDelta(delta) => {
+                    let Some(mut tokens) = editor.lsp_semantic_tokens.remove(&path) else {
+                        return;
+                    };
+                    let mut edits = delta.edits;
+                    edits.sort_by_key(|edit| edit.start);
+
+                    for edit in edits.into_iter().rev() {
+                        let start = (edit.start as usize).min(tokens.data.len());
+                        let end = (start + edit.delete_count as usize).min(tokens.data.len());
+                        tokens
+                            .data
+                            .splice(start..end, edit.data.unwrap_or_default());
+                    }
+
+                    tokens.result_id = delta.result_id;
+                    tokens
+                }
+                TextDocumentSemanticTokensFullDeltaResult::Null(_) => return,
+            };
+            let semantic_tokens = SemanticToken::decode(
+                &tokens.data,
+                &options.legend.token_types,
+                &options.legend.token_modifiers,
+            );
+            editor.lsp_semantic_tokens.insert(path.clone(), tokens);
+
+            // Tokens of an older version would not match the text
+            if !is_current(&editor, &path, version) {
+                return;
+            }
+
+            if let Some(document) = editor.document_mut(&path) {
+                document.set_semantic_tokens(semantic_tokens);
+                editor.event_loop(EventLoopMessage::Redraw);
+            }
+        });
+
+        self
+    }
+
+    /// Requests the symbols of the active document, or its `tree-sitter` items without a server.
+    ///
+    /// Sends [`EventLoopMessage::Symbols`] with the flattened symbols.
+    pub fn document_symbols(self) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+
+        self.editor.async_actor(move |editor| async move {
+            let client = editor.lock().unwrap().lsp_client(&language_id);
+            let Some((_, client)) = client else {
+                let editor = editor.lock().unwrap();
+
+                if let Some(document) = editor.document(&path) {
+                    editor.event_loop(EventLoopMessage::Symbols(document.items()));
+                }
+
+                return;
+            };
+
+            let params = DocumentSymbolParams {
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                partial_result_params: PartialResultParams {
+                    partial_result_token: None,
+                },
+                text_document: TextDocumentIdentifier { uri },
+            };
+            let symbols = match request::<TextDocumentDocumentSymbol>(&client, params).await {
+                Some(TextDocumentDocumentSymbolResult::DocumentSymbolList(symbols)) => {
+                    Symbol::flatten(&path, symbols, None)
+                }
+                Some(TextDocumentDocumentSymbolResult::SymbolInformationList(symbols)) => {
+                    symbols.into_iter().map(Symbol::from).collect()
+                }
+                Some(TextDocumentDocumentSymbolResult::Null(_)) => Vec::new(),
+                None => return,
+            };
+
+            let editor = editor.lock().unwrap();
+
+            if !is_current(&editor, &path, version) {
+                return;
+            }
+
+            editor.event_loop(EventLoopMessage::Symbols(symbols));
+        });
+
+        self
+    }
+
+    /// Searches the symbols of the workspace matching `query`, with the active document's server.
+    ///
+    /// Sends [`EventLoopMessage::WorkspaceSymbols`]. Cancels the previous search.
+    pub fn workspace_symbols(self, query: String) -> Self {
+        let language_id = language_id(self.editor.active_document().path());
+        let root = self.editor.root().to_owned();
+
+        let superseded = self.editor.supersede("workspace/symbol", &root);
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let params = WorkspaceSymbolParams {
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                partial_result_params: PartialResultParams {
+                    partial_result_token: None,
+                },
+                query,
+            };
+            let result = tokio::select! {
+                result = request::<requests::WorkspaceSymbol>(&client, params) => result,
+                _ = superseded => return,
+            };
+            let symbols = match result {
+                Some(WorkspaceSymbolResult::WorkspaceSymbolList(symbols)) => {
+                    symbols.into_iter().map(Symbol::from).collect()
+                }
+                Some(WorkspaceSymbolResult::SymbolInformationList(symbols)) => {
+                    symbols.into_iter().map(Symbol::from).collect()
+                }
+                Some(WorkspaceSymbolResult::Null(_)) => Vec::new(),
+                None => return,
+            };
+
+            editor
+                .lock()
+                .unwrap()
+                .event_loop(EventLoopMessage::WorkspaceSymbols(symbols));
+        });
+
+        self
+    }
+
+    /// Requests the inlay hints of `document` on `lines`.
+    ///
+    /// Hints are set on the document if it did not change meanwhile. Cancels the previous request
+    /// for this document.
+    pub fn inlay_hints(self, document: &Document, lines: Range<usize>) -> Self {
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let end = document.cursor_at(lines.end, usize::MAX);
+        let range = LspRange {
+            start: Position {
+                line: lines.start as UInteger,
+                character: 0,
+            },
+            end: Position {
+                line: end.line(document.rope()) as UInteger,
+                character: end.column(document.rope()) as UInteger,
+            },
+        };
+
+        let superseded = self.editor.supersede("textDocument/inlayHint", &path);
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let params = InlayHintParams {
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                text_document: TextDocumentIdentifier { uri },
+                range,
+            };
+            let result = tokio::select! {
+                result = request::<TextDocumentInlayHint>(&client, params) => result,
+                _ = superseded => return,
+            };
+            let hints = match result {
+                Some(TextDocumentInlayHintResult::InlayHintList(hints)) => hints,
+                Some(TextDocumentInlayHintResult::Null(_)) => Vec::new(),
+                None => return,
+            };
+
+            let mut editor = editor.lock().unwrap();
+
+            // Hints of an older version would not match the text
+            if !is_current(&editor, &path, version) {
+                return;
+            }
+
+            if let Some(document) = editor.document_mut(&path) {
+                document.set_inlay_hints(hints.into_iter().map(Into::into).collect());
+                editor.event_loop(EventLoopMessage::Redraw);
+            }
+        });
+
+        self
+    }
+
+    /// Checks that the symbol under the active document's head can be renamed.
+    ///
+    /// Sends [`EventLoopMessage::Rename`] with the symbol's name as placeholder if so.
+    pub fn prepare_rename(self) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let path = document.path().to_owned();
+        let version = document.version();
+        let uri = uri(document.path());
+        let position = Position {
+            line: document.head_line() as UInteger,
+            character: document.head_column() as UInteger,
+        };
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let params = PrepareRenameParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    position,
+                },
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+            };
+            let result = match request::<TextDocumentPrepareRename>(&client, params).await {
+                Some(TextDocumentPrepareRenameResult::PrepareRenameResult(result)) => result,
+                Some(TextDocumentPrepareRenameResult::Null(_)) | None => return,
+            };
+
+            if !is_current(&editor.lock().unwrap(), &path, version) {
+                return;
+            }
+
+            let editor = editor.lock().unwrap();
+            let placeholder = match result {
+                PrepareRenameResult::Range(range) => editor
+                    .document(&path)
+                    .map(|document| document.text(self::range(range)))
+                    .unwrap_or_default(),
+                PrepareRenameResult::RangeAndPlaceholder(range) => range.placeholder,
+                PrepareRenameResult::DefaultBehavior(_) => String::new(),
+            };
+
+            editor.event_loop(EventLoopMessage::Rename(placeholder));
+        });
+
+        self
+    }
+
+    /// Renames the symbol under the active document's head to `new_name`.
+    ///
+    /// The resulting workspace edit is applied with [`Editor::apply_workspace_edit`].
+    pub fn rename(self, new_name: String) -> Self {
+        let document = self.editor.active_document();
+        let language_id = language_id(document.path());
+        let uri = uri(document.path());
+        let position = Position {
+            line: document.head_line() as UInteger,
+            character: document.head_column() as UInteger,
+        };
+
+        self.editor.async_actor(move |editor| async move {
+            let Some((_, client)) = editor.lock().unwrap().lsp_client(&language_id) else {
+                return;
+            };
+
+            let params = RenameParams {
+                work_done_progress_params: WorkDoneProgressParams {
+                    work_done_token: None,
+                },
+                text_document: TextDocumentIdentifier { uri },
+                position,
+                new_name,
+            };
+            let edit = match request::<TextDocumentRename>(&client, params).await {
+                Some(TextDocumentRenameResult::WorkspaceEdit(edit)) => edit,
+                Some(TextDocumentRenameResult::Null(_)) | None => return,
+            };
+
+            let mut editor = editor.lock().unwrap();
+
+            if let Err(error) = editor.apply_workspace_edit(edit) {
+                eprintln!("Cannot apply rename: {}", error.reason);
+            }
+
+            editor.event_loop(EventLoopMessage::Redraw);
         });
 
         self
@@ -201,39 +1215,313 @@ impl<'editor> Lsp<'editor> {
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-async fn rust_lsp_handler(_editor: Arc<Mutex<Editor>>, mut receiver: ServerMessageReceiver) {
-    while let Some(message) = receiver.recv().await {
+/// How long to wait for responses to requests.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Characters of a path to percent-encode in a uri.
+const URI_PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+pub(crate) fn uri(path: &Path) -> String {
+    format!(
+        "file://{}",
+        utf8_percent_encode(&path.to_string_lossy(), URI_PATH)
+    )
+}
+
+pub(crate) fn path(uri: &str) -> PathBuf {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+
+    PathBuf::from(percent_decode_str(path).decode_utf8_lossy().into_owned())
+}
+
+pub(crate) fn range(range: LspRange) -> Range<(usize, usize)> {
+    (range.start.line as usize, range.start.character as usize)
+        ..(range.end.line as usize, range.end.character as usize)
+}
+
+/// Returns whether the document at `path` is still at `version`, i.e. a response is not stale.
+fn is_current(editor: &Editor, path: &Path, version: usize) -> bool {
+    editor
+        .document(path)
+        .is_some_and(|document| document.version() == version)
+}
+
+/// Marks `client` as dead after its initialization failed with `error`.
+async fn fail(client: &tokio::sync::Mutex<LspClient>, error: impl Display) {
+    eprintln!("initialize: {error}");
+    client.lock().await.fail();
+}
+
+/// Waits for `client` to be initialized, without holding it meanwhile.
+///
+/// Returns `None` if its initialization failed.
+async fn initied(client: &tokio::sync::Mutex<LspClient>) -> Option<InitializeResult> {
+    let initied = client.lock().await.initied();
+
+    initied.await
+}
+
+/// Sends a `T` notification to `client` once initialized, logging errors.
+async fn notify<T: NotificationTrait>(client: &tokio::sync::Mutex<LspClient>, params: T::Params) {
+    if initied(client).await.is_none() {
+        return;
+    }
+
+    if let Err(error) = client.lock().await.send_notification::<T>(params).await {
+        eprintln!("{}: {error}", T::METHOD);
+    }
+}
+
+/// Sends a `T` request to `client` once initialized, and waits for its response.
+///
+/// `client` is only held while sending: the server may send requests needing it before answering,
+/// e.g. `workspace/applyEdit`. Returns `None` on errors (logged) or after [`TIMEOUT`]. Dropping
+/// the future cancels the request.
+async fn request<T: RequestTrait>(
+    client: &tokio::sync::Mutex<LspClient>,
+    params: T::Params,
+) -> Option<T::Result> {
+    initied(client).await?;
+    let response = client.lock().await.send_request::<T>(params).await;
+    let result = match response {
+        Ok(response) => response.timeout(TIMEOUT).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(Ok(result)) => Some(result),
+        Ok(Err(error)) => {
+            eprintln!("{}: {}", T::METHOD, error.message);
+            None
+        }
+        Err(error) => {
+            eprintln!("{}: {error}", T::METHOD);
+            None
+        }
+    }
+}
+
+/// Requests formatting edits for the whole document at `uri`, or for its `range` only.
+async fn format(
+    client: Arc<tokio::sync::Mutex<LspClient>>,
+    uri: String,
+    range: Option<LspRange>,
+) -> Option<Vec<TextEdit>> {
+    const OPTIONS: FormattingOptions = FormattingOptions {
+        tab_size: 4,
+        insert_spaces: true,
+        trim_trailing_whitespace: None,
+        insert_final_newline: None,
+        trim_final_newlines: None,
+    };
+
+    let text_document = TextDocumentIdentifier { uri };
+    let work_done_progress_params = WorkDoneProgressParams {
+        work_done_token: None,
+    };
+
+    if let Some(range) = range {
+        let params = DocumentRangeFormattingParams {
+            work_done_progress_params,
+            text_document,
+            range,
+            options: OPTIONS,
+        };
+
+        request::<TextDocumentRangeFormatting>(&client, params)
+            .await
+            .map(|result| match result {
+                TextDocumentRangeFormattingResult::TextEditList(edits) => edits,
+                TextDocumentRangeFormattingResult::Null(_) => Vec::new(),
+            })
+    } else {
+        let params = DocumentFormattingParams {
+            work_done_progress_params,
+            text_document,
+            options: OPTIONS,
+        };
+
+        request::<TextDocumentFormatting>(&client, params)
+            .await
+            .map(|result| match result {
+                TextDocumentFormattingResult::TextEditList(edits) => edits,
+                TextDocumentFormattingResult::Null(_) => Vec::new(),
+            })
+    }
+}
+
+/// Applies formatting `edits` to the document at `path`, if still at `version`.
+fn apply_formatting(editor: &mut Editor, path: &Path, version: usize, edits: Vec<TextEdit>) {
+    if let Some(document) = editor.document_mut(path) {
+        if document.version() == version {
+            document.apply_edits(
+                edits
+                    .into_iter()
+                    .map(|edit| (range(edit.range), edit.new_text)),
+            );
+            document.parse();
+        }
+    }
+
+    editor.flush_changes();
+}
+
+async fn lsp_handler(editor: Arc<Mutex<Editor>>, mut receiver: LspServerMessageReceiver) {
+    while let Some((server, message)) = receiver.recv().await {
         match message {
             ServerMessage::ServerNotification(notification) => match notification {
                 ServerNotification::CancelRequest(_) => {}
                 ServerNotification::LogTrace(trace) => {
-                    dbg!(trace);
+                    eprintln!(
+                        "{}: {}",
+                        editor.lock().unwrap().lsps.name(server),
+                        trace.message
+                    );
+                }
+                // Already applied to the client's progress
+                ServerNotification::Progress(_) => {
+                    editor.lock().unwrap().event_loop(EventLoopMessage::Redraw);
                 }
-                ServerNotification::Progress(_) => {}
                 ServerNotification::TelemetryEvent(_) => {}
-                ServerNotification::TextDocumentPublishDiagnostics(_) => {}
+                ServerNotification::TextDocumentPublishDiagnostics(params) => {
+                    let mut editor = editor.lock().unwrap();
+                    editor.set_diagnostics(path(&params.uri), server, params.diagnostics);
+                }
                 ServerNotification::WindowLogMessage(_) => {}
                 ServerNotification::WindowShowMessage(_) => {}
             },
-            ServerMessage::ServerRequest(request) => match request {
-                ServerRequest::ClientRegisterCapability(_, _) => {}
-                ServerRequest::ClientUnregisterCapability(_, _) => {}
-                ServerRequest::WindowShowDocument(_, _) => {}
-                ServerRequest::WindowShowMessageRequest(_, _) => {}
-                ServerRequest::WindowWorkDoneProgressCreate(_, _) => {}
-                ServerRequest::WorkspaceApplyEdit(_, _) => {}
-                ServerRequest::WorkspaceCodeLensRefresh(_) => {}
-                ServerRequest::WorkspaceConfiguration(_, _) => {}
-                ServerRequest::WorkspaceDiagnosticRefresh(_) => {}
-                ServerRequest::WorkspaceInlayHintRefresh(_) => {}
-                ServerRequest::WorkspaceInlineValueRefresh(_) => {}
-                ServerRequest::WorkspaceSemanticTokensRefresh(_) => {}
-                ServerRequest::WorkspaceWorkspaceFolders(_) => {}
-            },
+            ServerMessage::ServerRequest(request) => {
+                // Answered without the client, which may be locked until the answer
+                let mut responder = editor.lock().unwrap().lsps.responder(server);
+                let mut response = responder.response();
+
+                let sent = match request {
+                    ServerRequest::ClientRegisterCapability(_, _) => Ok(()),
+                    ServerRequest::ClientUnregisterCapability(_, _) => Ok(()),
+                    ServerRequest::WindowShowDocument(_, _) => Ok(()),
+                    ServerRequest::WindowShowMessageRequest(_, _) => Ok(()),
+                    ServerRequest::WindowWorkDoneProgressCreate(id, _) => {
+                        response
+                            .window_work_done_progress_create(Some(id), Ok(Null))
+                            .await
+                    }
+                    ServerRequest::WorkspaceApplyEdit(id, params) => {
+                        let result = {
+                            let mut editor = editor.lock().unwrap();
+                            let result = editor.apply_workspace_edit(params.edit);
+                            editor.event_loop(EventLoopMessage::Redraw);
+                            result
+                        };
+
+                        response
+                            .workspace_apply_edit(
+                                Some(id),
+                                Ok(match result {
+                                    Ok(()) => ApplyWorkspaceEditResult {
+                                        applied: true,
+                                        failure_reason: None,
+                                        failed_change: None,
+                                    },
+                                    Err(error) => ApplyWorkspaceEditResult {
+                                        applied: false,
+                                        failure_reason: Some(error.reason),
+                                        failed_change: Some(error.index as UInteger),
+                                    },
+                                }),
+                            )
+                            .await
+                    }
+                    ServerRequest::WorkspaceCodeLensRefresh(_) => Ok(()),
+                    ServerRequest::WorkspaceConfiguration(_, _) => Ok(()),
+                    ServerRequest::WorkspaceDiagnosticRefresh(_) => Ok(()),
+                    ServerRequest::WorkspaceInlayHintRefresh(id) => {
+                        editor.lock().unwrap().refresh_inlay_hints();
+                        response
+                            .workspace_inlay_hint_refresh(Some(id), Ok(Null))
+                            .await
+                    }
+                    ServerRequest::WorkspaceInlineValueRefresh(_) => Ok(()),
+                    ServerRequest::WorkspaceSemanticTokensRefresh(id) => {
+                        editor.lock().unwrap().refresh_semantic_tokens();
+                        response
+                            .workspace_semantic_tokens_refresh(Some(id), Ok(Null))
+                            .await
+                    }
+                    ServerRequest::WorkspaceWorkspaceFolders(_) => Ok(()),
+                };
+
+                if let Err(error) = sent {
+                    eprintln!("{}: {error}", editor.lock().unwrap().lsps.name(server));
+                }
+            }
+            // Unknown requests are already answered with `MethodNotFound`
+            ServerMessage::UnknownNotification { .. } | ServerMessage::UnknownRequest { .. } => {}
+            ServerMessage::Error(ProtocolError::Transport(error)) => {
+                let mut editor = editor.lock().unwrap();
+                eprintln!("{}: {error}", editor.lsps.name(server));
+
+                editor.set_lsp_progress(server, None);
+                // Result IDs are unknown to the next server
+                editor.lsp_semantic_tokens.clear();
+                editor.event_loop(EventLoopMessage::Redraw);
+
+                if let Some(delay) = editor.lsps.exited(server) {
+                    editor.async_actor(move |editor| async move {
+                        tokio::time::sleep(delay).await;
+
+                        let mut editor = editor.lock().unwrap();
+                        editor.lsps.restart(server);
+                        editor.lsp().initialize(server, true);
+                        editor.refresh_semantic_tokens();
+                        editor.refresh_inlay_hints();
+                    });
+                }
+            }
+            ServerMessage::Error(error) => {
+                eprintln!("{}: {error:?}", editor.lock().unwrap().lsps.name(server));
+            }
         }
     }
 }
 
+/// Returns the language ID of the file at `path`, from its extension.
+pub(crate) fn language_id(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    match extension {
+        "rs" => "rust",
+        extension => extension,
+    }
+    .to_owned()
+}
+
+fn text_document_item(document: &Document) -> TextDocumentItem {
+    TextDocumentItem {
+        uri: uri(document.path()),
+        language_id: language_id(document.path()),
+        version: document.version() as Integer,
+        text: document.rope().to_string(),
+    }
+}
+
 fn initialize_params(
     process_id: Integer,
     work_done_token: String,
@@ -248,8 +1536,155 @@ fn initialize_params(
         client_info: None,
         locale: None,
         capabilities: ClientCapabilities {
-            workspace: None,
-            text_document: None,
+            workspace: Some(WorkspaceClientCapabilities {
+                apply_edit: Some(true),
+                workspace_edit: Some(WorkspaceEditClientCapabilities {
+                    document_changes: Some(true),
+                    resource_operations: Some(vec![
+                        ResourceOperationKind::Create,
+                        ResourceOperationKind::Rename,
+                        ResourceOperationKind::Delete,
+                    ]),
+                    failure_handling: Some(FailureHandlingKind::Abort),
+                    normalizes_line_endings: None,
+                    change_annotation_support: None,
+                }),
+                did_change_configuration: None,
+                did_change_watched_files: None,
+                symbol: Some(WorkspaceSymbolClientCapabilities {
+                    dynamic_registration: None,
+                    symbol_kind: None,
+                    tag_support: None,
+                    resolve_support: None,
+                }),
+                execute_command: None,
+                workspace_folders: None,
+                configuration: None,
+                semantic_tokens: Some(SemanticTokensWorkspaceClientCapabilities {
+                    refresh_support: Some(true),
+                }),
+                code_lens: None,
+                file_operations: None,
+                inline_value: None,
+                inlay_hint: Some(InlayHintWorkspaceClientCapabilities {
+                    refresh_support: Some(true),
+                }),
+                diagnostics: None,
+            }),
+            text_document: Some(TextDocumentClientCapabilities {
+                synchronization: None,
+                completion: None,
+                hover: None,
+                signature_help: None,
+                declaration: None,
+                definition: None,
+                type_definition: None,
+                implementation: None,
+                references: None,
+                document_highlight: None,
+                document_symbol: Some(DocumentSymbolClientCapabilities {
+                    dynamic_registration: None,
+                    symbol_kind: None,
+                    hierarchical_document_symbol_support: Some(true),
+                    tag_support: None,
+                    label_support: None,
+                }),
+                code_action: Some(CodeActionClientCapabilities {
+                    dynamic_registration: None,
+                    code_action_literal_support: Some(
+                        CodeActionClientCapabilitiesCodeActionLiteralSupport {
+                            code_action_kind:
+                                CodeActionClientCapabilitiesCodeActionLiteralSupportCodeActionKind {
+                                    value_set: vec![
+                                        CodeActionKind::Empty,
+                                        CodeActionKind::QuickFix,
+                                        CodeActionKind::Refactor,
+                                        CodeActionKind::RefactorExtract,
+                                        CodeActionKind::RefactorInline,
+                                        CodeActionKind::RefactorRewrite,
+                                        CodeActionKind::Source,
+                                        CodeActionKind::SourceOrganizeImports,
+                                        CodeActionKind::SourceFixAll,
+                                    ],
+                                },
+                        },
+                    ),
+                    is_preferred_support: None,
+                    disabled_support: Some(true),
+                    data_support: Some(true),
+                    resolve_support: Some(CodeActionClientCapabilitiesResolveSupport {
+                        properties: vec![String::from("edit")],
+                    }),
+                    honors_change_annotations: None,
+                }),
+                code_lens: None,
+                document_link: None,
+                color_provider: None,
+                formatting: None,
+                range_formatting: None,
+                on_type_formatting: None,
+                rename: Some(RenameClientCapabilities {
+                    dynamic_registration: None,
+                    prepare_support: Some(true),
+                    prepare_support_default_behavior: None,
+                    honors_change_annotations: None,
+                }),
+                folding_range: None,
+                selection_range: None,
+                publish_diagnostics: None,
+                call_hierarchy: None,
+                semantic_tokens: Some(SemanticTokensClientCapabilities {
+                    dynamic_registration: None,
+                    requests: SemanticTokensClientCapabilitiesRequests {
+                        range: None,
+                        full: Some(SemanticTokensClientCapabilitiesRequestsFull::OptionalDelta(
+                            SemanticTokensClientCapabilitiesRequestsFullOptionalDelta {
+                                delta: Some(true),
+                            },
+                        )),
+                    },
+                    // Those with a theme key
+                    token_types: [
+                        "namespace",
+                        "type",
+                        "class",
+                        "enum",
+                        "interface",
+                        "struct",
+                        "typeParameter",
+                        "parameter",
+                        "variable",
+                        "property",
+                        "enumMember",
+                        "function",
+                        "method",
+                        "macro",
+                        "keyword",
+                        "comment",
+                        "string",
+                        "number",
+                        "operator",
+                        "decorator",
+                    ]
+                    .map(String::from)
+                    .to_vec(),
+                    token_modifiers: [String::from("static")].to_vec(),
+                    formats: vec![TokenFormat::Relative],
+                    overlapping_token_support: Some(false),
+                    multiline_token_support: Some(false),
+                    server_cancel_support: None,
+                    augments_syntax_tokens: Some(true),
+                }),
+                linked_editing_range: None,
+                moniker: None,
+                type_hierarchy: None,
+                inline_value: None,
+                inlay_hint: Some(InlayHintClientCapabilities {
+                    dynamic_registration: None,
+                    resolve_support: None,
+                }),
+                diagnostic: None,
+            }),
             notebook_document: None,
             window: Some(WindowClientCapabilities {
                 work_done_progress: Some(true),
@@ -271,3 +1706,270 @@ fn initialize_params(
         ])),
     }
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_actor::AsyncActor;
+    use tokio::sync::mpsc::unbounded_channel;
+    use virus_lsp::{
+        structures::WorkDoneProgressCreateParams, ClientNotification, ClientRequest, Code, Error,
+        LspServerConfig, MockServer,
+    };
+
+    /// A temporary directory, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Returns an editor with a mock rust server, and a `main.rs` file in a new `name` directory.
+    fn editor(name: &str) -> (Arc<Mutex<Editor>>, MockServer, PathBuf, TempDir) {
+        let root = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let file = root.join("main.rs");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let (server, stream) = MockServer::new();
+        let (async_actor_sender, async_actor_receiver) = unbounded_channel();
+        let editor = Arc::new(Mutex::new(Editor::new(
+            root.clone(),
+            async_actor_sender,
+            Box::new(|_| {}),
+        )));
+        editor.lock().unwrap().add_language_server(
+            "rust",
+            LspServerConfig {
+                name: String::from("mock"),
+                transport: stream.into(),
+                initialization_options: None,
+                root_markers: Vec::new(),
+            },
+        );
+        tokio::spawn(AsyncActor::new(editor.clone(), async_actor_receiver).run());
+
+        (editor, server, file, TempDir(root))
+    }
+
+    #[tokio::test]
+    async fn syncs_documents() {
+        let (editor, mut server, file, _root) = editor("virus-lsp-syncs-documents");
+
+        // Started by the first rust document
+        editor.lock().unwrap().open(file.clone()).unwrap();
+        let params = server
+            .initialize(serde_json::json!({ "positionEncoding": "utf-8" }))
+            .await;
+        assert_eq!(
+            params.process_id,
+            InitializeParamsProcessId::Integer(std::process::id() as Integer)
+        );
+
+        let ClientNotification::TextDocumentDidOpen(params) =
+            server.text_document_notification().await
+        else {
+            panic!("Expected didOpen");
+        };
+        assert_eq!(params.text_document.uri, uri(&file));
+        assert_eq!(params.text_document.text, "fn main() {}\n");
+
+        {
+            let mut editor = editor.lock().unwrap();
+            editor.active_document_mut().edit(&"// ".into());
+            editor.flush_changes();
+        }
+        let ClientNotification::TextDocumentDidChange(params) =
+            server.text_document_notification().await
+        else {
+            panic!("Expected didChange");
+        };
+        assert_eq!(params.text_document.version, 1);
+        assert_eq!(
+            params.content_changes,
+            [TextDocumentContentChangeEvent::RangeAndText(
+                TextDocumentContentChangeEventRangeAndText {
+                    range: LspRange {
+                        start: Position {
+                            line: 0,
+                            character: 0
+                        },
+                        end: Position {
+                            line: 0,
+                            character: 0
+                        },
+                    },
+                    text: String::from("// "),
+                }
+            )]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn opens_before_initialized() {
+        let (editor, mut server, file, _root) = editor("virus-lsp-opens-before-initialized");
+
+        // Started, but initialized only after `didOpen` waits for it: until the server answers
+        // the initialize request
+        let rust = {
+            let mut editor = editor.lock().unwrap();
+            let rust = editor.lsps.servers("rust")[0];
+            editor.start_lsp(rust, &file);
+            editor.open(file.clone()).unwrap();
+            rust
+        };
+        editor.lock().unwrap().lsp().initialize(rust, false);
+
+        let notification = tokio::time::timeout(Duration::from_secs(5), async {
+            server
+                .initialize(serde_json::json!({ "positionEncoding": "utf-8" }))
+                .await;
+            server.text_document_notification().await
+        })
+        .await
+        .expect("Deadlock");
+        let ClientNotification::TextDocumentDidOpen(params) = notification else {
+            panic!("Expected didOpen");
+        };
+        assert_eq!(params.text_document.uri, uri(&file));
+    }
+
+    #[tokio::test]
+    async fn fails_initialization() {
+        let (editor, mut server, file, _root) = editor("virus-lsp-fails-initialization");
+
+        editor.lock().unwrap().open(file).unwrap();
+        let ClientRequest::Initialize(id, _) = server.request().await else {
+            panic!("Expected an initialize request");
+        };
+        server
+            .server()
+            .response()
+            .initialize(
+                Some(id),
+                Err(Error::new(Code::InternalError, "Oops".into(), None)),
+            )
+            .await
+            .unwrap();
+
+        // Marked as dead rather than waited for
+        let client = editor.lock().unwrap().lsp_client("rust").unwrap().1;
+        let initied = tokio::time::timeout(Duration::from_secs(5), initied(&client));
+        assert_eq!(initied.await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn refuses_utf16_servers() {
+        let (editor, mut server, file, _root) = editor("virus-lsp-refuses-utf16-servers");
+
+        editor.lock().unwrap().open(file).unwrap();
+        let ClientRequest::Initialize(id, _) = server.request().await else {
+            panic!("Expected an initialize request");
+        };
+        server
+            .server()
+            .response()
+            .initialize(
+                Some(id),
+                Ok(serde_json::from_value(serde_json::json!({ "capabilities": {} })).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        // Defaults to UTF-16
+        let client = editor.lock().unwrap().lsp_client("rust").unwrap().1;
+        let initied = tokio::time::timeout(Duration::from_secs(5), initied(&client));
+        assert_eq!(initied.await.unwrap(), None);
+    }
+
+    #[test]
+    fn encodes_uris() {
+        let path = Path::new("/tmp/a b/#1%/é.rs");
+
+        assert_eq!(uri(path), "file:///tmp/a%20b/%231%25/%C3%A9.rs");
+        assert_eq!(super::path(&uri(path)), path);
+        assert_eq!(uri(Path::new("/")), "file:///");
+    }
+
+    #[tokio::test]
+    async fn answers_requests_while_locked() {
+        let (editor, mut server, file, _root) = editor("virus-lsp-answers-requests-while-locked");
+
+        editor.lock().unwrap().open(file).unwrap();
+        server
+            .initialize(serde_json::json!({ "positionEncoding": "utf-8" }))
+            .await;
+
+        let client = editor.lock().unwrap().lsp_client("rust").unwrap().1;
+        let _client = client.lock().await;
+        let response = server
+            .server()
+            .request()
+            .window_work_done_progress_create(WorkDoneProgressCreateParams {
+                token: ProgressToken::Integer(0),
+            })
+            .await
+            .unwrap();
+        let response = tokio::time::timeout(Duration::from_secs(5), response);
+        assert_eq!(response.await.unwrap().unwrap(), Ok(Null));
+    }
+
+    #[tokio::test]
+    async fn applies_file_operations() {
+        let (editor, _server, main, root) = editor("virus-lsp-applies-file-operations");
+        let lib = root.0.join("lib.rs");
+        std::fs::write(&lib, "").unwrap();
+
+        let mut editor = editor.lock().unwrap();
+        editor.open(main.clone()).unwrap();
+        editor.open(lib.clone()).unwrap();
+        let diagnostic = |message| {
+            serde_json::from_value(serde_json::json!({
+                "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } },
+                "message": message,
+            }))
+            .unwrap()
+        };
+        editor.set_diagnostics(main.clone(), 0, vec![diagnostic("main")]);
+        editor.set_diagnostics(lib.clone(), 0, vec![diagnostic("lib")]);
+        let edit = |changes: serde_json::Value| {
+            serde_json::from_value(serde_json::json!({ "documentChanges": changes })).unwrap()
+        };
+
+        // Overwrites the opened `lib.rs`, moving the state of `main.rs`
+        editor
+            .apply_workspace_edit(edit(serde_json::json!([{
+                "kind": "rename",
+                "oldUri": uri(&main),
+                "newUri": uri(&lib),
+                "options": { "overwrite": true },
+            }])))
+            .unwrap();
+        assert_eq!(editor.documents().count(), 1);
+        assert_eq!(editor.active_document().path(), lib);
+        assert_eq!(
+            editor.active_document().rope().to_string(),
+            "fn main() {}\n"
+        );
+        assert_eq!(editor.diagnostics(&lib).next().unwrap().message, "main");
+        assert_eq!(editor.diagnostics(&main).count(), 0);
+
+        // Replaces the last document
+        editor
+            .apply_workspace_edit(edit(serde_json::json!([{
+                "kind": "delete",
+                "uri": uri(&lib),
+            }])))
+            .unwrap();
+        assert_eq!(editor.documents().count(), 1);
+        assert_eq!(editor.active_document().path(), Path::new(""));
+        assert_eq!(editor.diagnostics(&lib).count(), 0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::syntax::ThemeKey;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         SemanticToken                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A language server's semantic token, highlighted over `tree-sitter` [`Capture`]s.
+///
+/// [`Capture`]: crate::syntax::Capture
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SemanticToken {
+    pub line: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+    pub key: ThemeKey,
+}
+
+impl SemanticToken {
+    /// Decodes the relative encoding of `textDocument/semanticTokens`, with the `token_types` and
+    /// `token_modifiers` of the server's legend.
+    ///
+    /// Tokens without a [`ThemeKey`] are skipped.
+    pub fn decode(data: &[u32], token_types: &[String], token_modifiers: &[String]) -> Vec<Self> {
+        let mut line = 0;
+        let mut start = 0;
+
+        data.chunks_exact(5)
+            .filter_map(|token| {
+                let &[delta_line, delta_start, length, token_type, modifiers] = token else {
+                    unreachable!("Chunks of 5");
+                };
+
+                if delta_line != 0 {
+                    line += delta_line as usize;
+                    start = 0;
+                }
+
+                start += delta_start as usize;
+
+                let modifiers = token_modifiers
+                    .iter()
+                    .take(u32::BITS as usize)
+                    .enumerate()
+                    .filter(|(i, _)| modifiers & (1 << i) != 0)
+                    .map(|(_, modifier)| modifier.as_str());
+                let key = ThemeKey::semantic(token_types.get(token_type as usize)?, modifiers)?;
+
+                Some(Self {
+                    line,
+                    start_column: start,
+                    end_column: start + length as usize,
+                    key,
+                })
+            })
+            .collect()
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let token_types = ["variable", "interface", "unknown"].map(String::from);
+        let token_modifiers = ["declaration", "static"].map(String::from);
+        #[rustfmt::skip]
+        let data = [
+            // `x` at 1:4
+            1, 4, 1, 0, 0b01,
+            // `X` at 1:8, static
+            0, 4, 1, 0, 0b10,
+            // Unknown at 1:10, skipped
+            0, 2, 3, 2, 0,
+            // `Trait` at 3:2
+            2, 2, 5, 1, 0,
+        ];
+        let token = |line, start_column, end_column, key| SemanticToken {
+            line,
+            start_column,
+            end_column,
+            key,
+        };
+
+        assert_eq!(
+            SemanticToken::decode(&data, &token_types, &token_modifiers),
+            [
+                token(1, 4, 5, ThemeKey::Variable),
+                token(1, 8, 9, ThemeKey::Constant),
+                token(3, 2, 7, ThemeKey::TypeTrait),
+            ]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use std::ops::Index;
-use virus_graphics::{text::Styles, types::Rgba};
+use virus_graphics::{
+    text::{FontFeatures, Styles},
+    types::Rgba,
+};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                            ThemeKey                                            //
@@ -46,6 +49,7 @@ pub enum ThemeKey {
     TypeBuiltin,
     TypeEnumVariant,
     TypeParameter,
+    TypeTrait,
     Variable,
     VariableBuiltin,
     VariableOtherMember,
@@ -92,6 +96,7 @@ impl ThemeKey {
             "type.builtin" => Self::TypeBuiltin,
             "type.enum.variant" => Self::TypeEnumVariant,
             "type.parameter" => Self::TypeParameter,
+            "type.trait" => Self::TypeTrait,
             "variable" => Self::Variable,
             "variable.builtin" => Self::VariableBuiltin,
             "variable.other.member" => Self::VariableOtherMember,
@@ -99,6 +104,43 @@ impl ThemeKey {
             _ => Self::Default,
         }
     }
+
+    /// Returns the key of a semantic token of `token_type` with `modifiers`, if any.
+    pub fn semantic<'a>(
+        token_type: &str,
+        mut modifiers: impl Iterator<Item = &'a str>,
+    ) -> Option<Self> {
+        Some(match token_type {
+            "namespace" => Self::Namespace,
+            "type" | "class" | "enum" | "struct" | "typeAlias" | "union" => Self::Type,
+            "builtinType" => Self::TypeBuiltin,
+            "interface" => Self::TypeTrait,
+            "typeParameter" => Self::TypeParameter,
+            "parameter" => Self::VariableParameter,
+            "variable" if modifiers.any(|modifier| matches!(modifier, "static" | "constant")) => {
+                Self::Constant
+            }
+            "variable" => Self::Variable,
+            "const" | "static" => Self::Constant,
+            "property" => Self::VariableOtherMember,
+            "enumMember" => Self::TypeEnumVariant,
+            "function" => Self::Function,
+            "method" => Self::FunctionMethod,
+            "macro" => Self::FunctionMacro,
+            "keyword" => Self::Keyword,
+            "selfKeyword" | "selfTypeKeyword" => Self::VariableBuiltin,
+            "comment" => Self::Comment,
+            "string" => Self::String,
+            "number" => Self::ConstantNumericInteger,
+            "boolean" => Self::ConstantBuiltinBoolean,
+            "character" => Self::ConstantCharacter,
+            "escapeSequence" => Self::ConstantCharacterEscape,
+            "operator" => Self::Operator,
+            "decorator" | "attribute" => Self::Attribute,
+            "label" | "lifetime" => Self::Label,
+            _ => return None,
+        })
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -145,10 +187,13 @@ pub struct Theme {
     pub type_builtin: Styles,
     pub type_enum_variant: Styles,
     pub type_parameter: Styles,
+    pub type_trait: Styles,
     pub variable: Styles,
     pub variable_builtin: Styles,
     pub variable_other_member: Styles,
     pub variable_parameter: Styles,
+    /// Not a capture: inlay hints, between the text.
+    pub inlay_hint: Styles,
 }
 
 impl Theme {
@@ -167,6 +212,7 @@ impl Theme {
                 strike: Default::default(),
                 foreground,
                 background: Default::default(),
+                features: Default::default(),
             }
         }
 
@@ -206,15 +252,21 @@ impl Theme {
             punctuation_bracket: style(catppuccin.overlay2, Black, Normal),
             punctuation_delimiter: style(catppuccin.sky, Black, Normal),
             special: style(catppuccin.blue, Black, Normal),
-            string: style(catppuccin.green, Black, Normal),
+            // Strings as typed, without ligatures
+            string: Styles {
+                features: FontFeatures::new(&[("calt", 0), ("dlig", 0)]),
+                ..style(catppuccin.green, Black, Normal)
+            },
             r#type: style(catppuccin.yellow, Black, Normal),
             type_builtin: style(catppuccin.yellow, Black, Normal),
             type_enum_variant: style(catppuccin.teal, Black, Normal),
             type_parameter: style(catppuccin.yellow, Black, Normal),
+            type_trait: style(catppuccin.yellow, Black, Italic),
             variable: style(catppuccin.text, Black, Normal),
             variable_builtin: style(catppuccin.red, Black, Normal),
             variable_other_member: style(catppuccin.teal, Black, Normal),
             variable_parameter: style(catppuccin.maroon, Black, Normal),
+            inlay_hint: style(catppuccin.overlay1, Regular, Italic),
         }
     }
 }
@@ -262,6 +314,7 @@ impl Index<ThemeKey> for Theme {
             ThemeKey::TypeBuiltin => &self.type_builtin,
             ThemeKey::TypeEnumVariant => &self.type_enum_variant,
             ThemeKey::TypeParameter => &self.type_parameter,
+            ThemeKey::TypeTrait => &self.type_trait,
             ThemeKey::Variable => &self.variable,
             ThemeKey::VariableBuiltin => &self.variable_builtin,
             ThemeKey::VariableOtherMember => &self.variable_other_member,
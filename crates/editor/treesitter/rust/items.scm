@@ -0,0 +1,39 @@
+; Adapted from tree-sitter-rust's tags.scm, keeping definitions only.
+; Items of the outline when no language server is running.
+
+; -------
+; Types
+; -------
+
+(struct_item
+  name: (type_identifier) @name)
+(enum_item
+  name: (type_identifier) @name)
+(union_item
+  name: (type_identifier) @name)
+(type_item
+  name: (type_identifier) @name)
+(trait_item
+  name: (type_identifier) @name)
+
+; -------
+; Functions
+; -------
+
+(function_item
+  name: (identifier) @name)
+(function_signature_item
+  name: (identifier) @name)
+
+; -------
+; Others
+; -------
+
+(const_item
+  name: (identifier) @name)
+(static_item
+  name: (identifier) @name)
+(mod_item
+  name: (identifier) @name)
+(macro_definition
+  name: (identifier) @name)
@@ -0,0 +1,323 @@
+//! Graphics of either backend, with the same `Layer`/`Draw` API as [`crate::wgpu`].
+
+use crate::{
+    cpu,
+    text::{Context, FontSize, GlyphKey, Line, LineHeight},
+    types::{Position, Rectangle, Rgba, Size},
+    wgpu,
+};
+use image::RgbaImage;
+use std::ops::Range;
+use swash::scale::image::Image;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Graphics                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// WebGpu or CPU graphics.
+pub enum Graphics {
+    Wgpu(Box<wgpu::Graphics>),
+    Cpu(cpu::Graphics),
+}
+
+impl From<wgpu::Graphics> for Graphics {
+    fn from(graphics: wgpu::Graphics) -> Self {
+        Self::Wgpu(Box::new(graphics))
+    }
+}
+
+impl From<cpu::Graphics> for Graphics {
+    fn from(graphics: cpu::Graphics) -> Self {
+        Self::Cpu(graphics)
+    }
+}
+
+impl Graphics {
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        match self {
+            Self::Wgpu(graphics) => graphics.size(),
+            Self::Cpu(graphics) => graphics.size(),
+        }
+    }
+
+    /// Resizes to `width` by `height`.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        match self {
+            Self::Wgpu(graphics) => graphics.resize(width, height),
+            Self::Cpu(graphics) => graphics.resize(width, height),
+        }
+    }
+
+    /// Sets the color frames are cleared with.
+    pub fn set_clear_color(&mut self, color: Rgba) {
+        match self {
+            Self::Wgpu(graphics) => graphics.set_clear_color(color),
+            Self::Cpu(graphics) => graphics.set_clear_color(color),
+        }
+    }
+
+    /// Sets the `gamma` and `contrast` of text, see [`wgpu::Graphics::set_text_gamma()`].
+    pub fn set_text_gamma(&mut self, gamma: f32, contrast: f32) {
+        match self {
+            Self::Wgpu(graphics) => graphics.set_text_gamma(gamma, contrast),
+            Self::Cpu(graphics) => graphics.set_text_gamma(gamma, contrast),
+        }
+    }
+
+    /// Returns the `Layer`ing API.
+    pub fn layer(&mut self, region: Rectangle, layer: u16) -> Layer<'_> {
+        match self {
+            Self::Wgpu(graphics) => Layer::Wgpu(graphics.layer(region, layer)),
+            Self::Cpu(graphics) => Layer::Cpu(graphics.layer(region, layer)),
+        }
+    }
+
+    /// Renders the drawn layers.
+    pub fn render(&mut self) {
+        match self {
+            Self::Wgpu(graphics) => graphics.render(),
+            Self::Cpu(graphics) => graphics.render(),
+        }
+    }
+
+    /// Clears the glyph caches, e.g. when font sizes change.
+    pub fn clear_glyphs(&mut self) {
+        match self {
+            Self::Wgpu(graphics) => graphics.clear_glyphs(),
+            Self::Cpu(graphics) => graphics.clear_glyphs(),
+        }
+    }
+
+    /// Reads the last rendered frame back, `None` for window surfaces.
+    pub fn frame(&self) -> Option<RgbaImage> {
+        match self {
+            Self::Wgpu(graphics) => graphics.frame(),
+            Self::Cpu(graphics) => Some(graphics.frame()),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Layer                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub enum Layer<'graphics> {
+    Wgpu(wgpu::Layer<'graphics>),
+    Cpu(cpu::Layer<'graphics>),
+}
+
+impl<'graphics> Layer<'graphics> {
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        match self {
+            Self::Wgpu(layer) => layer.size(),
+            Self::Cpu(layer) => layer.size(),
+        }
+    }
+
+    /// Returns the `Draw`ing API.
+    pub fn draw(&mut self, region: impl Into<Option<Rectangle>>, layer: u16) -> Draw<'_> {
+        match self {
+            Self::Wgpu(this) => Draw::Wgpu(this.draw(region, layer)),
+            Self::Cpu(this) => Draw::Cpu(this.draw(region, layer)),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                              Draw                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub enum Draw<'graphics> {
+    Wgpu(wgpu::Draw<'graphics>),
+    Cpu(cpu::Draw<'graphics>),
+}
+
+impl<'graphics> Draw<'graphics> {
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        match self {
+            Self::Wgpu(draw) => draw.size(),
+            Self::Cpu(draw) => draw.size(),
+        }
+    }
+
+    /// Draws a rectange.
+    pub fn rectangle(&mut self, rectangle: impl Into<Option<Rectangle>>, color: Rgba) {
+        match self {
+            Self::Wgpu(draw) => draw.rectangle(rectangle, color),
+            Self::Cpu(draw) => draw.rectangle(rectangle, color),
+        }
+    }
+
+    /// Draws a rectangle with corners rounded by `radius`, and a `border` inside its edges.
+    pub fn rounded_rectangle(
+        &mut self,
+        rectangle: impl Into<Option<Rectangle>>,
+        radius: u32,
+        color: Rgba,
+        border: u32,
+        border_color: Rgba,
+    ) {
+        match self {
+            Self::Wgpu(draw) => {
+                draw.rounded_rectangle(rectangle, radius, color, border, border_color)
+            }
+            Self::Cpu(draw) => {
+                draw.rounded_rectangle(rectangle, radius, color, border, border_color)
+            }
+        }
+    }
+
+    /// Draws the shadow of a rectangle with corners rounded by `radius`, blurred over `blur`
+    /// pixels on each side of its edges.
+    pub fn shadow(&mut self, rectangle: Rectangle, radius: u32, blur: u32, color: Rgba) {
+        match self {
+            Self::Wgpu(draw) => draw.shadow(rectangle, radius, blur, color),
+            Self::Cpu(draw) => draw.shadow(rectangle, radius, blur, color),
+        }
+    }
+
+    /// Draws a glyph.
+    pub fn glyph<F: FnOnce() -> Image>(
+        &mut self,
+        position: Position,
+        font_size: FontSize,
+        key: GlyphKey,
+        color: Rgba,
+        image: F,
+    ) {
+        match self {
+            Self::Wgpu(draw) => draw.glyph(position, font_size, key, color, image),
+            Self::Cpu(draw) => draw.glyph(position, font_size, key, color, image),
+        }
+    }
+
+    /// Draws glyphs.
+    pub fn glyphs(
+        &mut self,
+        context: &mut Context,
+        position: Position,
+        line: &Line,
+        line_height: LineHeight,
+    ) {
+        match self {
+            Self::Wgpu(draw) => draw.glyphs(context, position, line, line_height),
+            Self::Cpu(draw) => draw.glyphs(context, position, line, line_height),
+        }
+    }
+
+    /// Draws a polyline.
+    pub fn polyline<T: IntoIterator<Item = (Position, Rgba)>>(&mut self, points: T) {
+        match self {
+            Self::Wgpu(draw) => draw.polyline(points),
+            Self::Cpu(draw) => draw.polyline(points),
+        }
+    }
+
+    /// Draws a polygon.
+    pub fn polygon<T: IntoIterator<Item = (Position, Rgba)>>(&mut self, points: T) {
+        match self {
+            Self::Wgpu(draw) => draw.polygon(points),
+            Self::Cpu(draw) => draw.polygon(points),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           Primitives                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// What the backends' `Draw`s build glyphs on.
+pub(crate) trait Primitives {
+    fn rectangle(&mut self, rectangle: Rectangle, color: Rgba);
+
+    fn glyph<F: FnOnce() -> Image>(
+        &mut self,
+        position: Position,
+        font_size: FontSize,
+        key: GlyphKey,
+        color: Rgba,
+        image: F,
+    );
+
+    /// Draws the backgrounds, then the glyphs of `line`.
+    fn glyphs(
+        &mut self,
+        context: &mut Context,
+        position: Position,
+        line: &Line,
+        line_height: LineHeight,
+    ) {
+        //
+        // Add backgrounds
+        //
+
+        for (Range { start, end }, _, background) in line.segments(|glyph| glyph.styles.background)
+        {
+            self.rectangle(
+                Rectangle {
+                    top: position.top,
+                    left: position.left + start as i32,
+                    width: (end - start) as u32,
+                    height: line_height,
+                },
+                background,
+            );
+        }
+
+        //
+        // Add glyphs
+        //
+
+        let mut scaler = line.scaler(context);
+
+        for glyph in line.glyphs() {
+            self.glyph(
+                Position {
+                    top: position.top,
+                    left: position.left + glyph.pixel_offset().0,
+                },
+                line.font_size(),
+                glyph.key(),
+                glyph.styles.foreground,
+                || scaler.render(glyph),
+            );
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_with_cpu() {
+        let mut graphics = Graphics::from(cpu::Graphics::new(20, 10));
+        graphics.set_clear_color(Rgba::WHITE);
+
+        {
+            let region = Rectangle::from((Position::default(), graphics.size()));
+            let mut layer = graphics.layer(region, 0);
+            layer.draw(None, 0).rectangle(
+                Rectangle {
+                    top: 0,
+                    left: 0,
+                    width: 10,
+                    height: 10,
+                },
+                Rgba::RED,
+            );
+        }
+        graphics.render();
+
+        let frame = graphics.frame().unwrap();
+        assert!(frame.get_pixel(5, 5).0 == [255, 0, 0, 255]);
+        assert!(frame.get_pixel(15, 5).0 == [255, 255, 255, 255]);
+    }
+}
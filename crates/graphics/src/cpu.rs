@@ -0,0 +1,704 @@
+//! CPU software rasterizer, with the same `Layer`/`Draw` API as [`crate::wgpu`].
+//!
+//! Follows the wgpu pipelines: layers render in order, rectangles then glyphs then lines,
+//! clipped to their regions and alpha blended in linear space.
+
+use crate::{
+    backend::Primitives,
+    text::{Context, FontSize, GlyphKey, Line, LineHeight},
+    types::{Position, Rectangle, Rgba, Size},
+};
+use image::{ImageResult, RgbaImage};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+use swash::{
+    scale::image::{Content, Image},
+    zeno::Placement,
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Commands                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A line point.
+type Point = (Position, Rgba);
+
+/// A rectangle shape, as the wgpu rectangle pipeline's.
+#[derive(Copy, Clone, Default, Debug)]
+struct Shape {
+    /// Uncropped rectangle.
+    rectangle: Rectangle,
+    /// Corner radius.
+    radius: u32,
+    /// Border width, inside the rectangle.
+    border: u32,
+    /// Blur width on each side of the edges, for shadows.
+    blur: u32,
+}
+
+/// Draw commands of a layer, in rendering order.
+#[derive(Default, Debug)]
+struct Commands {
+    /// Cropped rectangles, with their shape, color and border color.
+    rectangles: Vec<(Rectangle, Shape, Rgba, Rgba)>,
+    /// Glyphs, with their uncropped rectangle, crop region and color.
+    glyphs: Vec<(GlyphKey, Rectangle, Rectangle, Rgba)>,
+    /// Line segments, with their region.
+    lines: Vec<(Rectangle, Point, Point)>,
+}
+
+/// A cached glyph image.
+#[derive(Debug)]
+struct Glyph {
+    content: Content,
+    placement: Placement,
+    data: Vec<u8>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Graphics                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// CPU graphics.
+#[derive(Debug)]
+pub struct Graphics {
+    size: Size,
+    /// Linear RGBA pixels.
+    frame: Vec<[f32; 4]>,
+    layers: BTreeMap<u32, Commands>,
+    glyphs: HashMap<GlyphKey, Glyph>,
+    /// Frames' clear color.
+    clear_color: Rgba,
+    /// Text gamma and contrast.
+    gamma: (f32, f32),
+}
+
+impl Graphics {
+    /// Creates a new `Graphics` of `width` by `height`.
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut graphics = Self {
+            size: Size { width, height },
+            frame: Vec::new(),
+            layers: Default::default(),
+            glyphs: Default::default(),
+            clear_color: Rgba::BLACK,
+            gamma: (1., 0.),
+        };
+        graphics.clear();
+        graphics
+    }
+
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Resizes the framebuffer.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.size = Size { width, height };
+        self.clear();
+    }
+
+    /// Clears the glyph cache, e.g. when font sizes change.
+    pub fn clear_glyphs(&mut self) {
+        self.glyphs.clear();
+    }
+
+    /// Sets the color frames are cleared with (black by default).
+    pub fn set_clear_color(&mut self, color: Rgba) {
+        self.clear_color = color;
+    }
+
+    /// Sets the `gamma` and `contrast` of text, see [`crate::wgpu::Graphics::set_text_gamma()`].
+    pub fn set_text_gamma(&mut self, gamma: f32, contrast: f32) {
+        self.gamma = (gamma, contrast);
+    }
+
+    /// Returns the `Layer`ing API.
+    pub fn layer(&mut self, region: Rectangle, layer: u16) -> Layer<'_> {
+        Layer {
+            graphics: self,
+            region,
+            layer: layer as u32 * u16::MAX as u32,
+        }
+    }
+
+    /// Renders to the framebuffer.
+    pub fn render(&mut self) {
+        self.clear();
+
+        let layers = std::mem::take(&mut self.layers);
+        for commands in layers.values() {
+            for &(rectangle, shape, color, border_color) in &commands.rectangles {
+                self.rectangle(rectangle, shape, color, border_color);
+            }
+            for &(key, rectangle, region, color) in &commands.glyphs {
+                self.glyph(key, rectangle, region, color);
+            }
+            for &(region, from, to) in &commands.lines {
+                self.line(region, from, to);
+            }
+        }
+    }
+
+    /// Returns the last rendered frame.
+    pub fn frame(&self) -> RgbaImage {
+        let pixels = self
+            .frame
+            .iter()
+            .flat_map(|&[r, g, b, a]| [encode(r), encode(g), encode(b), quantize(a)])
+            .collect();
+
+        RgbaImage::from_raw(self.size.width, self.size.height, pixels).expect("Frame size")
+    }
+
+    /// Saves the last rendered frame as a PNG at `path`.
+    pub fn save_frame(&self, path: impl AsRef<Path>) -> ImageResult<()> {
+        self.frame().save(path)
+    }
+}
+
+/// Private.
+impl Graphics {
+    fn clear(&mut self) {
+        // As the wgpu clear color, given in linear space
+        let color = self.clear_color;
+        let color = [color.r, color.g, color.b, color.a].map(|c| c as f32 / 255.);
+
+        self.frame.clear();
+        self.frame
+            .resize((self.size.width * self.size.height) as usize, color);
+    }
+
+    fn rectangle(&mut self, rectangle: Rectangle, shape: Shape, color: Rgba, border_color: Rgba) {
+        let (color, border_color) = (linear(color), linear(border_color));
+        let plain = shape.radius == 0 && shape.border == 0 && shape.blur == 0;
+
+        for top in rectangle.top..rectangle.bottom() {
+            for left in rectangle.left..rectangle.right() {
+                let pixel = if plain {
+                    color
+                } else {
+                    let center = (left as f32 + 0.5, top as f32 + 0.5);
+                    shade(shape, center, color, border_color)
+                };
+                blend(&mut self.frame, self.size, top, left, pixel);
+            }
+        }
+    }
+
+    fn glyph(&mut self, key: GlyphKey, rectangle: Rectangle, region: Rectangle, color: Rgba) {
+        let (Some(glyph), Some(cropped)) = (self.glyphs.get(&key), rectangle.intersection(region))
+        else {
+            return;
+        };
+        let color = linear(color);
+
+        for top in cropped.top..cropped.bottom() {
+            for left in cropped.left..cropped.right() {
+                let x = (left - rectangle.left) as usize;
+                let y = (top - rectangle.top) as usize;
+                let index = y * glyph.placement.width as usize + x;
+
+                let pixel = match glyph.content {
+                    Content::Mask => {
+                        let mask = coverage(glyph.data[index] as f32 / 255., color, self.gamma);
+                        [color[0], color[1], color[2], color[3] * mask]
+                    }
+                    Content::Color => {
+                        let [r, g, b, a] = [0, 1, 2, 3].map(|i| glyph.data[4 * index + i]);
+                        linear(Rgba::new(r, g, b, a))
+                    }
+                    Content::SubpixelMask => {
+                        // Averages the RGB coverages, no subpixel rendering
+                        let [r, g, b] = [0, 1, 2].map(|i| glyph.data[4 * index + i] as f32);
+                        let mask = coverage((r + g + b) / (3. * 255.), color, self.gamma);
+                        [color[0], color[1], color[2], color[3] * mask]
+                    }
+                };
+                blend(&mut self.frame, self.size, top, left, pixel);
+            }
+        }
+    }
+
+    /// Rasterizes the segment (Bresenham), excluding its last point as GPU line lists do.
+    fn line(&mut self, region: Rectangle, (from, from_color): Point, (to, to_color): Point) {
+        let from = from + region.position();
+        let to = to + region.position();
+        let (from_color, to_color) = (linear(from_color), linear(to_color));
+
+        let dx = (to.left - from.left).abs();
+        let dy = -(to.top - from.top).abs();
+        let sx = (to.left - from.left).signum();
+        let sy = (to.top - from.top).signum();
+        let steps = dx.max(-dy);
+        let (mut left, mut top) = (from.left, from.top);
+        let mut error = dx + dy;
+
+        for step in 0..steps {
+            let inside = region.top <= top
+                && top < region.bottom()
+                && region.left <= left
+                && left < region.right();
+
+            if inside {
+                let t = step as f32 / steps as f32;
+                let color = [0, 1, 2, 3].map(|i| from_color[i] + (to_color[i] - from_color[i]) * t);
+                blend(&mut self.frame, self.size, top, left, color);
+            }
+
+            let double = 2 * error;
+            if dy <= double {
+                error += dy;
+                left += sx;
+            }
+            if double <= dx {
+                error += dx;
+                top += sy;
+            }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Layer                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct Layer<'graphics> {
+    graphics: &'graphics mut Graphics,
+    region: Rectangle,
+    layer: u32,
+}
+
+impl<'graphics> Layer<'graphics> {
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        self.region.size()
+    }
+
+    /// Returns the `Draw`ing API.
+    pub fn draw(&mut self, region: impl Into<Option<Rectangle>>, layer: u16) -> Draw<'_> {
+        let region = region
+            .into()
+            .map(|rectangle| rectangle.region(self.region).unwrap_or_default())
+            .unwrap_or(self.region);
+
+        Draw {
+            graphics: self.graphics,
+            layer: self.layer + layer as u32,
+            region,
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                              Draw                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct Draw<'graphics> {
+    graphics: &'graphics mut Graphics,
+    region: Rectangle,
+    layer: u32,
+}
+
+impl<'graphics> Draw<'graphics> {
+    /// Returns the size.
+    pub fn size(&self) -> Size {
+        self.region.size()
+    }
+
+    /// Draws a rectange.
+    pub fn rectangle(&mut self, rectangle: impl Into<Option<Rectangle>>, color: Rgba) {
+        let rectangle = rectangle
+            .into()
+            .unwrap_or_else(|| Rectangle::from((Position::default(), self.size())));
+
+        self.shape(rectangle, 0, 0, 0, color, Rgba::TRANSPARENT);
+    }
+
+    /// Draws a rectangle with corners rounded by `radius`, and a `border` inside its edges.
+    pub fn rounded_rectangle(
+        &mut self,
+        rectangle: impl Into<Option<Rectangle>>,
+        radius: u32,
+        color: Rgba,
+        border: u32,
+        border_color: Rgba,
+    ) {
+        let rectangle = rectangle
+            .into()
+            .unwrap_or_else(|| Rectangle::from((Position::default(), self.size())));
+
+        self.shape(rectangle, radius, border, 0, color, border_color);
+    }
+
+    /// Draws the shadow of a rectangle with corners rounded by `radius`, blurred over `blur`
+    /// pixels on each side of its edges.
+    pub fn shadow(&mut self, rectangle: Rectangle, radius: u32, blur: u32, color: Rgba) {
+        self.shape(rectangle, radius, 0, blur, color, Rgba::TRANSPARENT);
+    }
+
+    /// Draws a glyph.
+    pub fn glyph<F: FnOnce() -> Image>(
+        &mut self,
+        position: Position,
+        font_size: FontSize,
+        key: GlyphKey,
+        color: Rgba,
+        image: F,
+    ) {
+        // Early return for invisible glyphs
+        if !color.is_visible()
+            || matches!(u32::try_from(position.top), Ok(top) if self.region.size().height <= top)
+            || matches!(u32::try_from(position.left), Ok(left) if self.region.size().width <= left)
+        {
+            return;
+        }
+
+        // Get or insert glyph in cache
+        let placement = self
+            .graphics
+            .glyphs
+            .entry(key)
+            .or_insert_with(|| {
+                let image = image();
+
+                Glyph {
+                    content: image.content,
+                    placement: image.placement,
+                    data: image.data,
+                }
+            })
+            .placement;
+
+        let rectangle = Rectangle::from((font_size, placement)) + position + self.region.position();
+        let region = self.region;
+        self.commands().glyphs.push((key, rectangle, region, color));
+    }
+
+    /// Draws glyphs.
+    pub fn glyphs(
+        &mut self,
+        context: &mut Context,
+        position: Position,
+        line: &Line,
+        line_height: LineHeight,
+    ) {
+        Primitives::glyphs(self, context, position, line, line_height);
+    }
+
+    /// Draws a polyline.
+    pub fn polyline<T: IntoIterator<Item = (Position, Rgba)>>(&mut self, points: T) {
+        self.lines(points, false);
+    }
+
+    /// Draws a polygon.
+    pub fn polygon<T: IntoIterator<Item = (Position, Rgba)>>(&mut self, points: T) {
+        self.lines(points, true);
+    }
+}
+
+impl<'graphics> Primitives for Draw<'graphics> {
+    fn rectangle(&mut self, rectangle: Rectangle, color: Rgba) {
+        Draw::rectangle(self, rectangle, color);
+    }
+
+    fn glyph<F: FnOnce() -> Image>(
+        &mut self,
+        position: Position,
+        font_size: FontSize,
+        key: GlyphKey,
+        color: Rgba,
+        image: F,
+    ) {
+        Draw::glyph(self, position, font_size, key, color, image);
+    }
+}
+
+/// Private.
+impl<'graphics> Draw<'graphics> {
+    fn commands(&mut self) -> &mut Commands {
+        self.graphics.layers.entry(self.layer).or_default()
+    }
+
+    fn shape(
+        &mut self,
+        rectangle: Rectangle,
+        radius: u32,
+        border: u32,
+        blur: u32,
+        color: Rgba,
+        border_color: Rgba,
+    ) {
+        if !color.is_visible() && !(border != 0 && border_color.is_visible()) {
+            return;
+        }
+
+        // Shapes are not cropped, only their quads
+        let rectangle = rectangle + self.region.position();
+        let Some(cropped) = rectangle.grow(blur).intersection(self.region) else {
+            return;
+        };
+        let shape = Shape {
+            rectangle,
+            radius,
+            border,
+            blur,
+        };
+
+        self.commands()
+            .rectangles
+            .push((cropped, shape, color, border_color));
+    }
+
+    fn lines<T: IntoIterator<Item = (Position, Rgba)>>(&mut self, points: T, closed: bool) {
+        let region = self.region;
+        let mut points = points.into_iter();
+
+        let (first, mut prev) = if let Some(first) = points.next() {
+            (first, first)
+        } else {
+            debug_assert!(false, "No points");
+            return;
+        };
+
+        let lines = &mut self.commands().lines;
+        for curr in points {
+            lines.push((region, prev, curr));
+            prev = curr;
+        }
+
+        if closed {
+            lines.push((region, prev, first));
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Colors                                             //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Blends `color` (linear, straight alpha) over the pixel of `frame` at `(top, left)`.
+fn blend(frame: &mut [[f32; 4]], size: Size, top: i32, left: i32, [r, g, b, a]: [f32; 4]) {
+    if top < 0 || left < 0 || size.height as i32 <= top || size.width as i32 <= left {
+        return;
+    }
+
+    let pixel = &mut frame[(top as u32 * size.width + left as u32) as usize];
+    *pixel = [
+        r * a + pixel[0] * (1. - a),
+        g * a + pixel[1] * (1. - a),
+        b * a + pixel[2] * (1. - a),
+        a + pixel[3] * (1. - a),
+    ];
+}
+
+/// Adjusts `mask` coverage for the brightness of the (linear) text `color`, as the wgpu shader.
+fn coverage(mask: f32, color: [f32; 4], (gamma, contrast): (f32, f32)) -> f32 {
+    let brightness = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+    let k = contrast * (1. - brightness);
+    let enhanced = mask * (k + 1.) / (mask * k + 1.);
+
+    enhanced.powf(1. + (gamma - 1.) * brightness)
+}
+
+/// Returns the (linear, straight alpha) color of `shape` at `point`, as the wgpu shader.
+fn shade(shape: Shape, (x, y): (f32, f32), color: [f32; 4], border_color: [f32; 4]) -> [f32; 4] {
+    let Shape {
+        rectangle,
+        radius,
+        border,
+        blur,
+    } = shape;
+
+    // Signed distance to the rounded rectangle, negative inside
+    let half = (rectangle.width as f32 / 2., rectangle.height as f32 / 2.);
+    let center = (
+        rectangle.left as f32 + half.0,
+        rectangle.top as f32 + half.1,
+    );
+    let radius = (radius as f32).min(half.0).min(half.1);
+    let q = (
+        (x - center.0).abs() - half.0 + radius,
+        (y - center.1).abs() - half.1 + radius,
+    );
+    let distance = q.0.max(0.).hypot(q.1.max(0.)) + q.0.max(q.1).min(0.) - radius;
+
+    // Shadow
+    if blur != 0 {
+        let alpha = 1. - smoothstep(-(blur as f32), blur as f32, distance);
+        return [color[0], color[1], color[2], color[3] * alpha];
+    }
+
+    // Border over fill, premultiplied
+    let outer = (0.5 - distance).clamp(0., 1.);
+    let inner = (0.5 - distance - border as f32).clamp(0., 1.);
+    let premultiply =
+        |[r, g, b, a]: [f32; 4], coverage: f32| [r * a, g * a, b * a, a].map(|c| c * coverage);
+    let (fill, edge) = (
+        premultiply(color, inner),
+        premultiply(border_color, outer - inner),
+    );
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| fill[i] + edge[i]);
+
+    if a <= 0. {
+        [0.; 4]
+    } else {
+        [r / a, g / a, b / a, a]
+    }
+}
+
+fn smoothstep(from: f32, to: f32, x: f32) -> f32 {
+    let t = ((x - from) / (to - from)).clamp(0., 1.);
+
+    t * t * (3. - 2. * t)
+}
+
+/// Converts an sRGBA color to linear space, as the wgpu shaders.
+fn linear(color: Rgba) -> [f32; 4] {
+    [
+        (color.r as f32 / 255.).powf(2.2),
+        (color.g as f32 / 255.).powf(2.2),
+        (color.b as f32 / 255.).powf(2.2),
+        color.a as f32 / 255.,
+    ]
+}
+
+/// Converts a linear channel back to sRGB.
+fn encode(channel: f32) -> u8 {
+    quantize(channel.clamp(0., 1.).powf(1. / 2.2))
+}
+
+fn quantize(channel: f32) -> u8 {
+    (channel.clamp(0., 1.) * 255.).round() as u8
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterize() {
+        let mut graphics = Graphics::new(20, 10);
+        let background = graphics.frame().get_pixel(0, 0).0;
+        let region = Rectangle {
+            top: 2,
+            left: 2,
+            width: 10,
+            height: 6,
+        };
+
+        {
+            let mut layer = graphics.layer(region, 0);
+            let mut draw = layer.draw(None, 0);
+
+            // Cropped to the region
+            draw.rectangle(
+                Rectangle {
+                    top: -5,
+                    left: 0,
+                    width: 100,
+                    height: 100,
+                },
+                Rgba::RED,
+            );
+            // Above the rectangle, whatever the call order
+            draw.polyline([
+                (Position { top: 1, left: 0 }, Rgba::BLUE),
+                (Position { top: 1, left: 5 }, Rgba::BLUE),
+            ]);
+        }
+        {
+            // Below the first layer's lines
+            let mut layer = graphics.layer(region, 1);
+            layer.draw(None, 0).rectangle(
+                Rectangle {
+                    top: 0,
+                    left: 0,
+                    width: 1,
+                    height: 1,
+                },
+                Rgba::GREEN,
+            );
+        }
+        graphics.render();
+
+        let frame = graphics.frame();
+        assert!(frame.get_pixel(0, 0).0 == background);
+        assert!(frame.get_pixel(12, 2).0 == background);
+        assert!(frame.get_pixel(11, 7).0 == [255, 0, 0, 255]);
+        assert!(frame.get_pixel(2, 2).0 == [0, 255, 0, 255]);
+        assert!(frame.get_pixel(4, 3).0 == [0, 0, 255, 255]);
+        assert!(frame.get_pixel(7, 3).0 == [255, 0, 0, 255]);
+
+        // Commands are flushed
+        graphics.render();
+        assert!(graphics.frame().get_pixel(11, 7).0 == background);
+    }
+
+    #[test]
+    fn shapes() {
+        let mut graphics = Graphics::new(30, 20);
+        let background = [255, 255, 255, 255];
+        graphics.set_clear_color(Rgba::WHITE);
+        let region = Rectangle {
+            top: 0,
+            left: 0,
+            width: 30,
+            height: 10,
+        };
+
+        {
+            let mut layer = graphics.layer(region, 0);
+            let mut draw = layer.draw(None, 0);
+
+            draw.rounded_rectangle(
+                Rectangle {
+                    top: 0,
+                    left: 0,
+                    width: 10,
+                    height: 10,
+                },
+                4,
+                Rgba::GREEN,
+                1,
+                Rgba::RED,
+            );
+            // Cropped to the region, not reshaped
+            draw.shadow(
+                Rectangle {
+                    top: 5,
+                    left: 12,
+                    width: 6,
+                    height: 10,
+                },
+                0,
+                2,
+                Rgba::BLACK,
+            );
+        }
+        graphics.render();
+
+        let frame = graphics.frame();
+        let darker =
+            |[r, g, b, _]: [u8; 4]| r < background[0] && g < background[1] && b < background[2];
+
+        // Rounded corner, border, fill
+        assert!(frame.get_pixel(0, 0).0 == background);
+        assert!(frame.get_pixel(0, 5).0 == [255, 0, 0, 255]);
+        assert!(frame.get_pixel(5, 5).0 == [0, 255, 0, 255]);
+
+        // Blurred edges
+        assert!(frame.get_pixel(15, 9).0 == [0, 0, 0, 255]);
+        assert!(frame.get_pixel(18, 9).0 != [0, 0, 0, 255]);
+        assert!(darker(frame.get_pixel(18, 9).0));
+        assert!(frame.get_pixel(25, 9).0 == background);
+        assert!(frame.get_pixel(15, 12).0 == background);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod backend;
+pub mod cpu;
 pub mod muck;
 pub mod text;
 pub mod types {
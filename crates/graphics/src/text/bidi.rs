@@ -0,0 +1,225 @@
+//! Unicode bidirectional algorithm, for single lines of code.
+//!
+//! Lines are left-to-right paragraphs. Explicit embeddings, overrides and isolates are not
+//! supported: they resolve as neutrals.
+
+use swash::text::{BidiClass, Codepoint};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Levels                                             //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Returns the resolved embedding levels of `line`, one per byte (odd for right-to-left).
+pub fn levels(line: &str) -> Vec<u8> {
+    use BidiClass::*;
+
+    let original = line
+        .chars()
+        .map(|char| char.bidi_class())
+        .collect::<Vec<_>>();
+
+    if !original.iter().any(|class| class.needs_resolution()) {
+        return vec![0; line.len()];
+    }
+
+    // X9: explicit formatting characters are neutrals
+    let mut classes = original
+        .iter()
+        .map(|&class| match class {
+            LRE | RLE | LRO | RLO | PDF | LRI | RLI | FSI | PDI | BN => ON,
+            class => class,
+        })
+        .collect::<Vec<_>>();
+
+    // W1: non-spacing marks take the class of the previous character
+    let mut prev = L;
+    for class in &mut classes {
+        if *class == NSM {
+            *class = prev;
+        }
+        prev = *class;
+    }
+
+    // W2: european numbers after arabic letters are arabic numbers
+    // W3: arabic letters are right-to-left
+    let mut strong = L;
+    for class in &mut classes {
+        match *class {
+            L | R => strong = *class,
+            AL => {
+                strong = AL;
+                *class = R;
+            }
+            EN if strong == AL => *class = AN,
+            _ => {}
+        }
+    }
+
+    // W4: single separators between numbers of the same kind join them
+    for i in 1..classes.len().saturating_sub(1) {
+        match (classes[i - 1], classes[i], classes[i + 1]) {
+            (EN, ES | CS, EN) => classes[i] = EN,
+            (AN, CS, AN) => classes[i] = AN,
+            _ => {}
+        }
+    }
+
+    // W5: terminators next to european numbers are european numbers
+    for range in runs(&classes, |class| class == ET) {
+        let before = range.start.checked_sub(1).map(|i| classes[i]);
+        let after = classes.get(range.end).copied();
+
+        if before == Some(EN) || after == Some(EN) {
+            classes[range].fill(EN);
+        }
+    }
+
+    // W6: remaining separators and terminators are neutrals
+    // W7: european numbers after left-to-right letters are left-to-right
+    let mut strong = L;
+    for class in &mut classes {
+        match *class {
+            L | R => strong = *class,
+            ES | ET | CS => *class = ON,
+            EN if strong == L => *class = L,
+            _ => {}
+        }
+    }
+
+    // N1: neutrals between characters of the same direction take that direction
+    // N2: other neutrals take the paragraph direction
+    let direction = |class| if class == L { L } else { R };
+    for range in runs(&classes, |class| matches!(class, B | S | WS | ON)) {
+        let before = range
+            .start
+            .checked_sub(1)
+            .map_or(L, |i| direction(classes[i]));
+        let after = classes.get(range.end).map_or(L, |&class| direction(class));
+
+        classes[range].fill(if before == after { before } else { L });
+    }
+
+    // I1: implicit levels
+    let mut levels = classes
+        .iter()
+        .map(|class| match class {
+            R => 1,
+            EN | AN => 2,
+            _ => 0,
+        })
+        .collect::<Vec<u8>>();
+
+    // L1: separators and trailing whitespaces are reset to the paragraph level
+    let mut trailing = true;
+    for (level, class) in levels.iter_mut().zip(&original).rev() {
+        match class {
+            S | B => {
+                *level = 0;
+                trailing = true;
+            }
+            WS | LRE | RLE | LRO | RLO | PDF | LRI | RLI | FSI | PDI | BN if trailing => *level = 0,
+            _ => trailing = false,
+        }
+    }
+
+    line.chars()
+        .zip(levels)
+        .flat_map(|(char, level)| std::iter::repeat_n(level, char.len_utf8()))
+        .collect()
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Reorder                                             //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Returns the visual order of items at `levels`.
+pub fn reorder(levels: &[u8]) -> Vec<usize> {
+    let mut items = levels
+        .iter()
+        .copied()
+        .zip(0..)
+        .collect::<Vec<(u8, usize)>>();
+    let max = levels.iter().copied().max().unwrap_or_default();
+
+    // L2: from the highest level to the lowest odd level, reverse runs at or above that level
+    for level in (1..=max).rev() {
+        for run in items.split_mut(|&(item, _)| item < level) {
+            run.reverse();
+        }
+    }
+
+    items.into_iter().map(|(_, index)| index).collect()
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Returns the ranges of consecutive `classes` matching `predicate`.
+fn runs(
+    classes: &[BidiClass],
+    predicate: impl Fn(BidiClass) -> bool,
+) -> Vec<std::ops::Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (i, &class) in classes.iter().enumerate() {
+        match (start, predicate(class)) {
+            (None, true) => start = Some(i),
+            (Some(run), false) => {
+                runs.push(run..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = start {
+        runs.push(start..classes.len());
+    }
+
+    runs
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bidi() {
+        // Left-to-right
+        assert!(levels("let a = 1;") == [0; 10]);
+
+        // Right-to-left in left-to-right, with whitespaces taking the paragraph direction
+        assert!(levels("a אב c") == [0, 0, 1, 1, 1, 1, 0, 0]);
+
+        // Numbers and whitespaces in right-to-left, trailing whitespaces reset
+        assert!(levels("אב 12 ") == [1, 1, 1, 1, 1, 2, 2, 0]);
+
+        // Arabic numbers and marks
+        assert!(levels("ب1") == [1, 1, 2]);
+        assert!(levels("בּ") == [1, 1, 1, 1]);
+
+        // Terminators next to numbers are numbers, others are neutrals
+        assert!(levels("א $1") == [1, 1, 1, 2, 2]);
+        assert!(levels("א $$1") == [1, 1, 1, 2, 2, 2]);
+        assert!(levels("א $") == [1, 1, 0, 0]);
+
+        // Single common separators join numbers of the same kind, others are neutrals
+        assert!(levels("א 1,2") == [1, 1, 1, 2, 2, 2]);
+        assert!(levels("א 1,,2") == [1, 1, 1, 2, 1, 1, 2]);
+        assert!(levels("ب1,2") == [1, 1, 2, 2, 2]);
+        assert!(levels("1,2") == [0; 3]);
+
+        // Isolates are not supported, they resolve as neutrals
+        assert!(levels("\u{2067}a\u{2069}") == [0; 7]);
+        assert!(levels("a\u{2067}אב\u{2069}c") == [0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0]);
+        assert!(levels("\u{2066}אב\u{2069}") == [0, 0, 0, 1, 1, 1, 1, 0, 0, 0]);
+
+        assert!(reorder(&[0, 0, 0]) == [0, 1, 2]);
+        assert!(reorder(&[0, 1, 1, 0]) == [0, 2, 1, 3]);
+        assert!(reorder(&[1, 1, 1, 2, 2, 0]) == [3, 4, 2, 1, 0, 5]);
+    }
+}
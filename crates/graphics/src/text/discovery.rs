@@ -0,0 +1,627 @@
+use super::{CreateFamilyError, Font, FontFamilyKey, FontStyle, FontWeight, Fonts};
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use swash::{tag_from_bytes, FontRef, StringId, Style, Weight};
+
+const EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+/// Tables read to describe a face when scanning.
+const METADATA_TABLES: &[&[u8; 4]] = &[b"OS/2", b"fvar", b"name", b"post"];
+const MONOSPACE_FAMILIES: &[&str] = &[
+    "DejaVu Sans Mono",
+    "Noto Sans Mono",
+    "Liberation Mono",
+    "Ubuntu Mono",
+    "SF Mono",
+    "Menlo",
+    "Consolas",
+    "Courier New",
+];
+const EMOJI_FAMILIES: &[&str] = &[
+    "Noto Color Emoji",
+    "Apple Color Emoji",
+    "Twemoji",
+    "JoyPixels",
+    "Segoe UI Emoji",
+];
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            FontFace                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A font face found on the system.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FontFace {
+    /// Font file.
+    pub path: PathBuf,
+    /// Index in the font file, for collections.
+    pub index: usize,
+    /// Family name.
+    pub family: String,
+    /// Face weight.
+    pub weight: FontWeight,
+    /// Face style.
+    pub style: FontStyle,
+    /// Whether the face is monospace.
+    pub monospace: bool,
+}
+
+impl FontFace {
+    /// Returns the face of `font` (at `index` in the file at `path`), from its metadata.
+    fn new(path: &Path, index: usize, font: FontRef) -> Option<Self> {
+        let strings = font.localized_strings();
+        // Typographic families group more than 4 faces, e.g. `Thin` and `Black`
+        let family = strings
+            .find_by_id(StringId::TypographicFamily, None)
+            .or_else(|| strings.find_by_id(StringId::Family, None))?
+            .to_string();
+        let attributes = font.attributes();
+
+        Some(Self {
+            path: path.to_owned(),
+            index,
+            family,
+            weight: weight(attributes.weight()),
+            style: match attributes.style() {
+                Style::Normal => FontStyle::Normal,
+                Style::Italic => FontStyle::Italic,
+                Style::Oblique(_) => FontStyle::Oblique,
+            },
+            // `post.isFixedPitch`
+            monospace: font
+                .table(tag_from_bytes(b"post"))
+                .and_then(|post| post.get(12..16))
+                .is_some_and(|fixed| fixed != [0; 4]),
+        })
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                       FontDiscoveryError                                       //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A [`SystemFonts`] error.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum FontDiscoveryError {
+    /// No face of this family was found in these directories.
+    FamilyNotFound(String, Vec<PathBuf>),
+    /// A face could not be loaded.
+    InvalidFace(FontFace),
+}
+
+impl fmt::Display for FontDiscoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FamilyNotFound(family, directories) => {
+                write!(f, "Font family `{family}` not found in:")?;
+
+                for directory in directories {
+                    write!(f, "\n- {}", directory.display())?;
+                }
+
+                Ok(())
+            }
+            Self::InvalidFace(face) => write!(
+                f,
+                "Cannot load font face {} of `{}` ({:?} {:?})",
+                face.path.display(),
+                face.family,
+                face.weight,
+                face.style,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FontDiscoveryError {}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                          SystemFonts                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Font faces found on the system, indexed by family, weight and style.
+///
+/// Scans the standard font directories, and those of fontconfig's configs.
+#[derive(Clone, Debug)]
+pub struct SystemFonts {
+    /// Scanned directories.
+    directories: Vec<PathBuf>,
+    /// Found faces.
+    faces: Vec<FontFace>,
+}
+
+impl SystemFonts {
+    /// Scans the system font directories.
+    pub fn scan() -> Self {
+        Self::scan_directories(Self::directories())
+    }
+
+    /// Scans `directories`, recursively.
+    pub fn scan_directories(directories: Vec<PathBuf>) -> Self {
+        let mut faces = Vec::new();
+        let mut visited = HashSet::new();
+
+        for directory in &directories {
+            scan(directory, &mut visited, &mut faces);
+        }
+
+        Self { directories, faces }
+    }
+
+    /// Returns the standard font directories, then those of fontconfig's configs.
+    pub fn directories() -> Vec<PathBuf> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".config")));
+
+        let mut directories = vec![
+            PathBuf::from("/usr/share/fonts"),
+            PathBuf::from("/usr/local/share/fonts"),
+            // macOS
+            PathBuf::from("/System/Library/Fonts"),
+            PathBuf::from("/Library/Fonts"),
+        ];
+        directories.extend(data_home.map(|data_home| data_home.join("fonts")));
+        directories.extend(home.as_ref().map(|home| home.join(".fonts")));
+        directories.extend(home.as_ref().map(|home| home.join("Library/Fonts")));
+
+        let mut configs = vec![PathBuf::from("/etc/fonts/fonts.conf")];
+        configs.extend(config_home.map(|config_home| config_home.join("fontconfig/fonts.conf")));
+        let mut visited = HashSet::new();
+
+        for config in configs {
+            config_directories(&config, &mut visited, &mut directories);
+        }
+
+        let mut unique = HashSet::new();
+        directories.retain(|directory| unique.insert(directory.clone()));
+        directories
+    }
+
+    /// Returns the scanned directories.
+    pub fn scanned_directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    /// Returns the found faces.
+    pub fn faces(&self) -> &[FontFace] {
+        &self.faces
+    }
+
+    /// Returns the faces of `family` (case insensitive).
+    pub fn family<'a>(&'a self, family: &'a str) -> impl 'a + Iterator<Item = &'a FontFace> {
+        self.faces
+            .iter()
+            .filter(move |face| face.family.eq_ignore_ascii_case(family))
+    }
+
+    /// Returns a common monospace family, or the first monospace family found.
+    pub fn monospace(&self) -> Option<&str> {
+        MONOSPACE_FAMILIES
+            .iter()
+            .find_map(|family| self.family(family).next())
+            .or_else(|| self.faces.iter().find(|face| face.monospace))
+            .map(|face| face.family.as_str())
+    }
+
+    /// Loads the faces of `family` in `fonts`, as a new family.
+    ///
+    /// The first face found for a weight and style wins.
+    pub fn load(
+        &self,
+        fonts: &mut Fonts,
+        family: &str,
+    ) -> Result<FontFamilyKey, FontDiscoveryError> {
+        self.load_faces(fonts, family, self.family(family))
+    }
+
+    /// Loads the regular face of `family` (or its first face) in `fonts`, as a new family.
+    ///
+    /// Other weights and styles fall back to it, e.g. for fallback families.
+    pub fn load_regular(
+        &self,
+        fonts: &mut Fonts,
+        family: &str,
+    ) -> Result<FontFamilyKey, FontDiscoveryError> {
+        self.load_faces(fonts, family, self.regular(family))
+    }
+
+    /// Returns an emoji font, or the regular face of `fallback` if there are none.
+    pub fn emoji(&self, fallback: &str) -> Result<Font, FontDiscoveryError> {
+        let face = EMOJI_FAMILIES
+            .iter()
+            .find_map(|family| self.family(family).next())
+            .or_else(|| self.regular(fallback))
+            .ok_or_else(|| {
+                FontDiscoveryError::FamilyNotFound(fallback.to_owned(), self.directories.clone())
+            })?;
+
+        Font::from_collection(&face.path, face.index)
+            .ok_or_else(|| FontDiscoveryError::InvalidFace(face.clone()))
+    }
+}
+
+/// Private.
+impl SystemFonts {
+    /// Returns the regular face of `family`, or its first face.
+    fn regular<'a>(&'a self, family: &'a str) -> Option<&'a FontFace> {
+        self.family(family)
+            .find(|face| (face.weight, face.style) == Default::default())
+            .or_else(|| self.family(family).next())
+    }
+
+    /// Loads `faces` of `family` in `fonts`, as a new family.
+    ///
+    /// The first face found for a weight and style wins.
+    fn load_faces<'a>(
+        &self,
+        fonts: &mut Fonts,
+        family: &str,
+        faces: impl IntoIterator<Item = &'a FontFace>,
+    ) -> Result<FontFamilyKey, FontDiscoveryError> {
+        let mut faces = faces.into_iter().peekable();
+
+        let Some(first) = faces.peek() else {
+            return Err(FontDiscoveryError::FamilyNotFound(
+                family.to_owned(),
+                self.directories.clone(),
+            ));
+        };
+        let family = match fonts.set(first.family.clone()) {
+            Ok(family) => family,
+            Err(CreateFamilyError::NameExists(family)) => return Ok(family),
+        };
+
+        for face in faces {
+            if fonts
+                .get(family)
+                .is_some_and(|family| family.variants().contains_key(&(face.weight, face.style)))
+            {
+                continue;
+            }
+
+            let font = Font::from_collection(&face.path, face.index)
+                .ok_or_else(|| FontDiscoveryError::InvalidFace(face.clone()))?;
+            let font = fonts.set(font).expect("New font");
+            fonts
+                .set((family, face.weight, face.style, font))
+                .expect("New variant");
+        }
+
+        Ok(family)
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Rounds `weight` to the nearest [`FontWeight`].
+fn weight(weight: Weight) -> FontWeight {
+    use FontWeight::*;
+
+    match weight.0 {
+        ..=149 => Thin,
+        150..=249 => ExtraLight,
+        250..=349 => Light,
+        350..=449 => Regular,
+        450..=549 => Medium,
+        550..=649 => SemiBold,
+        650..=749 => Bold,
+        750..=849 => ExtraBold,
+        _ => Black,
+    }
+}
+
+/// Pushes the faces of the font files in `directory` to `faces`, recursively.
+fn scan(directory: &Path, visited: &mut HashSet<PathBuf>, faces: &mut Vec<FontFace>) {
+    // Symlinks may loop
+    let Ok(directory) = directory.canonicalize() else {
+        return;
+    };
+
+    if !visited.insert(directory.clone()) {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(&directory) else {
+        return;
+    };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            scan(&path, visited, faces);
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()))
+        {
+            faces.extend(file_faces(&path).unwrap_or_default());
+        }
+    }
+}
+
+/// Returns the faces of the font file at `path`, reading only their [`METADATA_TABLES`].
+fn file_faces(path: &Path) -> io::Result<Vec<FontFace>> {
+    let mut file = File::open(path)?;
+    let header = read_at(&mut file, 0, 12)?;
+    let offsets = if &header[..4] == b"ttcf" {
+        let count = u32_at(&header, 8) as usize;
+        read_at(&mut file, 12, 4 * count)?
+            .chunks_exact(4)
+            .map(|offset| u32_at(offset, 0))
+            .collect()
+    } else {
+        vec![0]
+    };
+
+    Ok(offsets
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, offset)| {
+            let data = metadata(&mut file, offset).ok()?;
+            FontFace::new(path, index, FontRef::from_index(&data, 0)?)
+        })
+        .collect())
+}
+
+/// Returns a font with only the [`METADATA_TABLES`] of the table directory at `offset` in `file`.
+fn metadata(file: &mut File, offset: u32) -> io::Result<Vec<u8>> {
+    let header = read_at(file, offset as u64, 12)?;
+    let count = u16::from_be_bytes([header[4], header[5]]) as usize;
+    let records = read_at(file, offset as u64 + 12, 16 * count)?;
+    // Records are sorted by tag, for binary searches
+    let records = records
+        .chunks_exact(16)
+        .filter(|record| METADATA_TABLES.iter().any(|tag| tag[..] == record[..4]))
+        .collect::<Vec<_>>();
+
+    let mut data = header[..4].to_vec();
+    data.extend((records.len() as u16).to_be_bytes());
+    data.extend([0; 6]);
+    let mut tables = Vec::new();
+
+    for record in &records {
+        let start = 12 + 16 * records.len() + tables.len();
+        tables.extend(read_at(
+            file,
+            u32_at(record, 8) as u64,
+            u32_at(record, 12) as usize,
+        )?);
+        data.extend(&record[..8]);
+        data.extend((start as u32).to_be_bytes());
+        data.extend(&record[12..]);
+    }
+
+    data.extend(tables);
+    Ok(data)
+}
+
+/// Reads `len` bytes at `offset` in `file`.
+fn read_at(file: &mut File, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    // Does not trust `len` for the allocation
+    let mut data = Vec::new();
+    file.by_ref().take(len as u64).read_to_end(&mut data)?;
+
+    if data.len() == len {
+        Ok(data)
+    } else {
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+/// Returns the big-endian `u32` at `index` in `bytes`.
+fn u32_at(bytes: &[u8], index: usize) -> u32 {
+    u32::from_be_bytes([
+        bytes[index],
+        bytes[index + 1],
+        bytes[index + 2],
+        bytes[index + 3],
+    ])
+}
+
+/// Pushes the `<dir>`s of the fontconfig config at `path` to `directories`, following
+/// `<include>`s.
+fn config_directories(path: &Path, visited: &mut HashSet<PathBuf>, directories: &mut Vec<PathBuf>) {
+    if !visited.insert(path.to_owned()) {
+        return;
+    }
+
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        let mut configs = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "conf")
+            })
+            .collect::<Vec<_>>();
+        configs.sort();
+
+        for config in configs {
+            config_directories(&config, visited, directories);
+        }
+
+        return;
+    }
+
+    let Ok(config) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let parent = path.parent().unwrap_or(Path::new("/"));
+
+    for (tag, attributes, text) in elements(&config, &["dir", "include"]) {
+        let Some(path) = config_path(parent, attributes, text) else {
+            continue;
+        };
+
+        match tag {
+            "dir" => directories.push(path),
+            _ => config_directories(&path, visited, directories),
+        }
+    }
+}
+
+/// Returns the `(tag, attributes, text)` of the `tags` elements in the XML `config`.
+///
+/// Good enough for fontconfig's configs: no nesting in these elements, no entities.
+fn elements<'a>(config: &'a str, tags: &[&'a str]) -> Vec<(&'a str, &'a str, &'a str)> {
+    let mut elements = Vec::new();
+    let mut rest = config;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+
+        let Some(&tag) = tags.iter().find(|tag| {
+            rest.strip_prefix(**tag)
+                .is_some_and(|rest| rest.starts_with(|c: char| c == '>' || c.is_whitespace()))
+        }) else {
+            continue;
+        };
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let attributes = rest[tag.len()..end].trim();
+        rest = &rest[end + 1..];
+
+        // Self-closing
+        if attributes.ends_with('/') {
+            continue;
+        }
+
+        let Some(close) = rest.find(&format!("</{tag}>")) else {
+            break;
+        };
+        elements.push((tag, attributes, rest[..close].trim()));
+        rest = &rest[close..];
+    }
+
+    elements
+}
+
+/// Resolves the `text` path of a fontconfig element with `attributes`, in the config directory
+/// `parent`.
+fn config_path(parent: &Path, attributes: &str, text: &str) -> Option<PathBuf> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+
+    if text.is_empty() {
+        return None;
+    }
+
+    if attributes.contains(r#"prefix="xdg""#) {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home().map(|home| home.join(".local/share")))?;
+
+        Some(data_home.join(text))
+    } else if let Some(text) = text.strip_prefix("~/") {
+        Some(home()?.join(text))
+    } else {
+        Some(parent.join(text))
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::tests::sfnt;
+
+    #[test]
+    fn config_elements() {
+        let config = r#"<?xml version="1.0"?>
+<fontconfig>
+    <!-- Font directories -->
+    <dir>/usr/share/fonts</dir>
+    <dir prefix="xdg">fonts</dir>
+    <directory>/not/a/dir</directory>
+    <include ignore_missing="yes">conf.d</include>
+    <dir/>
+</fontconfig>"#;
+
+        assert_eq!(
+            elements(config, &["dir", "include"]),
+            [
+                ("dir", "", "/usr/share/fonts"),
+                ("dir", r#"prefix="xdg""#, "fonts"),
+                ("include", r#"ignore_missing="yes""#, "conf.d"),
+            ]
+        );
+        assert_eq!(
+            config_path(Path::new("/etc/fonts"), "", "conf.d"),
+            Some(PathBuf::from("/etc/fonts/conf.d"))
+        );
+        assert_eq!(
+            config_path(Path::new("/etc/fonts"), "", "/usr/share/fonts"),
+            Some(PathBuf::from("/usr/share/fonts"))
+        );
+    }
+
+    #[test]
+    fn scan_metadata() {
+        let directory = std::env::temp_dir().join(format!("virus-fonts-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("mono.ttf"), font("Test Mono", 700, true)).unwrap();
+        std::fs::write(directory.join("notes.txt"), "Not a font").unwrap();
+
+        let fonts = SystemFonts::scan_directories(vec![directory.clone()]);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            fonts.faces(),
+            [FontFace {
+                path: directory
+                    .canonicalize()
+                    .unwrap_or(directory)
+                    .join("mono.ttf"),
+                index: 0,
+                family: String::from("Test Mono"),
+                weight: FontWeight::Bold,
+                style: FontStyle::Italic,
+                monospace: true,
+            }]
+        );
+        assert_eq!(fonts.monospace(), Some("Test Mono"));
+    }
+
+    /// Returns a font file with `name`, OS/2, post and (unread) glyf tables.
+    fn font(family: &str, weight: u16, monospace: bool) -> Vec<u8> {
+        let family = family
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>();
+        let mut name = [0, 0, 0, 1, 0, 18, 0, 3, 0, 1, 4, 9, 0, 1].to_vec();
+        name.extend((family.len() as u16).to_be_bytes());
+        name.extend([0, 0]);
+        name.extend(family);
+        let mut os2 = vec![0; 78];
+        os2[4..6].copy_from_slice(&weight.to_be_bytes());
+        os2[63] = 1; // Italic
+        let mut post = vec![0; 32];
+        post[1] = 3;
+        post[15] = monospace as u8;
+
+        sfnt(vec![
+            (b"OS/2", os2),
+            (b"glyf", vec![0xff; 1024]),
+            (b"name", name),
+            (b"post", post),
+        ])
+    }
+}
@@ -1,6 +1,6 @@
 use super::{Advance, FontSize};
 use std::{collections::HashMap, path::Path};
-use swash::{CacheKey, FontDataRef, FontRef};
+use swash::{tag_from_bytes, CacheKey, FontDataRef, FontRef, Style, Tag};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                             FontWeight                                         //
@@ -31,6 +31,11 @@ pub enum FontWeight {
 }
 
 impl FontWeight {
+    /// Returns the numeric weight, from 100 to 900.
+    pub fn value(&self) -> u16 {
+        100 * (*self as u16 + 1)
+    }
+
     pub fn fallbacks(&self) -> &'static [Self] {
         use FontWeight::*;
 
@@ -101,6 +106,74 @@ impl FontStyle {
     }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         FontVariations                                         //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Variable font axes settings.
+///
+/// Lets a single variable font file supply the weights and slants missing from its family.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct FontVariations {
+    /// `wght` axis value.
+    pub weight: Option<u16>,
+    /// `slnt` axis value, in degrees.
+    pub slant: Option<i16>,
+}
+
+impl FontVariations {
+    /// Returns the axes settings, for swash's shaper and scaler.
+    pub fn settings(&self) -> impl Iterator<Item = (&'static str, f32)> {
+        let weight = self.weight.map(|weight| ("wght", weight as f32));
+        let slant = self.slant.map(|slant| ("slnt", slant as f32));
+
+        weight.into_iter().chain(slant)
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                          FontFeatures                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// OpenType features settings (e.g. `("ss01", 1)`), at most [`FontFeatures::CAPACITY`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct FontFeatures {
+    features: [(Tag, u16); Self::CAPACITY],
+    len: u8,
+}
+
+impl FontFeatures {
+    /// Maximum number of features.
+    pub const CAPACITY: usize = 4;
+
+    /// Returns the `features`.
+    ///
+    /// Panics with more than [`Self::CAPACITY`] features, or tags not of 4 bytes.
+    pub const fn new(features: &[(&str, u16)]) -> Self {
+        assert!(features.len() <= Self::CAPACITY, "Too many font features");
+
+        let mut this = Self {
+            features: [(0, 0); Self::CAPACITY],
+            len: features.len() as u8,
+        };
+        let mut i = 0;
+        while i < features.len() {
+            let (tag, value) = features[i];
+            let tag = tag.as_bytes();
+            assert!(tag.len() == 4, "Font feature tags are 4 bytes");
+            this.features[i] = (tag_from_bytes(&[tag[0], tag[1], tag[2], tag[3]]), value);
+            i += 1;
+        }
+
+        this
+    }
+
+    /// Returns the features settings, for swash's shaper.
+    pub fn settings(&self) -> impl Iterator<Item = (Tag, u16)> {
+        self.features.into_iter().take(self.len as usize)
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                              FontKey                                           //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -125,10 +198,26 @@ pub struct Font {
 impl Font {
     /// Returns a `Font` from `path`.
     ///
-    /// Does not support collections. Only the first font in the file is used.
+    /// For collections, only the first font in the file is used.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Option<Self> {
-        let data = std::fs::read(path).ok()?;
-        let font = FontRef::from_index(&data, 0)?;
+        Self::from_collection(path, 0)
+    }
+
+    /// Returns the font at `index` in the collection at `path`.
+    pub fn from_collection<P: AsRef<Path>>(path: P, index: usize) -> Option<Self> {
+        Self::from_collection_data(std::fs::read(path).ok()?, index)
+    }
+
+    /// Returns a `Font` from the `data` of a font file.
+    ///
+    /// For collections, only the first font in the data is used.
+    pub fn from_data(data: Vec<u8>) -> Option<Self> {
+        Self::from_collection_data(data, 0)
+    }
+
+    /// Returns the font at `index` in the collection `data`.
+    pub fn from_collection_data(data: Vec<u8>, index: usize) -> Option<Self> {
+        let font = FontRef::from_index(&data, index)?;
         let offset = font.offset;
         let key = font.key;
 
@@ -149,6 +238,32 @@ impl Font {
         self.key
     }
 
+    /// Returns the [`FontVariations`] approaching `weight` and `style` with this font's axes.
+    ///
+    /// Axes at their default value are left unset. Slant is only applied to upright fonts.
+    pub fn variations(&self, weight: FontWeight, style: FontStyle) -> FontVariations {
+        let font = self.as_ref();
+        let mut variations = FontVariations::default();
+
+        for axis in font.variations() {
+            let (min, max, default) = (axis.min_value(), axis.max_value(), axis.default_value());
+            let unless_default = |value: f32| (value != default).then_some(value);
+
+            if axis.tag() == tag_from_bytes(b"wght") {
+                variations.weight = unless_default((weight.value() as f32).clamp(min, max))
+                    .map(|value| value.round() as u16);
+            } else if axis.tag() == tag_from_bytes(b"slnt")
+                && style != FontStyle::Normal
+                && font.attributes().style() == Style::Normal
+            {
+                // Negative slant leans to the right
+                variations.slant = unless_default(min).map(|value| value.round() as i16);
+            }
+        }
+
+        variations
+    }
+
     /// Returns the advance given `size`.
     pub fn advance_for_size(&self, size: FontSize) -> Advance {
         let metrics = self.as_ref().metrics(&[]);
@@ -306,7 +421,8 @@ impl FontFamily {
 
 /// [`Font`] collection.
 ///
-/// Contains multiple [`Font`]s, organized in [`FontFamily`]s, and an emoji font fallback.
+/// Contains multiple [`Font`]s, organized in [`FontFamily`]s, with a chain of fallback families
+/// and a last emoji font fallback.
 ///
 /// `FontFamilyKey`s ***must not*** be reused in other `Fonts`.
 #[derive(Debug)]
@@ -315,6 +431,8 @@ pub struct Fonts {
     fonts: HashMap<FontKey, Font>,
     /// Families in the collection.
     families: HashMap<FontFamilyKey, FontFamily>,
+    /// Fallback families, in order.
+    fallbacks: Vec<FontFamilyKey>,
     /// The emoji fallback font.
     emoji: Font,
 }
@@ -325,6 +443,7 @@ impl Fonts {
         Self {
             fonts: Default::default(),
             families: Default::default(),
+            fallbacks: Default::default(),
             emoji,
         }
     }
@@ -339,6 +458,23 @@ impl Fonts {
         &self.families
     }
 
+    /// Returns the fallback families, in order.
+    pub fn fallbacks(&self) -> &[FontFamilyKey] {
+        &self.fallbacks
+    }
+
+    /// Sets the fallback families, tried in order when a family misses a character.
+    ///
+    /// Should be set before shaping: fallbacks are cached per character in [`Context`].
+    ///
+    /// [`Context`]: super::Context
+    pub fn set_fallbacks(&mut self, fallbacks: Vec<FontFamilyKey>) {
+        debug_assert!(fallbacks
+            .iter()
+            .all(|family| self.families.contains_key(family)));
+        self.fallbacks = fallbacks;
+    }
+
     /// Returns the emoji font.
     pub fn emoji(&self) -> &Font {
         &self.emoji
@@ -571,5 +707,35 @@ mod tests {
 
         // Variant falls back
         assert!(fonts.get((family, Regular, Italic)).unwrap().key() == regular);
+
+        // No fallbacks by default
+        assert!(fonts.fallbacks().is_empty());
+
+        // Set fallbacks
+        fonts.set_fallbacks(vec![family]);
+        assert!(fonts.fallbacks() == [family]);
+    }
+
+    #[test]
+    fn variations() {
+        assert!(FontWeight::Thin.value() == 100);
+        assert!(FontWeight::Regular.value() == 400);
+        assert!(FontWeight::Black.value() == 900);
+
+        // Static fonts have no axes
+        let font = Font {
+            data: vec![],
+            offset: 0,
+            key: FontKey::new(),
+        };
+        let variations = font.variations(FontWeight::Bold, FontStyle::Italic);
+        assert!(variations == FontVariations::default());
+        assert!(variations.settings().count() == 0);
+
+        let variations = FontVariations {
+            weight: Some(700),
+            slant: Some(-10),
+        };
+        assert!(variations.settings().eq([("wght", 700.), ("slnt", -10.)]));
     }
 }
@@ -1,6 +1,6 @@
 use crate::text::{
-    Advance, Context, FontFamilyKey, FontKey, FontSize, Glyph, Styles, FEATURES, HINT, SCRIPT,
-    SOURCES,
+    bidi, Advance, Context, Font, FontFamilyKey, FontFeatures, FontKey, FontSize, FontVariations,
+    Glyph, Styles, FEATURES, HINT, SCRIPT, SOURCES, SUBPIXEL_OFFSETS,
 };
 use std::{
     collections::HashMap,
@@ -9,8 +9,13 @@ use std::{
 };
 use swash::{
     scale::{image::Image, Render},
-    shape::Shaper,
-    text::cluster::{CharCluster, Parser, Status, Token},
+    shape::{Direction, Shaper},
+    text::{
+        cluster::{CharCluster, Parser, SourceRange, Status, Token},
+        Codepoint, Script,
+    },
+    zeno::Vector,
+    Charmap,
 };
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -55,6 +60,81 @@ impl Line {
         self.advance
     }
 
+    /// Returns the visual position of the caret before the character at byte `index`.
+    ///
+    /// Past the end of the line, returns the position after the last character.
+    pub fn position(&self, index: usize) -> Advance {
+        let glyphs = || self.glyphs.iter().filter(|glyph| !glyph.is_virtual());
+        let mut containing = glyphs().filter(|glyph| {
+            (glyph.range.start as usize..glyph.range.end as usize).contains(&index)
+        });
+
+        // Leading edge of the character
+        if let Some(first) = containing.next() {
+            return if first.is_rtl() {
+                let last = containing.next_back().unwrap_or(first);
+                last.offset + last.advance
+            } else {
+                first.offset
+            };
+        }
+
+        // Trailing edge of the line
+        match glyphs().max_by_key(|glyph| glyph.range.end) {
+            Some(last) if last.is_rtl() => last.offset,
+            Some(last) if last.level != 0 => last.offset + last.advance,
+            _ => self.advance,
+        }
+    }
+
+    /// Returns the visual ranges of the characters in the byte `range`, from left to right.
+    ///
+    /// Virtual text inside `range` (or at its end) is included.
+    pub fn ranges(&self, range: Range<usize>) -> impl '_ + Iterator<Item = Range<Advance>> {
+        self.segments(move |glyph| {
+            let index = glyph.range.start as usize;
+
+            if glyph.is_virtual() {
+                range.start < index && index <= range.end
+            } else {
+                range.contains(&index)
+            }
+        })
+        .filter_map(|(advance, _, selected)| selected.then_some(advance))
+    }
+
+    /// Inserts the glyphs of `text` as virtual text at the caret [`position`](Self::position)
+    /// before the character at byte `index`, after the virtual text already there.
+    ///
+    /// The inserted glyphs have an empty range at `index`, and the glyphs visually after them are
+    /// moved.
+    pub fn insert_virtual(&mut self, index: usize, text: Line) {
+        let offset = self.position(index);
+        // Glyphs are in visual order, those ending at `offset` (e.g. marks) stay before
+        let position = self
+            .glyphs
+            .iter()
+            .position(|glyph| glyph.offset + glyph.advance / 2. > offset)
+            .unwrap_or(self.glyphs.len());
+
+        for glyph in &mut self.glyphs[position..] {
+            glyph.offset += text.advance;
+        }
+
+        self.glyphs.splice(
+            position..position,
+            text.glyphs.into_iter().map(|glyph| Glyph {
+                offset: offset + glyph.offset,
+                range: SourceRange {
+                    start: index as u32,
+                    end: index as u32,
+                },
+                ..glyph
+            }),
+        );
+        self.advance += text.advance;
+    }
+
     /// Returns an iterator of contiguous `key(glyph)`.
     pub fn segments<'a, T, F>(
         &'a self,
@@ -142,6 +222,8 @@ pub struct Cluster {
     cluster: CharCluster,
     pattern: usize,
     styles: Styles,
+    script: Script,
+    level: u8,
 }
 
 impl Cluster {
@@ -153,6 +235,11 @@ impl Cluster {
         self.pattern
     }
 
+    /// Returns the bidi embedding level, odd for right-to-left.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
     pub fn styles(&self) -> Styles {
         self.styles
     }
@@ -174,6 +261,8 @@ impl std::fmt::Debug for Cluster {
             chars: &'a [swash::text::cluster::Char],
             pattern: usize,
             styles: Styles,
+            script: Script,
+            level: u8,
         }
 
         std::fmt::Debug::fmt(
@@ -181,6 +270,8 @@ impl std::fmt::Debug for Cluster {
                 chars: self.cluster.chars(),
                 pattern: self.pattern,
                 styles: self.styles,
+                script: self.script,
+                level: self.level,
             },
             f,
         )
@@ -197,25 +288,52 @@ pub struct LineShaper {
 impl LineShaper {
     /// Creates a new `LineShaper` with default `pattern` and `styles`.
     pub fn new(line: &str, pattern: usize, styles: Styles) -> Self {
+        let levels = bidi::levels(line);
+
+        // Characters common to several scripts take the script of the previous characters (or
+        // of the first script in the line)
+        let mut scripts = line
+            .char_indices()
+            .scan(None, |script, (i, char)| {
+                match char.script() {
+                    Script::Common | Script::Inherited | Script::Unknown => {}
+                    char => *script = Some(char),
+                }
+
+                Some((i, char, *script))
+            })
+            .collect::<Vec<_>>();
+        let first = scripts.iter().find_map(|(_, _, script)| *script);
+        for (_, _, script) in &mut scripts {
+            script.get_or_insert(first.unwrap_or(SCRIPT));
+        }
+
+        // Clusters are parsed in runs of the same script, for complex scripts
         let mut clusters = Vec::new();
         let mut cluster = CharCluster::default();
-        let mut parser = Parser::new(
-            SCRIPT,
-            line.char_indices().map(|(i, char)| Token {
-                ch: char,
-                offset: i as u32,
-                len: char.len_utf8() as u8,
-                info: char.into(),
-                data: Default::default(),
-            }),
-        );
 
-        while parser.next(&mut cluster) {
-            clusters.push(Cluster {
-                cluster,
-                pattern,
-                styles,
-            });
+        for run in scripts.chunk_by(|(_, _, a), (_, _, b)| a == b) {
+            let script = run[0].2.unwrap_or(SCRIPT);
+            let mut parser = Parser::new(
+                script,
+                run.iter().map(|&(i, char, _)| Token {
+                    ch: char,
+                    offset: i as u32,
+                    len: char.len_utf8() as u8,
+                    info: char.into(),
+                    data: Default::default(),
+                }),
+            );
+
+            while parser.next(&mut cluster) {
+                clusters.push(Cluster {
+                    cluster,
+                    pattern,
+                    styles,
+                    script,
+                    level: levels[cluster.range().start as usize],
+                });
+            }
         }
 
         Self { clusters, styles }
@@ -244,30 +362,59 @@ impl LineShaper {
             shaper: Shaper<'context>,
             font: FontKey,
             size: FontSize,
+            features: FontFeatures,
+            variations: FontVariations,
+            script: Script,
+            level: u8,
+            /// Cell width to align glyph advances on, for fallback fonts.
+            cell: Option<Advance>,
         }
 
         impl<'context> Prev<'context> {
             fn flush(self, line: &mut Line, styles: Styles) {
                 self.shaper.shape_with(|cluster| {
                     for glyph in cluster.glyphs {
+                        let advance = match self.cell {
+                            Some(cell) if glyph.advance != 0. => {
+                                (glyph.advance / cell).round().max(1.) * cell
+                            }
+                            _ => glyph.advance,
+                        };
+
                         line.glyphs.push(Glyph {
                             font: self.font,
                             size: self.size,
                             id: glyph.id,
+                            variations: self.variations,
                             offset: line.advance,
-                            advance: glyph.advance,
+                            advance,
                             range: cluster.source,
+                            level: self.level,
                             styles,
                         });
-                        line.advance += glyph.advance;
+                        line.advance += advance;
                     }
                 });
             }
         }
 
-        let (fonts, shape, _) = context.as_muts();
+        fn charmap<'font>(
+            charmaps: &mut HashMap<FontKey, Charmap<'font>>,
+            font: &'font Font,
+        ) -> Charmap<'font> {
+            *charmaps
+                .entry(font.key())
+                .or_insert_with(|| font.as_ref().charmap())
+        }
+
+        let Context {
+            fonts,
+            fallbacks,
+            shape,
+            ..
+        } = context;
+        let fonts = &*fonts;
         let emoji = fonts.emoji();
-        let emoji_charmap = emoji.as_ref().charmap();
         let (unligature_1, unligature_2) = if let Some((unligature_1, unligature_2)) =
             unligature_1.clone().zip(unligature_2.clone())
         {
@@ -291,7 +438,9 @@ impl LineShaper {
             (unligature_1, unligature_2)
         };
 
+        let mut charmaps = HashMap::new();
         let mut cache = HashMap::new();
+        let mut variations = HashMap::new();
         let mut prev = Option::<Prev>::None;
         let mut line = Line {
             glyphs: Vec::new(),
@@ -299,33 +448,75 @@ impl LineShaper {
             advance: 0.,
         };
 
-        // Shape the clusters reusing the shaper as long as the font is the same
-        // (or forcing new shaper to unligature)
+        // Shape the clusters reusing the shaper as long as the font, features, variations, script and
+        // direction are the same (or forcing new shaper to unligature)
         for cluster in &mut self.clusters {
+            let (weight, style) = (cluster.styles.weight, cluster.styles.style);
             let font = fonts
-                .get((family, cluster.styles.weight, cluster.styles.style))
+                .get((family, weight, style))
                 .expect("Font not found in font cache");
-            let (font_charmap, emoji_size) = *cache.entry(font.key()).or_insert_with(|| {
-                (
-                    font.as_ref().charmap(),
-                    emoji.size_for_advance(2.0 * font.advance_for_size(font_size)),
-                )
+            let (cell, emoji_size) = *cache.entry(font.key()).or_insert_with(|| {
+                let cell = font.advance_for_size(font_size);
+
+                (cell, emoji.size_for_advance(2.0 * cell))
             });
-            let selected = match cluster.cluster.map(|char| font_charmap.map(char)) {
-                Status::Discard => {
-                    cluster.cluster.map(|char| emoji_charmap.map(char));
-                    emoji.key()
-                }
-                Status::Keep => match cluster.cluster.map(|char| emoji_charmap.map(char)) {
-                    Status::Discard => {
-                        cluster.cluster.map(|char| font_charmap.map(char));
-                        font.key()
+
+            // Single characters are cached, other clusters are rare enough
+            let char = match cluster.cluster.chars() {
+                [char] => Some(char.ch),
+                _ => None,
+            };
+            let selected = match char.and_then(|char| fallbacks.get(&(font.key(), char))) {
+                Some(&selected) => selected,
+                None => {
+                    // The font, the fallback families, then emojis
+                    let chain = std::iter::once(font)
+                        .chain(
+                            fonts
+                                .fallbacks()
+                                .iter()
+                                .filter_map(|&family| fonts.get((family, weight, style))),
+                        )
+                        .chain(std::iter::once(emoji));
+                    let mut keep = None;
+                    let mut complete = None;
+
+                    for candidate in chain {
+                        let charmap = charmap(&mut charmaps, candidate);
+
+                        match cluster.cluster.map(|char| charmap.map(char)) {
+                            Status::Complete => {
+                                complete = Some(candidate.key());
+                                break;
+                            }
+                            Status::Keep => {
+                                keep.get_or_insert(candidate.key());
+                            }
+                            Status::Discard => {}
+                        }
                     }
-                    Status::Keep => emoji.key(),
-                    Status::Complete => emoji.key(),
-                },
-                Status::Complete => font.key(),
+
+                    let selected = complete.or(keep).unwrap_or(font.key());
+
+                    if let Some(char) = char {
+                        fallbacks.insert((font.key(), char), selected);
+                    }
+
+                    selected
+                }
+            };
+            let (size, cell) = match () {
+                _ if selected == font.key() => (font_size, None),
+                _ if selected == emoji.key() => (emoji_size, None),
+                _ => (font_size, Some(cell)),
             };
+            let selected_font = fonts.get(selected).expect("Font not found in font cache");
+            let features = cluster.styles.features;
+            let variations = *variations
+                .entry((selected, weight, style))
+                .or_insert_with(|| selected_font.variations(weight, style));
+            let selected_charmap = charmap(&mut charmaps, selected_font);
+            cluster.cluster.map(|char| selected_charmap.map(char));
             let force_flush = {
                 // Assuming clusters align "nicely" with unligature ranges
                 let force_push_1 = {
@@ -349,7 +540,13 @@ impl LineShaper {
             prev = Some(Prev {
                 shaper: {
                     let shaper = prev.take().and_then(|prev| {
-                        if !force_flush && prev.font == selected {
+                        if !force_flush
+                            && prev.font == selected
+                            && prev.features == features
+                            && prev.variations == variations
+                            && prev.script == cluster.script
+                            && prev.level == cluster.level
+                        {
                             Some(prev.shaper)
                         } else {
                             prev.flush(&mut line, self.styles);
@@ -359,17 +556,17 @@ impl LineShaper {
                     let mut shaper = if let Some(shaper) = shaper {
                         shaper
                     } else {
-                        let (font, size) = match () {
-                            _ if selected == font.key() => (font, font_size),
-                            _ if selected == emoji.key() => (emoji, emoji_size),
-                            _ => unreachable!(),
-                        };
-
                         shape
-                            .builder(font.as_ref())
-                            .script(SCRIPT)
+                            .builder(selected_font.as_ref())
+                            .script(cluster.script)
+                            .direction(if cluster.level % 2 == 1 {
+                                Direction::RightToLeft
+                            } else {
+                                Direction::LeftToRight
+                            })
                             .size(size as f32)
-                            .features(FEATURES)
+                            .features(FEATURES.settings().chain(features.settings()))
+                            .variations(variations.settings())
                             .build()
                     };
 
@@ -377,11 +574,12 @@ impl LineShaper {
                     shaper
                 },
                 font: selected,
-                size: match () {
-                    _ if selected == font.key() => font_size,
-                    _ if selected == emoji.key() => emoji_size,
-                    _ => unreachable!(),
-                },
+                size,
+                features,
+                variations,
+                script: cluster.script,
+                level: cluster.level,
+                cell,
             });
         }
 
@@ -419,6 +617,31 @@ impl LineShaper {
             }
         }
 
+        // Reorder glyph clusters visually, glyphs stay in logical order in their cluster
+        if line.glyphs.iter().any(|glyph| glyph.level != 0) {
+            let clusters = line
+                .glyphs
+                .chunk_by(|a, b| (a.range.start, a.range.end) == (b.range.start, b.range.end))
+                .collect::<Vec<_>>();
+            let levels = clusters
+                .iter()
+                .map(|cluster| cluster[0].level)
+                .collect::<Vec<_>>();
+            let mut advance = 0.;
+            let glyphs = bidi::reorder(&levels)
+                .into_iter()
+                .flat_map(|index| clusters[index])
+                .map(|glyph| {
+                    let offset = advance;
+                    advance += glyph.advance;
+
+                    Glyph { offset, ..*glyph }
+                })
+                .collect();
+
+            line.glyphs = glyphs;
+        }
+
         line
     }
 }
@@ -452,9 +675,13 @@ impl<'a> LineScaler<'a> {
             .builder(font.as_ref())
             .size(self.font_size as f32)
             .hint(HINT)
+            .variations(glyph.variations.settings())
             .build();
 
-        if let Some(image) = self.render.render(scaler, glyph.id) {
+        let (_, subpixel) = glyph.pixel_offset();
+        let offset = Vector::new(subpixel as f32 / SUBPIXEL_OFFSETS as f32, 0.);
+
+        if let Some(image) = self.render.offset(offset).render(scaler, glyph.id) {
             image
         } else {
             debug_assert!(false, "No image for glyph");
@@ -462,3 +689,188 @@ impl<'a> LineScaler<'a> {
         }
     }
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{tests::sfnt, FontKey, Fonts};
+
+    #[test]
+    fn bidi() {
+        // "aאב", visually "aבא"
+        let glyph = |start, end, level, offset| Glyph {
+            font: FontKey::new(),
+            size: 10,
+            id: 0,
+            variations: Default::default(),
+            offset,
+            advance: 1.,
+            range: SourceRange { start, end },
+            level,
+            styles: Default::default(),
+        };
+        let line = Line {
+            glyphs: vec![glyph(0, 1, 0, 0.), glyph(3, 5, 1, 1.), glyph(1, 3, 1, 2.)],
+            font_size: 10,
+            advance: 3.,
+        };
+
+        assert!(line.position(0) == 0.);
+        assert!(line.position(1) == 3.);
+        assert!(line.position(3) == 2.);
+        assert!(line.position(5) == 1.);
+
+        assert!(line.ranges(1..5).eq([1. ..3.]));
+        assert!(line.ranges(0..3).eq([0. ..1., 2. ..3.]));
+
+        // Between "א" and "ב" logically, visually "aבxא"
+        let mut inserted = line.clone();
+        inserted.insert_virtual(
+            3,
+            Line {
+                glyphs: vec![glyph(0, 1, 0, 0.)],
+                font_size: 10,
+                advance: 1.,
+            },
+        );
+        let offsets = |line: &Line| {
+            line.glyphs
+                .iter()
+                .map(|glyph| (glyph.range.start, glyph.offset))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(offsets(&inserted), [(0, 0.), (3, 1.), (3, 2.), (1, 3.)]);
+        assert!(inserted.glyphs[2].is_virtual());
+        assert!(inserted.advance == 4.);
+
+        // Before "א", at its leading (right) edge: "aבאx"
+        let mut inserted = line.clone();
+        inserted.insert_virtual(
+            1,
+            Line {
+                glyphs: vec![glyph(0, 1, 0, 0.)],
+                font_size: 10,
+                advance: 1.,
+            },
+        );
+        assert_eq!(offsets(&inserted), [(0, 0.), (3, 1.), (1, 2.), (1, 3.)]);
+        assert!(inserted.glyphs[3].is_virtual());
+    }
+
+    #[test]
+    fn fallbacks() {
+        let mut fonts = Fonts::new(font("😀"));
+        let regular = fonts.set(font("ac")).unwrap();
+        let fallback = fonts.set(font("bc")).unwrap();
+        let emoji = fonts.emoji().key();
+        let family = fonts.set(String::from("Regular")).unwrap();
+        let fallback_family = fonts.set(String::from("Fallback")).unwrap();
+        fonts
+            .set((family, Default::default(), Default::default(), regular))
+            .unwrap();
+        fonts
+            .set((
+                fallback_family,
+                Default::default(),
+                Default::default(),
+                fallback,
+            ))
+            .unwrap();
+        fonts.set_fallbacks(vec![fallback_family]);
+        let mut context = Context::new(fonts);
+        let shape = |context: &mut Context, line: &str| {
+            LineShaper::new(line, 0, Styles::default())
+                .shape(context, family, 10, None, None)
+                .glyphs
+                .iter()
+                .map(|glyph| glyph.font)
+                .collect::<Vec<_>>()
+        };
+
+        // The font, then the fallback families, then emojis, else the font
+        assert_eq!(
+            shape(&mut context, "abc😀d"),
+            [regular, fallback, regular, emoji, regular]
+        );
+        assert_eq!(context.fallbacks.get(&(regular, 'b')), Some(&fallback));
+        assert_eq!(context.fallbacks.get(&(regular, 'd')), Some(&regular));
+
+        // Cached per character
+        context.fallbacks.insert((regular, 'c'), fallback);
+        assert_eq!(shape(&mut context, "cb"), [fallback, fallback]);
+    }
+
+    #[test]
+    fn features() {
+        let mut fonts = Fonts::new(font(""));
+        let regular = fonts.set(font("ab")).unwrap();
+        let family = fonts.set(String::from("Features")).unwrap();
+        fonts
+            .set((family, Default::default(), Default::default(), regular))
+            .unwrap();
+        let mut context = Context::new(fonts);
+        let mut advances = |features: [&'static [(&'static str, u16)]; 2]| {
+            let mut shaper = LineShaper::new("ab", 0, Styles::default());
+            for (cluster, features) in shaper.clusters_mut().iter_mut().zip(features) {
+                cluster.styles_mut().features = FontFeatures::new(features);
+            }
+            shaper
+                .shape(&mut context, family, 10, None, None)
+                .glyphs
+                .iter()
+                .map(|glyph| glyph.advance)
+                .collect::<Vec<_>>()
+        };
+
+        // "ab" kerns in a single run
+        assert!(advances([&[], &[]]) == [4., 5.]);
+        assert!(advances([&[("ss01", 1)], &[("ss01", 1)]]) == [4., 5.]);
+        // Features reach the shaper
+        assert!(advances([&[("kern", 0)], &[("kern", 0)]]) == [5., 5.]);
+        // Runs break on feature changes
+        assert!(advances([&[], &[("ss01", 1)]]) == [5., 5.]);
+    }
+
+    /// Returns a font mapping `chars`, with advances of half an em, kerning the first two by a
+    /// tenth of an em.
+    fn font(chars: &str) -> Font {
+        let glyphs = 1 + chars.chars().count() as u16;
+        let mut cmap = [0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12, 0, 12, 0, 0].to_vec();
+        cmap.extend((16 + 12 * chars.chars().count() as u32).to_be_bytes());
+        cmap.extend([0; 4]);
+        cmap.extend((chars.chars().count() as u32).to_be_bytes());
+        for (id, char) in chars.chars().enumerate() {
+            cmap.extend((char as u32).to_be_bytes());
+            cmap.extend((char as u32).to_be_bytes());
+            cmap.extend((id as u32 + 1).to_be_bytes());
+        }
+        let mut head = vec![0; 54];
+        head[1] = 1;
+        head[12..16].copy_from_slice(&0x5f0f3cf5_u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000_u16.to_be_bytes());
+        let mut hhea = vec![0; 36];
+        hhea[1] = 1;
+        hhea[10..12].copy_from_slice(&500_u16.to_be_bytes());
+        hhea[34..36].copy_from_slice(&glyphs.to_be_bytes());
+        let hmtx = (0..glyphs).flat_map(|_| [1, 244, 0, 0]).collect();
+        let kern = [
+            0, 0, 0, 1, 0, 0, 0, 20, 0, 1, 0, 1, 0, 6, 0, 0, 0, 0, 0, 1, 0, 2, 255, 156,
+        ];
+        let mut maxp = vec![0, 0, 0x50, 0];
+        maxp.extend(glyphs.to_be_bytes());
+
+        Font::from_data(sfnt(vec![
+            (b"cmap", cmap),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"kern", kern.to_vec()),
+            (b"maxp", maxp),
+        ]))
+        .unwrap()
+    }
+}
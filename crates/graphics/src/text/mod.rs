@@ -1,12 +1,16 @@
 //! Text shaping and scaling.
 
+mod bidi;
+mod discovery;
 mod font;
 mod line;
 
+pub use discovery::*;
 pub use font::*;
 pub use line::*;
 
 use crate::types::Rgba;
+use std::collections::HashMap;
 use swash::{
     scale::{ScaleContext, Source, StrikeWith},
     shape::ShapeContext,
@@ -17,8 +21,10 @@ use swash::{
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
 const SCRIPT: Script = Script::Unknown;
-const FEATURES: &'static [(&'static str, u16)] = &[("dlig", 1), ("calt", 1)];
+const FEATURES: FontFeatures = FontFeatures::new(&[("dlig", 1), ("calt", 1)]);
 const HINT: bool = true;
+/// Horizontal subpixel offsets per pixel glyphs are rasterized at.
+const SUBPIXEL_OFFSETS: u8 = 4;
 const SOURCES: &[Source] = &[
     Source::ColorOutline(0),
     Source::ColorBitmap(StrikeWith::BestFit),
@@ -52,7 +58,19 @@ pub type Advance = f32;
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
 /// [`Glyph`] key.
-pub type GlyphKey = (FontKey, FontSize, GlyphId);
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct GlyphKey {
+    /// Font key.
+    pub font: FontKey,
+    /// Font size.
+    pub size: FontSize,
+    /// Glyph id.
+    pub id: GlyphId,
+    /// Subpixel offset, see [`Glyph::pixel_offset()`].
+    pub subpixel: u8,
+    /// Font variations.
+    pub variations: FontVariations,
+}
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                             Styles                                             //
@@ -67,6 +85,8 @@ pub struct Styles {
     pub background: Rgba,
     pub underline: bool,
     pub strike: bool,
+    /// OpenType features, on top of the default ones.
+    pub features: FontFeatures,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -82,12 +102,16 @@ pub struct Glyph {
     pub size: FontSize,
     /// Glyph id.
     pub id: GlyphId,
+    /// Font variations.
+    pub variations: FontVariations,
     /// Glyph advance offset.
     pub offset: Advance,
     /// Glyph advance.
     pub advance: Advance,
     /// Range in the underlying string.
     pub range: SourceRange,
+    /// Bidi embedding level, odd for right-to-left.
+    pub level: u8,
     /// Glyph styles.
     pub styles: Styles,
 }
@@ -95,7 +119,35 @@ pub struct Glyph {
 impl Glyph {
     /// Returns the [`GlyphKey`].
     pub fn key(&self) -> GlyphKey {
-        (self.font, self.size, self.id)
+        GlyphKey {
+            font: self.font,
+            size: self.size,
+            id: self.id,
+            subpixel: self.pixel_offset().1,
+            variations: self.variations,
+        }
+    }
+
+    /// Returns the pixel and subpixel offsets of the glyph's `offset`.
+    ///
+    /// The subpixel offset is in `1 / SUBPIXEL_OFFSETS` pixels, rounded to the closest.
+    pub fn pixel_offset(&self) -> (i32, u8) {
+        let subpixels = (self.offset * SUBPIXEL_OFFSETS as f32).round() as i32;
+
+        (
+            subpixels.div_euclid(SUBPIXEL_OFFSETS as i32),
+            subpixels.rem_euclid(SUBPIXEL_OFFSETS as i32) as u8,
+        )
+    }
+
+    /// Returns whether this glyph is right-to-left.
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+
+    /// Returns whether this glyph is virtual text (e.g. an inlay hint), with an empty range.
+    pub fn is_virtual(&self) -> bool {
+        self.range.start == self.range.end
     }
 }
 
@@ -107,6 +159,8 @@ impl Glyph {
 pub struct Context {
     /// Font cache.
     fonts: Fonts,
+    /// Fonts selected for characters missing from a font, by font and character.
+    fallbacks: HashMap<(FontKey, char), FontKey>,
     /// Shape context.
     shape: ShapeContext,
     /// Scale context.
@@ -118,6 +172,7 @@ impl Context {
     pub fn new(fonts: Fonts) -> Self {
         Self {
             fonts,
+            fallbacks: Default::default(),
             shape: Default::default(),
             scale: Default::default(),
         }
@@ -133,3 +188,50 @@ impl Context {
         (&mut self.fonts, &mut self.shape, &mut self.scale)
     }
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_offset() {
+        let glyph = |offset| Glyph {
+            font: FontKey::new(),
+            size: 10,
+            id: 0,
+            variations: FontVariations::default(),
+            offset,
+            advance: 0.,
+            range: SourceRange { start: 0, end: 0 },
+            level: 0,
+            styles: Styles::default(),
+        };
+
+        assert!(glyph(0.).pixel_offset() == (0, 0));
+        assert!(glyph(10.2).pixel_offset() == (10, 1));
+        assert!(glyph(10.5).pixel_offset() == (10, 2));
+        assert!(glyph(10.9).pixel_offset() == (11, 0));
+        assert!(glyph(-0.3).pixel_offset() == (-1, 3));
+    }
+
+    /// Returns a font file of `tables`, sorted by tag.
+    pub(crate) fn sfnt(tables: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+        let mut data = vec![0, 1, 0, 0, 0, tables.len() as u8, 0, 0, 0, 0, 0, 0];
+        let mut offset = 12 + 16 * tables.len();
+
+        for (tag, table) in &tables {
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            offset += table.len();
+        }
+
+        data.extend(tables.into_iter().flat_map(|(_, table)| table));
+        data
+    }
+}
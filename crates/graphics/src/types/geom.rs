@@ -156,6 +156,16 @@ impl Rectangle {
         }
     }
 
+    /// Returns the rectangle grown by `by` on each side.
+    pub fn grow(self, by: u32) -> Self {
+        Self {
+            top: self.top - by as i32,
+            left: self.left - by as i32,
+            width: self.width + 2 * by,
+            height: self.height + 2 * by,
+        }
+    }
+
     /// Translates and crops the rectangle to `region`.
     pub fn region(&self, region: Self) -> Option<Self> {
         (*self + region.position()).intersection(region)
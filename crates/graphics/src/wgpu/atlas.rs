@@ -4,13 +4,17 @@ use super::*;
 //                                              Item                                              //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-/// An [`Atlas`] item.
+/// A [`Packer`] item.
 #[derive(Copy, Clone, Debug)]
 struct Item<V> {
     /// The top coordinate of the item in the atlas.
     top: u32,
     /// The left coordinate of the item in the atlas.
     left: u32,
+    /// The size of the item.
+    size: Size,
+    /// The bin and shelf indices of the item.
+    shelf: (usize, usize),
     /// The value associated with the item.
     value: V,
 }
@@ -32,39 +36,332 @@ impl<V> Item<V> {
 //                                             Shelf                                              //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-/// An [`Atlas`] shelf.
-#[derive(Copy, Clone, Debug)]
-struct Shelf {
+/// A [`Packer`] shelf.
+#[derive(Clone, Debug)]
+struct Shelf<K> {
     /// The occupied width of the shelf.
     width: u32,
     /// The height of the largest item in the shelf.
     height: u32,
+    /// The last frame an item of the shelf was used in.
+    used: u64,
+    /// The keys of the items in the shelf.
+    keys: Vec<K>,
+}
+
+impl<K> Shelf<K> {
+    /// Creates a new empty shelf.
+    fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            used: 0,
+            keys: Vec::new(),
+        }
+    }
+}
+
+/// Where an item fits in a [`Packer`], in a new shelf/bin when indices are out of bounds.
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    bin: usize,
+    shelf: usize,
+    top: u32,
+    left: u32,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
-//                                             Atlas                                              //
-// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Packer                                             //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
 /// Error type for [`Atlas::insert()`].
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum AtlasError {
     /// The item already exists in the atlas.
     KeyExists,
-    /// The item is too big for the atlas' remaining space. Clear the atlas.
+    /// The atlas is full of items used in the current frame.
     OutOfSpace,
     /// The item is too big for the atlas' width/height/bin dimensions. Resize the atlas.
     WontFit,
 }
 
-/// An atlas.
+/// [`Atlas`] metrics.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct AtlasMetrics {
+    /// Lookups that found their item.
+    pub hits: u64,
+    /// Items inserted, i.e. lookups that did not find their item.
+    pub misses: u64,
+    /// Items evicted to make room for others.
+    pub evictions: u64,
+    /// Times the texture grew to make room for others.
+    pub growths: u64,
+    /// Ratio of the atlas area occupied by items.
+    pub occupancy: f32,
+}
+
+impl AtlasMetrics {
+    /// Returns the ratio of lookups that found their item.
+    pub fn hit_rate(&self) -> f32 {
+        match self.hits + self.misses {
+            0 => 0.,
+            lookups => self.hits as f32 / lookups as f32,
+        }
+    }
+}
+
+/// A shelf packer, evicting the least recently used shelves when full.
+///
+/// Items are packed in shelves, stacked in fixed-width bins.
 #[derive(Debug)]
-pub struct Atlas<K: Clone + Eq + Hash, V> {
+pub struct Packer<K: Clone + Eq + Hash, V> {
+    /// The size of the packer.
+    size: Size,
     /// The width of bins (last may be smaller).
     bin_width: u32,
-    /// The bins of the atlas.
-    bins: Vec<Vec<Shelf>>,
-    /// The items in the atlas.
+    /// The bins of the packer.
+    bins: Vec<Vec<Shelf<K>>>,
+    /// The items in the packer.
     items: HashMap<K, Item<V>>,
+    /// The current frame.
+    frame: u64,
+    /// The area occupied by items.
+    area: u64,
+    /// The metrics, but occupancy.
+    metrics: AtlasMetrics,
+}
+
+impl<K: Clone + Eq + Hash, V> Packer<K, V> {
+    /// Creates a new empty packer of `size` with `bin_width`.
+    pub fn new(size: Size, bin_width: u32) -> Self {
+        Self {
+            size,
+            bin_width: bin_width.min(size.width),
+            bins: Default::default(),
+            items: Default::default(),
+            frame: 0,
+            area: 0,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the size of the packer.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the metrics.
+    pub fn metrics(&self) -> AtlasMetrics {
+        AtlasMetrics {
+            occupancy: self.area as f32 / area(self.size).max(1) as f32,
+            ..self.metrics
+        }
+    }
+
+    /// Returns the position and value of the item for `key`, marking it used in this frame.
+    pub fn get(&mut self, key: &K) -> Option<(Position, &V)> {
+        let item = self.items.get(key)?;
+        let (bin, shelf) = item.shelf;
+
+        self.bins[bin][shelf].used = self.frame;
+        self.metrics.hits += 1;
+
+        Some((item.position(), &item.value))
+    }
+
+    /// Whether an item of `size` fits without evicting other items.
+    pub fn has_room(&self, size: Size) -> bool {
+        self.find(size).is_some()
+    }
+
+    /// Inserts an item for `key` with `size` and `value`,
+    /// evicting the least recently used shelf if needed.
+    ///
+    /// Items used in the current frame are never evicted.
+    pub fn insert(&mut self, key: K, value: V, size: Size) -> Result<(Position, &V), AtlasError> {
+        if self.items.contains_key(&key) {
+            return Err(AtlasError::KeyExists);
+        }
+
+        if !((size.width <= self.bin_width) && (size.height <= self.size.height)) {
+            return Err(AtlasError::WontFit);
+        }
+
+        let Some(slot) = self.find(size).or_else(|| self.evict(size)) else {
+            return Err(AtlasError::OutOfSpace);
+        };
+
+        if slot.bin == self.bins.len() {
+            self.bins.push(Vec::new());
+        }
+        let bin = &mut self.bins[slot.bin];
+        if slot.shelf == bin.len() {
+            bin.push(Shelf::new());
+        }
+        let shelf = &mut bin[slot.shelf];
+
+        shelf.width += size.width;
+        shelf.height = shelf.height.max(size.height);
+        shelf.used = self.frame;
+        shelf.keys.push(key.clone());
+        self.area += area(size);
+        self.metrics.misses += 1;
+
+        let item = self.items.entry(key).or_insert(Item {
+            top: slot.top,
+            left: slot.left,
+            size,
+            shelf: (slot.bin, slot.shelf),
+            value,
+        });
+
+        Ok((item.position(), &item.value))
+    }
+
+    /// Grows the packer to `size`, keeping items in place.
+    pub fn grow(&mut self, size: Size) {
+        debug_assert!(self.size.width <= size.width && self.size.height <= size.height);
+
+        self.size = size;
+        self.metrics.growths += 1;
+    }
+
+    /// Ends the current frame.
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Clears the packer.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.bins.clear();
+        self.area = 0;
+    }
+
+    /// Clears and resizes the packer.
+    pub fn clear_and_resize(&mut self, size: Size, bin_width: u32) {
+        self.clear();
+        self.size = size;
+        self.bin_width = bin_width.min(size.width);
+    }
+}
+
+/// Private.
+impl<K: Clone + Eq + Hash, V> Packer<K, V> {
+    /// Finds a slot for an item of `size`.
+    fn find(&self, Size { width, height }: Size) -> Option<Slot> {
+        let mut bin_left = 0;
+
+        for (b, bin) in self.bins.iter().enumerate() {
+            let bin_width = self.bin_width.min(self.size.width - bin_left);
+            let mut shelf_top = 0;
+
+            for (s, shelf) in bin.iter().enumerate() {
+                // Fits in shelf? (the last one is open and can grow)
+                let max_height = if s + 1 == bin.len() {
+                    self.size.height - shelf_top
+                } else {
+                    shelf.height
+                };
+
+                if (width <= bin_width - shelf.width) && (height <= max_height) {
+                    return Some(Slot {
+                        bin: b,
+                        shelf: s,
+                        top: shelf_top,
+                        left: bin_left + shelf.width,
+                    });
+                }
+
+                shelf_top += shelf.height;
+            }
+
+            // Fits in new shelf?
+            if (width <= bin_width) && (height <= self.size.height - shelf_top) {
+                return Some(Slot {
+                    bin: b,
+                    shelf: bin.len(),
+                    top: shelf_top,
+                    left: bin_left,
+                });
+            }
+
+            bin_left += bin_width;
+        }
+
+        // Fits in new bin?
+        if (width <= self.bin_width.min(self.size.width - bin_left)) && (height <= self.size.height)
+        {
+            return Some(Slot {
+                bin: self.bins.len(),
+                shelf: 0,
+                top: 0,
+                left: bin_left,
+            });
+        }
+
+        None
+    }
+
+    /// Evicts the least recently used shelf where an item of `size` fits, and finds a slot.
+    fn evict(&mut self, Size { width, height }: Size) -> Option<Slot> {
+        let mut lru = Option::<(u64, usize, usize)>::None;
+        let mut bin_left = 0;
+
+        for (b, bin) in self.bins.iter().enumerate() {
+            let bin_width = self.bin_width.min(self.size.width - bin_left);
+            let mut shelf_top = 0;
+
+            for (s, shelf) in bin.iter().enumerate() {
+                let max_height = if s + 1 == bin.len() {
+                    self.size.height - shelf_top
+                } else {
+                    shelf.height
+                };
+                let fits = (width <= bin_width) && (height <= max_height);
+                let older = lru.is_none_or(|(used, ..)| shelf.used < used);
+
+                if fits && older && shelf.used < self.frame {
+                    lru = Some((shelf.used, b, s));
+                }
+
+                shelf_top += shelf.height;
+            }
+
+            bin_left += bin_width;
+        }
+
+        let (_, bin, shelf) = lru?;
+        let shelf = &mut self.bins[bin][shelf];
+
+        for key in shelf.keys.drain(..) {
+            if let Some(item) = self.items.remove(&key) {
+                self.area -= area(item.size);
+                self.metrics.evictions += 1;
+            }
+        }
+        shelf.width = 0;
+
+        self.find(Size { width, height })
+    }
+}
+
+/// Returns the area of `size`.
+fn area(size: Size) -> u64 {
+    size.width as u64 * size.height as u64
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Atlas                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// An atlas, packing items in a GPU texture.
+///
+/// When full, the texture grows up to the device limit before evicting items.
+#[derive(Debug)]
+pub struct Atlas<K: Clone + Eq + Hash, V> {
+    /// The items packer.
+    packer: Packer<K, V>,
     /// The GPU texture.
     texture: Texture,
 }
@@ -73,9 +370,7 @@ impl<K: Clone + Eq + Hash, V> Atlas<K, V> {
     /// Creates a new empty atlas into `texture` with `bin_witdth`.
     pub fn new(texture: Texture, bin_width: u32) -> Self {
         Self {
-            bin_width: bin_width.min(texture.width()),
-            bins: Default::default(),
-            items: Default::default(),
+            packer: Packer::new(size(&texture), bin_width),
             texture,
         }
     }
@@ -85,178 +380,214 @@ impl<K: Clone + Eq + Hash, V> Atlas<K, V> {
         &self.texture
     }
 
-    /// Returns the position and value of the item for `key`.
-    pub fn get(&self, key: &K) -> Option<(Position, &V)> {
-        self.items
-            .get(&key)
-            .map(|item| (item.position(), &item.value))
+    /// Returns the metrics.
+    pub fn metrics(&self) -> AtlasMetrics {
+        self.packer.metrics()
+    }
+
+    /// Returns the position and value of the item for `key`, marking it used in this frame.
+    pub fn get(&mut self, key: &K) -> Option<(Position, &V)> {
+        self.packer.get(key)
     }
 
     /// Inserts an item for `key` with `size` and `value`.
     ///
-    /// If allocation fails, call [`Self::clear()`] before the next frame, or try a larger
-    /// atlas.
+    /// Grows the texture (see [`Self::texture()`]) or evicts items unused in this frame
+    /// if needed.
     pub fn insert(
         &mut self,
+        device: &Device,
         queue: &Queue,
         key: K,
         value: V,
         size: Size,
         bytes: &[u8],
     ) -> Result<(Position, &V), AtlasError> {
-        if self.items.contains_key(&key) {
-            return Err(AtlasError::KeyExists);
+        if !self.packer.has_room(size) {
+            self.grow(device, queue);
         }
 
-        self.try_insert(&key, value, size)?;
+        let (position, value) = self.packer.insert(key, value, size)?;
+        write(&self.texture, queue, (position, size).into(), bytes);
 
-        let item = self.items.get(&key).unwrap();
-        self.write(queue, (item.position(), size).into(), bytes);
+        Ok((position, value))
+    }
 
-        Ok((item.position(), &item.value))
+    /// Ends the current frame.
+    pub fn end_frame(&mut self) {
+        self.packer.end_frame();
     }
 
     /// Clears the atlas.
     pub fn clear(&mut self) {
-        self.items.clear();
-        self.bins.clear();
+        self.packer.clear();
     }
 
     /// Clears and resizes the atlas.
     pub fn clear_and_resize(&mut self, texture: Texture, bin: u32) {
-        self.clear();
-        self.bin_width = bin.min(texture.width());
+        self.packer.clear_and_resize(size(&texture), bin);
         self.texture = texture;
     }
 }
 
 /// Private.
 impl<K: Clone + Eq + Hash, V> Atlas<K, V> {
-    /// Tries to insert an item.
-    fn try_insert(
-        &mut self,
-        key: &K,
-        value: V,
-        Size { width, height }: Size,
-    ) -> Result<(), AtlasError> {
-        if !((width <= self.bin_width) && (height <= self.texture.height())) {
-            return Err(AtlasError::WontFit);
+    /// Doubles the texture, up to the device limit, copying its content.
+    fn grow(&mut self, device: &Device, queue: &Queue) {
+        let max = device.limits().max_texture_dimension_2d;
+        let Size { width, height } = self.packer.size();
+        let size = Size {
+            width: (2 * width).min(max),
+            height: (2 * height).min(max),
+        };
+
+        if size == self.packer.size() {
+            return;
         }
 
-        let mut bin_left = 0;
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("[Atlas] Texture"),
+            size: Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: self.texture.format(),
+            usage: self.texture.usage(),
+            view_formats: &[],
+        });
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("[Atlas] Encoder"),
+        });
+        encoder.copy_texture_to_texture(
+            self.texture.as_image_copy(),
+            texture.as_image_copy(),
+            self.texture.size(),
+        );
+        queue.submit([encoder.finish()]);
 
-        for bin in &mut self.bins {
-            let bin_width = self.bin_width.min(self.texture.width() - bin_left);
-            let mut shelf_top = 0;
+        self.texture = texture;
+        self.packer.grow(size);
+    }
+}
 
-            if let Some((open, closeds)) = bin.split_last_mut() {
-                for closed in closeds {
-                    // Fits in closed shelf?
-                    if (width <= bin_width - closed.width) && (height <= closed.height) {
-                        self.items.insert(
-                            key.clone(),
-                            Item {
-                                top: shelf_top,
-                                left: bin_left + closed.width,
-                                value,
-                            },
-                        );
-                        closed.width += width;
-
-                        return Ok(());
-                    }
-
-                    shelf_top += closed.height;
-                }
+/// Returns the size of `texture`.
+fn size(texture: &Texture) -> Size {
+    Size {
+        width: texture.width(),
+        height: texture.height(),
+    }
+}
 
-                // Fits in open shelf?
-                if (width <= bin_width - open.width)
-                    && (height <= self.texture.height() - shelf_top)
-                {
-                    self.items.insert(
-                        key.clone(),
-                        Item {
-                            top: shelf_top,
-                            left: bin_left + open.width,
-                            value,
-                        },
-                    );
-                    open.width += width;
-                    open.height = open.height.max(height);
-
-                    return Ok(());
-                }
+/// Writes `data` in `texture`.
+fn write(texture: &Texture, queue: &Queue, rectangle: Rectangle, data: &[u8]) {
+    let Rectangle {
+        top,
+        left,
+        width,
+        height,
+    } = rectangle;
+
+    queue.write_texture(
+        ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: Origin3d {
+                x: left as u32,
+                y: top as u32,
+                z: 0,
+            },
+            aspect: TextureAspect::All,
+        },
+        data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(texture.format().components() as u32 * width),
+            rows_per_image: Some(height),
+        },
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
 
-                shelf_top += open.height;
-            }
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-            // Fits in new shelf?
-            if (width <= bin_width) && (height <= self.texture.height() - shelf_top) {
-                self.items.insert(
-                    key.clone(),
-                    Item {
-                        top: shelf_top,
-                        left: bin_left,
-                        value,
-                    },
-                );
-                bin.push(Shelf { width, height });
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                return Ok(());
-            }
+    fn size(width: u32, height: u32) -> Size {
+        Size { width, height }
+    }
 
-            bin_left += bin_width;
-        }
+    fn position(top: i32, left: i32) -> Position {
+        Position { top, left }
+    }
 
-        // Fits in new bin?
-        if width <= self.bin_width.min(self.texture.width() - bin_left) {
-            self.items.insert(
-                key.clone(),
-                Item {
-                    top: 0,
-                    left: bin_left,
-                    value,
-                },
-            );
-            self.bins.push(vec![Shelf { width, height }]);
+    #[test]
+    fn pack() {
+        let mut packer = Packer::new(size(30, 20), 20);
+        let mut insert = |key, size| packer.insert(key, (), size).map(|(position, _)| position);
 
-            return Ok(());
-        }
+        // Shelves fill left to right, then stack top to bottom
+        assert!(insert('a', size(10, 10)) == Ok(position(0, 0)));
+        assert!(insert('b', size(5, 5)) == Ok(position(0, 10)));
+        assert!(insert('c', size(10, 5)) == Ok(position(10, 0)));
+
+        // Closed shelves take smaller items
+        assert!(insert('d', size(5, 5)) == Ok(position(0, 15)));
+
+        // Bins fill left to right, the last one with the remaining width
+        assert!(insert('e', size(5, 12)) == Ok(position(0, 20)));
 
-        Err(AtlasError::OutOfSpace)
+        // Errors
+        assert!(insert('a', size(1, 1)) == Err(AtlasError::KeyExists));
+        assert!(insert('g', size(21, 1)) == Err(AtlasError::WontFit));
+        assert!(insert('g', size(1, 21)) == Err(AtlasError::WontFit));
+        assert!(insert('g', size(20, 10)) == Err(AtlasError::OutOfSpace));
     }
 
-    /// Writes `data` in texture.
-    fn write(&self, queue: &Queue, rectangle: Rectangle, data: &[u8]) {
-        let Rectangle {
-            top,
-            left,
-            width,
-            height,
-        } = rectangle;
-
-        queue.write_texture(
-            ImageCopyTexture {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: Origin3d {
-                    x: left as u32,
-                    y: top as u32,
-                    z: 0,
-                },
-                aspect: TextureAspect::All,
-            },
-            &data,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(self.texture.format().components() as u32 * width),
-                rows_per_image: Some(height),
-            },
-            Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
+    #[test]
+    fn evict() {
+        let mut packer = Packer::new(size(10, 10), 10);
+
+        // Two full shelves, used in frame 0
+        packer.insert('a', (), size(10, 5)).unwrap();
+        packer.insert('b', (), size(10, 5)).unwrap();
+
+        // Items used in the current frame are not evicted
+        assert!(packer.insert('c', (), size(10, 5)) == Err(AtlasError::OutOfSpace));
+
+        // Least recently used shelf is evicted
+        packer.end_frame();
+        packer.get(&'a').unwrap();
+        packer.end_frame();
+        assert!(packer.insert('c', (), size(5, 5)).unwrap().0 == position(5, 0));
+        assert!(packer.get(&'b').is_none());
+        assert!(packer.get(&'a').is_some());
+
+        // Evicted shelves are reused
+        assert!(packer.insert('d', (), size(5, 5)).unwrap().0 == position(5, 5));
+
+        // Grown packers make room
+        assert!(!packer.has_room(size(10, 5)));
+        packer.grow(size(10, 15));
+        assert!(packer.insert('e', (), size(10, 5)).unwrap().0 == position(10, 0));
+
+        // Metrics
+        let metrics = packer.metrics();
+        assert!(metrics.hits == 2);
+        assert!(metrics.misses == 5);
+        assert!(metrics.evictions == 1);
+        assert!(metrics.growths == 1);
+        assert!(metrics.occupancy == 1.);
     }
 }
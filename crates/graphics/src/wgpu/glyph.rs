@@ -27,6 +27,43 @@ impl Type {
     const COLOR: Self = Self(1);
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         TextConstants                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// [`Constants`], with the text gamma and contrast of glyphs.
+#[derive(Copy, Clone, Debug)]
+struct TextConstants {
+    constants: Constants,
+    /// Text gamma, see [`Graphics::set_text_gamma()`].
+    gamma: f32,
+    /// Text contrast, see [`Graphics::set_text_gamma()`].
+    contrast: f32,
+}
+
+impl TextConstants {
+    fn new(config: &SurfaceConfiguration) -> Self {
+        Self {
+            constants: Constants::new(config),
+            gamma: 1.,
+            contrast: 0.,
+        }
+    }
+
+    fn as_array(&self) -> [f32; 4] {
+        let [width, height] = self.constants.as_array();
+        [width, height, self.gamma, self.contrast]
+    }
+
+    fn resize(&mut self, config: &SurfaceConfiguration) {
+        self.constants.resize(config);
+    }
+
+    fn size() -> u32 {
+        std::mem::size_of::<[f32; 4]>() as u32
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                            Instance                                            //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -78,7 +115,9 @@ impl<'a> Init<'a> {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::R8Unorm,
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC,
             view_formats: &[],
         })
     }
@@ -95,7 +134,9 @@ impl<'a> Init<'a> {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::Rgba8UnormSrgb,
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC,
             view_formats: &[],
         })
     }
@@ -177,7 +218,7 @@ impl<'a> Init<'a> {
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[PushConstantRange {
                 stages: Constants::STAGES,
-                range: 0..Constants::size(),
+                range: 0..TextConstants::size(),
             }],
         });
 
@@ -218,7 +259,7 @@ impl<'a> Init<'a> {
 /// Pipeline.
 #[derive(Debug)]
 pub struct Pipeline {
-    constants: Constants,
+    constants: TextConstants,
     layers: BTreeMap<u32, (Vec<Instance>, Range<BufferAddress>)>,
     buffer: Buffer,
     mask: Atlas<GlyphKey, Placement>,
@@ -235,9 +276,7 @@ impl Pipeline {
         let max_buffer_size = limits.max_buffer_size;
         let max_texture_dimension = limits.max_texture_dimension_2d;
 
-        let constants = Constants {
-            surface: [config.width as f32, config.height as f32],
-        };
+        let constants = TextConstants::new(config);
         let buffer = Init(device).buffer(max_buffer_size); // TODO limit size
         let mask = Atlas::new(
             Init(device).mask_texture(max_texture_dimension, config),
@@ -294,12 +333,30 @@ impl Pipeline {
         );
     }
 
+    /// Sets the text `gamma` and `contrast`.
+    pub fn set_text_gamma(&mut self, gamma: f32, contrast: f32) {
+        self.constants.gamma = gamma;
+        self.constants.contrast = contrast;
+    }
+
+    /// Clears the mask and color atlases.
+    pub fn clear(&mut self) {
+        self.mask.clear();
+        self.color.clear();
+    }
+
+    /// Returns the metrics of the mask and color atlases.
+    pub fn metrics(&self) -> (AtlasMetrics, AtlasMetrics) {
+        (self.mask.metrics(), self.color.metrics())
+    }
+
     /// Pushes a glyph `key` to be rendered for `layer` in `region`
     /// with `position`, `font_size` and `color`.
     ///
     /// Image data will be obtained through `ìmage` and only called if not in atlas already.
     pub fn push<F: FnOnce() -> Image>(
         &mut self,
+        device: &Device,
         queue: &Queue,
         layer: u32,
         region: Rectangle,
@@ -319,15 +376,15 @@ impl Pipeline {
 
         // Get or insert glyph in atlas
         let (ty, uv, placement) = if let Some((ty, uv, placement)) = {
-            let in_mask = || {
+            let mut in_mask = || {
                 self.mask
                     .get(&key)
-                    .map(|(uv, placement)| (Type::MASK, uv, placement))
+                    .map(|(uv, placement)| (Type::MASK, uv, *placement))
             };
             let in_color = || {
                 self.color
                     .get(&key)
-                    .map(|(uv, placement)| (Type::COLOR, uv, placement))
+                    .map(|(uv, placement)| (Type::COLOR, uv, *placement))
             };
 
             in_mask().or_else(in_color)
@@ -341,7 +398,9 @@ impl Pipeline {
                 Content::SubpixelMask => unimplemented!(),
             };
 
-            match atlas.insert(
+            let texture = atlas.texture().size();
+            let inserted = match atlas.insert(
+                device,
                 queue,
                 key,
                 image.placement,
@@ -351,18 +410,32 @@ impl Pipeline {
                 },
                 &image.data,
             ) {
-                Ok((uv, placement)) => (ty, uv, placement),
+                Ok((uv, placement)) => (ty, uv, *placement),
                 Err(AtlasError::KeyExists) => unreachable!("Just checked this"),
-                Err(AtlasError::OutOfSpace) => todo!("Atlas full"),
+                Err(AtlasError::OutOfSpace) => {
+                    debug_assert!(false, "Glyph atlas full of this frame's glyphs");
+                    return;
+                }
                 Err(AtlasError::WontFit) => {
                     debug_assert!(false, "Glyph does not fit the atlas");
                     return;
                 }
+            };
+
+            // Grown atlas textures must be rebound
+            if atlas.texture().size() != texture {
+                self.bind_group = Init(device).bind_group(
+                    &self.bind_group_layout,
+                    self.mask.texture(),
+                    self.color.texture(),
+                );
             }
+
+            inserted
         };
 
         // Crop to region
-        let rectangle = Rectangle::from((font_size, *placement)) + position;
+        let rectangle = Rectangle::from((font_size, placement)) + position;
         let uv = uv
             - Position {
                 top: rectangle.position().top.min(0),
@@ -419,5 +492,8 @@ impl Pipeline {
             instances.clear();
             *range = Default::default();
         }
+
+        self.mask.end_frame();
+        self.color.end_frame();
     }
 }
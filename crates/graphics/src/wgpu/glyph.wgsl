@@ -7,6 +7,10 @@ var<push_constant> CONSTANTS: Constants;
 struct Constants {
     // Surface `(width, height)` size.
     surface: vec2f,
+    // Text gamma.
+    gamma: f32,
+    // Text contrast.
+    contrast: f32,
 }
 
 fn to_clip(position: vec2f) -> vec4f {
@@ -77,6 +81,16 @@ fn color(color: vec4u) -> vec4f {
     return pow(vec4f(color) / 255.0, vec4f(2.2, 2.2, 2.2, 1.0));
 }
 
+// Adjusts mask coverage for the brightness of the (linear) text color:
+// gamma thins light text, contrast thickens dark text.
+fn coverage(mask: f32, color: vec3f) -> f32 {
+    let brightness = dot(color, vec3f(0.2126, 0.7152, 0.0722));
+    let k = CONSTANTS.contrast * (1.0 - brightness);
+    let enhanced = mask * (k + 1.0) / (mask * k + 1.0);
+
+    return pow(enhanced, mix(1.0, CONSTANTS.gamma, brightness));
+}
+
 @vertex
 fn vertex(instance: Instance) -> Fragment {
     var fragment: Fragment;
@@ -105,7 +119,7 @@ fn fragment(fragment: Fragment) -> @location(0) vec4f {
         // Mask glyph
         case 0u: {
             let mask = textureSampleLevel(MASK, SAMPLER, fragment.uv, 0.0).r;
-            return vec4f(fragment.color.rgb, fragment.color.a * mask);
+            return vec4f(fragment.color.rgb, fragment.color.a * coverage(mask, fragment.color.rgb));
         }
         // Color glyph
         case 1u: {
@@ -123,9 +123,7 @@ impl Pipeline {
         let limits = device.limits();
         let max_buffer_size = limits.max_buffer_size;
 
-        let constants = Constants {
-            surface: [config.width as f32, config.height as f32],
-        };
+        let constants = Constants::new(config);
         let buffer = Init(device).buffer(max_buffer_size); // TODO limit this size
         let layers = Default::default();
         let bind_group_layout = Init(device).bind_group_layout();
@@ -6,19 +6,24 @@ mod line;
 mod rectangle;
 
 use crate::{
+    backend::Primitives,
     muck::WithAttributes,
     text::{Context, FontSize, Glyph, GlyphKey, Line, LineHeight, LineScaler, Styles},
     types::{Position, Rectangle, Rgba, Size},
 };
 use atlas::{Atlas, AtlasError};
+
+pub use atlas::AtlasMetrics;
 use glyph::Pipeline as GlyphPipeline;
+use image::{ImageResult, RgbaImage};
 use line::Pipeline as LinePipeline;
-use rectangle::Pipeline as RectanglePipeline;
+use rectangle::{Pipeline as RectanglePipeline, Shape};
 use std::{
     collections::{BTreeMap, HashMap},
     hash::Hash,
     ops::Range,
-    sync::Arc,
+    path::Path,
+    sync::{mpsc, Arc},
     time::Duration,
 };
 use swash::{
@@ -30,14 +35,15 @@ use wgpu::{
     BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
     BlendState, Buffer, BufferAddress, BufferDescriptor, BufferUsages, Color, ColorTargetState,
     ColorWrites, CommandEncoderDescriptor, CompositeAlphaMode, Device, DeviceDescriptor, Extent3d,
-    Features, FragmentState, ImageCopyTexture, ImageDataLayout, IndexFormat, Instance, Limits,
-    LoadOp, Operations, Origin3d, PipelineLayout, PipelineLayoutDescriptor, PresentMode,
-    PrimitiveState, PrimitiveTopology, PushConstantRange, Queue, RenderPass,
-    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
-    RequestAdapterOptions, SamplerBindingType, ShaderModule, ShaderStages, StoreOp, Surface,
-    SurfaceConfiguration, Texture, TextureAspect, TextureDescriptor, TextureDimension,
-    TextureFormat, TextureSampleType, TextureUsages, TextureView, TextureViewDimension,
-    VertexAttribute, VertexBufferLayout, VertexState, VertexStepMode,
+    Features, FragmentState, ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, IndexFormat,
+    Instance, Limits, LoadOp, Maintain, MapMode, Operations, Origin3d, PipelineLayout,
+    PipelineLayoutDescriptor, PresentMode, PrimitiveState, PrimitiveTopology, PushConstantRange,
+    Queue, RenderPass, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
+    RenderPipelineDescriptor, RequestAdapterOptions, SamplerBindingType, ShaderModule,
+    ShaderStages, StoreOp, Surface, SurfaceConfiguration, Texture, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+    TextureView, TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexState,
+    VertexStepMode,
 };
 use winit::{dpi::PhysicalSize, window::Window};
 
@@ -53,6 +59,12 @@ struct Constants {
 impl Constants {
     const STAGES: ShaderStages = ShaderStages::VERTEX_FRAGMENT;
 
+    fn new(config: &SurfaceConfiguration) -> Self {
+        Self {
+            surface: [config.width as f32, config.height as f32],
+        }
+    }
+
     fn as_array(&self) -> [f32; 2] {
         [self.surface[0], self.surface[1]]
     }
@@ -70,15 +82,25 @@ impl Constants {
 //                                            Graphics                                            //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
+/// Where `Graphics` render.
+enum Target {
+    /// A window's surface, presented after rendering.
+    Surface(Surface<'static>),
+    /// An offscreen texture, read back with [`Graphics::frame`].
+    Texture(Texture),
+}
+
 /// WebGpu graphics.
 pub struct Graphics {
-    surface: Surface<'static>,
+    target: Target,
     config: SurfaceConfiguration,
     device: Device,
     queue: Queue,
     rectangle: RectanglePipeline,
     glyph: GlyphPipeline,
     line: LinePipeline,
+    /// Frames' clear color.
+    clear_color: Rgba,
 }
 
 impl Graphics {
@@ -98,18 +120,7 @@ impl Graphics {
             ..Default::default()
         }))
         .unwrap();
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &DeviceDescriptor {
-                label: Some("Device descriptor"),
-                required_features: Features::PUSH_CONSTANTS,
-                required_limits: Limits {
-                    max_push_constant_size: 128,
-                    ..Default::default()
-                },
-            },
-            None,
-        ))
-        .unwrap();
+        let (device, queue) = Self::device(&adapter).unwrap();
 
         // Configure surface
         let config = {
@@ -128,34 +139,85 @@ impl Graphics {
         };
         surface.configure(&device, &config);
 
-        // Pipelines
-        let rectangle = RectanglePipeline::new(&device, &config);
-        let glyph = GlyphPipeline::new(&device, &config);
-        let line = LinePipeline::new(&device, &config);
+        Self::with_target(Target::Surface(surface), config, device, queue)
+    }
 
-        Self {
-            surface,
+    /// Creates a new `Graphics` rendering into an offscreen texture of `width` by `height`.
+    ///
+    /// Falls back to a software adapter when no hardware adapter is available,
+    /// returns `None` when there is no adapter at all (e.g. no drivers in CI),
+    /// where [`crate::cpu::Graphics`] can render instead.
+    pub fn offscreen(width: u32, height: u32) -> Option<Self> {
+        // WGPU instance
+        let instance = Instance::new(Default::default());
+
+        // Request adapter (hardware, or software fallback), device and queue
+        let adapter =
+            pollster::block_on(instance.request_adapter(&Default::default())).or_else(|| {
+                pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
+                    force_fallback_adapter: true,
+                    ..Default::default()
+                }))
+            })?;
+        let (device, queue) = Self::device(&adapter).ok()?;
+
+        // Texture configuration, as a surface's
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            format: TextureFormat::Rgba8UnormSrgb,
+            width,
+            height,
+            present_mode: PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let texture = Self::texture(&device, &config);
+
+        Some(Self::with_target(
+            Target::Texture(texture),
             config,
             device,
             queue,
-            rectangle,
-            glyph,
-            line,
+        ))
+    }
+
+    /// Returns the size of the surface (or offscreen texture).
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.config.width,
+            height: self.config.height,
         }
     }
 
-    /// Resizes the surface to the window's logical size.
-    pub fn resize(&mut self, window: &Window) {
-        let size = window.inner_size();
-        self.config.width = size.width;
-        self.config.height = size.height;
+    /// Resizes the surface (or offscreen texture) to `width` by `height`.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.config.width = width;
+        self.config.height = height;
 
-        self.surface.configure(&self.device, &self.config);
+        match &mut self.target {
+            Target::Surface(surface) => surface.configure(&self.device, &self.config),
+            Target::Texture(texture) => *texture = Self::texture(&self.device, &self.config),
+        }
         self.rectangle.resize(&self.device, &self.config);
         self.glyph.resize(&self.device, &self.config);
         self.line.resize(&self.device, &self.config);
     }
 
+    /// Sets the color frames are cleared with (black by default).
+    pub fn set_clear_color(&mut self, color: Rgba) {
+        self.clear_color = color;
+    }
+
+    /// Sets the `gamma` and `contrast` of text (`1.` and `0.` by default).
+    ///
+    /// Blending in linear space makes light text on dark backgrounds look bolder and dark text
+    /// on light backgrounds thinner: `gamma` (`> 1.`) thins light text, `contrast` (`> 0.`)
+    /// thickens dark text.
+    pub fn set_text_gamma(&mut self, gamma: f32, contrast: f32) {
+        self.glyph.set_text_gamma(gamma, contrast);
+    }
+
     /// Returns the `Layer`ing API.
     pub fn layer(&mut self, region: Rectangle, layer: u16) -> Layer {
         Layer {
@@ -167,9 +229,15 @@ impl Graphics {
 
     /// Renders to the screen.
     pub fn render(&mut self) {
-        // Get output texture from surface
-        let output = self.surface.get_current_texture().unwrap();
-        let output_texture = output.texture.create_view(&Default::default());
+        // Get output texture from surface (or offscreen texture)
+        let (output, output_texture) = match &self.target {
+            Target::Surface(surface) => {
+                let output = surface.get_current_texture().unwrap();
+                let output_texture = output.texture.create_view(&Default::default());
+                (Some(output), output_texture)
+            }
+            Target::Texture(texture) => (None, texture.create_view(&Default::default())),
+        };
         let mut encoder = self
             .device
             .create_command_encoder(&CommandEncoderDescriptor {
@@ -184,7 +252,7 @@ impl Graphics {
                 resolve_target: None,
                 ops: Operations {
                     load: LoadOp::Clear({
-                        let color = crate::Catppuccin::default().crust;
+                        let color = self.clear_color;
                         Color {
                             r: color.r as f64 / 255.0,
                             g: color.g as f64 / 255.0,
@@ -262,13 +330,146 @@ impl Graphics {
         // Flush
         drop(render_pass);
         self.queue.submit([encoder.finish()]);
-        output.present();
+        if let Some(output) = output {
+            output.present();
+        }
 
         // Post render
         self.rectangle.post_render();
         self.glyph.post_render();
         self.line.post_render();
     }
+
+    /// Clears the glyph atlases, e.g. when font sizes change.
+    pub fn clear_glyphs(&mut self) {
+        self.glyph.clear();
+    }
+
+    /// Returns the metrics of the glyph mask and color atlases.
+    pub fn atlas_metrics(&self) -> (AtlasMetrics, AtlasMetrics) {
+        self.glyph.metrics()
+    }
+
+    /// Reads the last rendered frame back, for offscreen `Graphics` only.
+    pub fn frame(&self) -> Option<RgbaImage> {
+        let Target::Texture(texture) = &self.target else {
+            return None;
+        };
+
+        // Rows of the copy must be aligned
+        let (width, height) = (self.config.width, self.config.height);
+        let bytes_per_row = 4 * width;
+        let padded_bytes_per_row =
+            bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Frame buffer"),
+            size: (padded_bytes_per_row * height) as BufferAddress,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // Copy texture to buffer
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Frame encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit([encoder.finish()]);
+
+        // Map buffer and remove row padding
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        self.device.poll(Maintain::Wait);
+        receiver.recv().ok()?.ok()?;
+
+        let data = slice.get_mapped_range();
+        let pixels = data
+            .chunks(padded_bytes_per_row as usize)
+            .flat_map(|row| &row[..bytes_per_row as usize])
+            .copied()
+            .collect();
+        drop(data);
+        buffer.unmap();
+
+        RgbaImage::from_raw(width, height, pixels)
+    }
+
+    /// Saves the last rendered frame as a PNG at `path`, for offscreen `Graphics` only.
+    pub fn save_frame(&self, path: impl AsRef<Path>) -> Option<ImageResult<()>> {
+        self.frame().map(|frame| frame.save(path))
+    }
+}
+
+/// Private.
+impl Graphics {
+    fn with_target(
+        target: Target,
+        config: SurfaceConfiguration,
+        device: Device,
+        queue: Queue,
+    ) -> Self {
+        // Pipelines
+        let rectangle = RectanglePipeline::new(&device, &config);
+        let glyph = GlyphPipeline::new(&device, &config);
+        let line = LinePipeline::new(&device, &config);
+
+        Self {
+            target,
+            config,
+            device,
+            queue,
+            rectangle,
+            glyph,
+            line,
+            clear_color: Rgba::BLACK,
+        }
+    }
+
+    fn device(adapter: &wgpu::Adapter) -> Result<(Device, Queue), wgpu::RequestDeviceError> {
+        pollster::block_on(adapter.request_device(
+            &DeviceDescriptor {
+                label: Some("Device descriptor"),
+                required_features: Features::PUSH_CONSTANTS,
+                required_limits: Limits {
+                    max_push_constant_size: 128,
+                    ..Default::default()
+                },
+            },
+            None,
+        ))
+    }
+
+    fn texture(device: &Device, config: &SurfaceConfiguration) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            label: Some("Offscreen texture"),
+            size: Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        })
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -324,9 +525,61 @@ impl<'graphics> Draw<'graphics> {
             .into()
             .unwrap_or_else(|| Rectangle::from((Position::default(), self.size())));
 
-        self.graphics
-            .rectangle
-            .push(self.layer, self.region, rectangle, color);
+        self.graphics.rectangle.push(
+            self.layer,
+            self.region,
+            rectangle,
+            Shape::default(),
+            color,
+            Rgba::TRANSPARENT,
+        );
+    }
+
+    /// Draws a rectangle with corners rounded by `radius`, and a `border` inside its edges.
+    pub fn rounded_rectangle(
+        &mut self,
+        rectangle: impl Into<Option<Rectangle>>,
+        radius: u32,
+        color: Rgba,
+        border: u32,
+        border_color: Rgba,
+    ) {
+        let rectangle = rectangle
+            .into()
+            .unwrap_or_else(|| Rectangle::from((Position::default(), self.size())));
+        let shape = Shape {
+            radius,
+            border,
+            blur: 0,
+        };
+
+        self.graphics.rectangle.push(
+            self.layer,
+            self.region,
+            rectangle,
+            shape,
+            color,
+            border_color,
+        );
+    }
+
+    /// Draws the shadow of a rectangle with corners rounded by `radius`, blurred over `blur`
+    /// pixels on each side of its edges.
+    pub fn shadow(&mut self, rectangle: Rectangle, radius: u32, blur: u32, color: Rgba) {
+        let shape = Shape {
+            radius,
+            border: 0,
+            blur,
+        };
+
+        self.graphics.rectangle.push(
+            self.layer,
+            self.region,
+            rectangle,
+            shape,
+            color,
+            Rgba::TRANSPARENT,
+        );
     }
 
     /// Draws a glyph.
@@ -339,6 +592,7 @@ impl<'graphics> Draw<'graphics> {
         image: F,
     ) {
         self.graphics.glyph.push(
+            &self.graphics.device,
             &self.graphics.queue,
             self.layer,
             self.region,
@@ -358,41 +612,7 @@ impl<'graphics> Draw<'graphics> {
         line: &Line,
         line_height: LineHeight,
     ) {
-        //
-        // Add backgrounds
-        //
-
-        for (Range { start, end }, _, background) in line.segments(|glyph| glyph.styles.background)
-        {
-            self.rectangle(
-                Rectangle {
-                    top: position.top,
-                    left: position.left + start as i32,
-                    width: (end - start) as u32,
-                    height: line_height,
-                },
-                background,
-            );
-        }
-
-        //
-        // Add glyphs
-        //
-
-        let mut scaler = line.scaler(context);
-
-        for glyph in line.glyphs() {
-            self.glyph(
-                Position {
-                    top: position.top,
-                    left: position.left + glyph.offset.round() as i32,
-                },
-                line.font_size(),
-                glyph.key(),
-                glyph.styles.foreground,
-                || scaler.render(&glyph),
-            );
-        }
+        Primitives::glyphs(self, context, position, line, line_height);
     }
 
     /// Draws a polyline.
@@ -409,3 +629,88 @@ impl<'graphics> Draw<'graphics> {
             .push(self.layer, self.region, points, true);
     }
 }
+
+impl<'graphics> Primitives for Draw<'graphics> {
+    fn rectangle(&mut self, rectangle: Rectangle, color: Rgba) {
+        Draw::rectangle(self, rectangle, color);
+    }
+
+    fn glyph<F: FnOnce() -> Image>(
+        &mut self,
+        position: Position,
+        font_size: FontSize,
+        key: GlyphKey,
+        color: Rgba,
+        image: F,
+    ) {
+        Draw::glyph(self, position, font_size, key, color, image);
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "needs a graphics adapter, run with `cargo test -- --ignored`"]
+    fn offscreen() {
+        let mut graphics = Graphics::offscreen(10, 10).expect("Graphics adapter");
+        graphics.resize(20, 10);
+        assert!(
+            graphics.size()
+                == Size {
+                    width: 20,
+                    height: 10
+                }
+        );
+        let color = Rgba::new(255, 0, 0, 255);
+
+        {
+            let mut layer = graphics.layer(
+                Rectangle::from((
+                    Position::default(),
+                    Size {
+                        width: 20,
+                        height: 10,
+                    },
+                )),
+                0,
+            );
+            let mut draw = layer.draw(None, 0);
+
+            draw.rectangle(
+                Rectangle {
+                    top: 0,
+                    left: 0,
+                    width: 10,
+                    height: 10,
+                },
+                color,
+            );
+            draw.rounded_rectangle(
+                Rectangle {
+                    top: 0,
+                    left: 10,
+                    width: 10,
+                    height: 10,
+                },
+                4,
+                Rgba::GREEN,
+                1,
+                Rgba::BLUE,
+            );
+        }
+        graphics.render();
+
+        let frame = graphics.frame().unwrap();
+        assert!(frame.dimensions() == (20, 10));
+        assert!(frame.get_pixel(5, 5).0 == [255, 0, 0, 255]);
+        assert!(frame.get_pixel(15, 5).0 == [0, 255, 0, 255]);
+        assert!(frame.get_pixel(10, 5).0 == [0, 0, 255, 255]);
+        assert!(![[0, 255, 0, 255], [0, 0, 255, 255]].contains(&frame.get_pixel(10, 0).0));
+    }
+}
@@ -10,18 +10,40 @@ macro_rules! label {
 //                                            Instance                                            //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-crate::muck!(unsafe Instance => Instance: [Position, Size, Rgba]);
+crate::muck!(unsafe Shape => Uint32x3);
+
+/// Rectangle shape, in pixels.
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Shape {
+    /// Corner radius.
+    pub radius: u32,
+    /// Border width, inside the rectangle.
+    pub border: u32,
+    /// Blur width on each side of the edges, for shadows.
+    pub blur: u32,
+}
+
+crate::muck!(unsafe Instance => Instance: [Position, Size, Position, Size, Shape, Rgba, Rgba]);
 
 /// Instance.
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 struct Instance {
-    /// Rectangle position.
+    /// Quad position (the rectangle cropped to its region).
     position: Position,
-    /// Rectangle size.
+    /// Quad size.
     size: Size,
+    /// Rectangle position.
+    rectangle_position: Position,
+    /// Rectangle size.
+    rectangle_size: Size,
+    /// Rectangle shape.
+    shape: Shape,
     /// Rectangle color.
     color: Rgba,
+    /// Border color.
+    border_color: Rgba,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -121,9 +143,7 @@ impl Pipeline {
         let limits = device.limits();
         let max_buffer_size = limits.max_buffer_size;
 
-        let constants = Constants {
-            surface: [config.width as f32, config.height as f32],
-        };
+        let constants = Constants::new(config);
         let buffer = Init(device).buffer(max_buffer_size); // TODO limit this size
         let layers = Default::default();
         let bind_group_layout = Init(device).bind_group_layout();
@@ -153,20 +173,35 @@ impl Pipeline {
         self.constants.resize(config);
     }
 
-    /// Pushes a `rectangle` to be rendered for `layer` in `region` with `color`.
-    pub fn push(&mut self, layer: u32, region: Rectangle, rectangle: Rectangle, color: Rgba) {
-        if !color.is_visible() {
+    /// Pushes a `rectangle` to be rendered for `layer` in `region` with `shape`, `color` and
+    /// `border_color`.
+    pub fn push(
+        &mut self,
+        layer: u32,
+        region: Rectangle,
+        rectangle: Rectangle,
+        shape: Shape,
+        color: Rgba,
+        border_color: Rgba,
+    ) {
+        if !color.is_visible() && !(shape.border != 0 && border_color.is_visible()) {
             return;
         }
 
-        let Some(rectangle) = rectangle.region(region) else {
+        // Shapes are not cropped, only their quads
+        let rectangle = rectangle + region.position();
+        let Some(quad) = rectangle.grow(shape.blur).intersection(region) else {
             return;
         };
 
         self.layers.entry(layer).or_default().0.push(Instance {
-            position: rectangle.position(),
-            size: rectangle.size(),
+            position: quad.position(),
+            size: quad.size(),
+            rectangle_position: rectangle.position(),
+            rectangle_size: rectangle.size(),
+            shape,
             color,
+            border_color,
         });
     }
 
@@ -20,12 +20,20 @@ fn to_clip(position: vec2f) -> vec4f {
 
 struct Instance {
     @builtin(vertex_index) index: u32,
-    // Rectangle `(top, left)` position.
+    // Quad `(top, left)` position.
     @location(0) position: vec2i,
-    // Rectangle `(width, height)` size.
+    // Quad `(width, height)` size.
     @location(1) size: vec2u,
+    // Rectangle `(top, left)` position.
+    @location(2) rectangle_position: vec2i,
+    // Rectangle `(width, height)` size.
+    @location(3) rectangle_size: vec2u,
+    // Rectangle `(radius, border, blur)` shape.
+    @location(4) shape: vec3u,
     // Rectangle sRGBA color.
-    @location(2) color: vec4u,
+    @location(5) color: vec4u,
+    // Border sRGBA color.
+    @location(6) border_color: vec4u,
 }
 
 fn position(index: u32, position: vec2i, size: vec2u) -> vec2f {
@@ -60,9 +68,16 @@ fn color(color: vec4u) -> vec4f {
 
 @vertex
 fn vertex(instance: Instance) -> Fragment {
+    let top = f32(instance.rectangle_position.x);
+    let left = f32(instance.rectangle_position.y);
+    let size = vec2f(instance.rectangle_size);
+
     var fragment: Fragment;
     fragment.position = to_clip(position(instance.index, instance.position, instance.size));
     fragment.color = color(instance.color);
+    fragment.border_color = color(instance.border_color);
+    fragment.rectangle = vec4f(left, top, left + size.x, top + size.y);
+    fragment.shape = vec3f(instance.shape);
 
     return fragment;
 }
@@ -74,9 +89,46 @@ fn vertex(instance: Instance) -> Fragment {
 struct Fragment {
     @builtin(position) position: vec4f,
     @location(0) color: vec4f,
+    @location(1) border_color: vec4f,
+    // Rectangle `(left, top, right, bottom)`.
+    @location(2) @interpolate(flat) rectangle: vec4f,
+    // Rectangle `(radius, border, blur)` shape.
+    @location(3) @interpolate(flat) shape: vec3f,
+}
+
+// Signed distance from `point` to the rounded `rectangle`, negative inside.
+fn sdf(point: vec2f, rectangle: vec4f, radius: f32) -> f32 {
+    let center = (rectangle.xy + rectangle.zw) / 2.0;
+    let half = (rectangle.zw - rectangle.xy) / 2.0;
+    let r = min(radius, min(half.x, half.y));
+    let q = abs(point - center) - half + r;
+
+    return length(max(q, vec2f(0.0))) + min(max(q.x, q.y), 0.0) - r;
 }
 
 @fragment
 fn fragment(fragment: Fragment) -> @location(0) vec4f {
-    return fragment.color;
+    let radius = fragment.shape.x;
+    let border = fragment.shape.y;
+    let blur = fragment.shape.z;
+    let distance = sdf(fragment.position.xy, fragment.rectangle, radius);
+
+    // Shadow
+    if blur > 0.0 {
+        let alpha = 1.0 - smoothstep(-blur, blur, distance);
+        return vec4f(fragment.color.rgb, fragment.color.a * alpha);
+    }
+
+    // Border over fill, premultiplied
+    let outer = clamp(0.5 - distance, 0.0, 1.0);
+    let inner = clamp(0.5 - distance - border, 0.0, 1.0);
+    let color = vec4f(fragment.color.rgb * fragment.color.a, fragment.color.a) * inner
+        + vec4f(fragment.border_color.rgb * fragment.border_color.a, fragment.border_color.a)
+        * (outer - inner);
+
+    if color.a <= 0.0 {
+        discard;
+    }
+
+    return vec4f(color.rgb / color.a, color.a);
 }
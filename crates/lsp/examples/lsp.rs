@@ -34,6 +34,9 @@ async fn main() {
                 ServerMessage::ServerRequest(request) => {
                     dbg!(request);
                 }
+                message => {
+                    dbg!(message);
+                }
             }
         }
     });
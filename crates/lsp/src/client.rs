@@ -1,26 +1,30 @@
 use crate::{
-    notifications::NotificationTrait,
+    notifications::{CancelRequest, NotificationTrait},
     requests::RequestTrait,
-    structures::{InitializeResult, ProgressParams},
+    structures::{
+        CancelParams, CancelParamsId, InitializeResult, ProgressParams, WorkDoneProgressBegin,
+        WorkDoneProgressEnd, WorkDoneProgressReport,
+    },
     transport::{Message, Notification, Request, Response},
     type_aliases::{LspAny, ProgressToken},
-    Error, Id, Integer, ServerNotification, ServerRequest,
+    Code, Error, Id, Integer, ServerNotification, ServerRequest, UInteger,
 };
 use futures::Future;
+use serde::Deserialize;
 use serde_json::Value;
 use std::{
     collections::{HashMap, HashSet},
     io,
-    str::FromStr,
+    marker::PhantomData,
+    pin::Pin,
     sync::{Arc, Mutex},
-    task::{Poll, Waker},
+    task::{Context, Poll, Waker},
     time::Duration,
 };
 use tokio::{
     io::{AsyncBufRead, AsyncWrite},
-    process::ChildStdin,
     sync::{
-        mpsc::{UnboundedReceiver, UnboundedSender},
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
         watch::{channel, Receiver, Sender},
     },
     task::JoinHandle,
@@ -37,22 +41,103 @@ pub type ServerMessageReceiver = UnboundedReceiver<ServerMessage>;
 pub enum ServerMessage {
     ServerNotification(ServerNotification),
     ServerRequest(ServerRequest),
+    /// A notification of a method we do not know of, e.g. `experimental/serverStatus`.
+    UnknownNotification {
+        method: String,
+        params: Option<Value>,
+    },
+    /// A request of a method we do not know of, already answered with `MethodNotFound`.
+    UnknownRequest {
+        id: Id,
+        method: String,
+        params: Option<Value>,
+    },
+    Error(ProtocolError),
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// An error in the received messages.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ProtocolError {
+    /// The connection is broken: no more messages will be received.
+    Transport(String),
+    /// A message is not valid JSON-RPC, and was skipped (servers answer with `ParseError`).
+    InvalidMessage(String),
+    /// A message of a known method has invalid parameters, and was skipped (requests are
+    /// answered with `InvalidParams`).
+    InvalidParams { method: String, error: String },
+    /// A response does not match any pending request.
+    UnexpectedResponse(Option<Id>),
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                           LspClient                                            //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-type State = HashMap<Id, (Option<Response<Value, Value>>, Option<Waker>)>;
+#[derive(Default)]
+pub(crate) struct State {
+    /// Pending requests, with their response once received.
+    pending: HashMap<Id, (Option<Response<Value, Value>>, Option<Waker>)>,
+    /// Cancelled requests, whose responses are ignored.
+    cancelled: HashSet<Id>,
+    /// Whether the connection is broken: pending requests fail.
+    closed: bool,
+}
+
+impl State {
+    pub(crate) fn insert(&mut self, id: Id) -> io::Result<()> {
+        if self.closed {
+            return Err(connection_closed());
+        }
 
-pub struct LspClient<W: AsyncWrite + Unpin = ChildStdin> {
+        self.pending.insert(id, (None, None));
+        Ok(())
+    }
+
+    /// Fails pending requests, waking the tasks waiting for them.
+    pub(crate) fn close(&mut self) {
+        self.closed = true;
+
+        for (_, waker) in self.pending.values_mut() {
+            if let Some(waker) = waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The error of requests on a broken connection.
+fn connection_closed() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "Connection closed")
+}
+
+/// The writer of clients in [`LspClients`](crate::LspClients).
+pub type LspWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+pub struct LspClient<W: AsyncWrite + Unpin = LspWriter> {
     id: Integer,
-    writer: W,
+    /// Shared with the reader task, which answers requests it cannot deserialize.
+    writer: Arc<tokio::sync::Mutex<W>>,
+    /// Shares the writer.
+    responder: LspResponder<W>,
     state: Arc<Mutex<State>>,
     handle: JoinHandle<()>,
-    work_done_receiver: Receiver<Option<WorkDone>>,
-    initialize_result_sender: Sender<Option<InitializeResult>>,
-    initialize_result_receiver: Receiver<Option<InitializeResult>>,
+    /// Sends `$/cancelRequest` for cancelled [`PendingResponse`]s.
+    cancel_sender: UnboundedSender<Id>,
+    cancel_handle: JoinHandle<()>,
+    progress_receiver: Receiver<Vec<ProgressState>>,
+    initialization_sender: Sender<Initialization>,
+    initialization_receiver: Receiver<Initialization>,
+}
+
+/// Whether a client is initialized.
+#[derive(Clone)]
+enum Initialization {
+    Pending,
+    Initialized(Box<InitializeResult>),
+    /// The server could not be initialized: the client is dead.
+    Failed,
 }
 
 impl<W: AsyncWrite + Unpin> LspClient<W> {
@@ -60,122 +145,182 @@ impl<W: AsyncWrite + Unpin> LspClient<W> {
         mut reader: R,
         writer: W,
         server_message_sender: ServerMessageSender,
-    ) -> Self {
-        let (work_done_sender, work_done_receiver) = channel(None);
-        let (initialize_result_sender, initialize_result_receiver) = channel(None);
-        let state = Arc::new(Mutex::new(State::new()));
+    ) -> Self
+    where
+        W: Send + 'static,
+    {
+        let (progress_sender, progress_receiver) = channel(Vec::new());
+        let (initialization_sender, initialization_receiver) = channel(Initialization::Pending);
+        let writer = Arc::new(tokio::sync::Mutex::new(writer));
+        let state = Arc::new(Mutex::new(State::default()));
+        let (cancel_sender, mut cancel_receiver) = unbounded_channel::<Id>();
+        let cancel_handle = tokio::spawn({
+            let writer = writer.clone();
+
+            async move {
+                while let Some(id) = cancel_receiver.recv().await {
+                    let id = match id {
+                        Id::Integer(id) => CancelParamsId::Integer(id),
+                        Id::String(id) => CancelParamsId::String(id.into_owned()),
+                    };
+                    let notification = Notification::new(
+                        CancelRequest::METHOD.into(),
+                        CancelRequest::params(CancelParams { id }),
+                    );
+
+                    if notification.write(&mut *writer.lock().await).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
         let handle = tokio::spawn({
+            let writer = writer.clone();
             let state = state.clone();
 
             async move {
+                let mut progresses = Progresses::default();
+
                 loop {
-                    let message = match Message::<Value, Value>::read(&mut reader).await {
-                        Ok(message) => message,
+                    let message = match Message::<Value, Value>::try_read(&mut reader).await {
+                        Ok(Ok(message)) => message,
+                        Ok(Err(err)) => {
+                            let error = ProtocolError::InvalidMessage(err.to_string());
+
+                            if server_message_sender
+                                .send(ServerMessage::Error(error))
+                                .is_err()
+                            {
+                                break;
+                            }
+
+                            continue;
+                        }
                         Err(err) => {
-                            dbg!(err);
+                            let error = ProtocolError::Transport(err.to_string());
+                            let _ = server_message_sender.send(ServerMessage::Error(error));
                             break;
                         }
                     };
 
-                    match message {
+                    let message = match message {
                         Message::Request(request) => {
-                            let request = ServerMessage::ServerRequest(
-                                ServerRequest::deserialize(request)
-                                    .expect("ServerRequest::deserialize"),
-                            );
-
-                            server_message_sender
-                                .send(request)
-                                .expect("Send server message");
-                        }
-                        Message::Notification(notification) => {
-                            let notification = ServerNotification::deserialize(notification)
-                                .expect("ServerNotification::deserialize");
+                            let id = request.id.clone();
+                            let (message, error) = server_request(request);
 
-                            match &notification {
-                                ServerNotification::Progress(progress) => Some(progress),
-                                _ => None,
+                            if let Some(error) = error {
+                                let response = Response::<(), ()>::with_error(Some(id), error);
+
+                                if let Err(err) = response.write(&mut *writer.lock().await).await {
+                                    let error = ProtocolError::Transport(err.to_string());
+                                    let _ = server_message_sender.send(ServerMessage::Error(error));
+                                    break;
+                                }
                             }
-                            .and_then(|progress| WorkDone::try_from(progress).ok())
-                            .map(|work_done| {
-                                work_done_sender
-                                    .send(Some(work_done))
-                                    .expect("Work done receiver")
-                            });
-
-                            server_message_sender
-                                .send(ServerMessage::ServerNotification(notification))
-                                .expect("Send server message");
-                        }
-                        Message::Response(response) => {
-                            let id = response.id.as_ref().expect("Response id");
-                            let mut state = state.lock().expect("State lock");
-                            let (response_slot, waker) = state.get_mut(id).expect("Request state");
 
-                            debug_assert!(response_slot.is_none(), "Got two responses");
-                            *response_slot = Some(response);
+                            message
+                        }
+                        Message::Notification(notification) => server_notification(notification),
+                        Message::Response(response) => match receive_response(&state, response) {
+                            Ok(()) => continue,
+                            Err(error) => ServerMessage::Error(error),
+                        },
+                    };
 
-                            if let Some(waker) = waker.take() {
-                                waker.wake();
-                            }
+                    if let ServerMessage::ServerNotification(ServerNotification::Progress(params)) =
+                        &message
+                    {
+                        if progresses.update(params) {
+                            progress_sender.send_replace(progresses.to_vec());
                         }
                     }
+
+                    if server_message_sender.send(message).is_err() {
+                        break;
+                    }
                 }
+
+                // No more responses
+                state.lock().unwrap().close();
             }
         });
 
         Self {
             id: 0,
+            responder: LspResponder {
+                writer: writer.clone(),
+            },
             writer,
             state,
             handle,
-            work_done_receiver,
-            initialize_result_sender,
-            initialize_result_receiver,
+            cancel_sender,
+            cancel_handle,
+            progress_receiver,
+            initialization_sender,
+            initialization_receiver,
         }
     }
 
     pub fn init(&self, initialize_result: InitializeResult) {
-        self.initialize_result_sender
-            .send(Some(initialize_result))
-            .expect("Initialize result receiver");
+        self.initialization_sender
+            .send_replace(Initialization::Initialized(Box::new(initialize_result)));
     }
 
-    pub async fn initied(&mut self) -> InitializeResult {
-        self.initialize_result_receiver
-            .wait_for(|initialize_result| initialize_result.is_some())
-            .await
-            .expect("Initialize result sender")
-            .clone()
-            .unwrap()
+    /// Marks the client as dead, after its server failed to initialize.
+    pub fn fail(&self) {
+        self.initialization_sender
+            .send_replace(Initialization::Failed);
     }
 
-    pub async fn wait_for_work_done(&mut self) {
-        const DELAY_MS: u64 = 1000;
+    /// Resolves once [`init`](Self::init)ed, or with `None` once [`fail`](Self::fail)ed.
+    ///
+    /// Does not borrow `self`: the client can be used (or released) while waiting.
+    pub fn initied(&self) -> impl Future<Output = Option<InitializeResult>> + Send + 'static {
+        let mut initialization_receiver = self.initialization_receiver.clone();
 
-        let mut tokens = HashSet::<Token>::new();
+        async move {
+            let initialization = initialization_receiver
+                .wait_for(|initialization| !matches!(initialization, Initialization::Pending))
+                .await
+                .ok()?;
 
-        loop {
-            self.work_done_receiver
-                .wait_for(|work_done| {
-                    if let Some(work_done) = work_done {
-                        match work_done.kind {
-                            Kind::Begin | Kind::Report => tokens.insert(work_done.token.clone()),
-                            Kind::End => tokens.remove(&work_done.token),
-                        };
-                    }
+            match &*initialization {
+                Initialization::Initialized(initialize_result) => {
+                    Some((**initialize_result).clone())
+                }
+                Initialization::Pending | Initialization::Failed => None,
+            }
+        }
+    }
 
-                    tokens.is_empty()
-                })
-                .await
-                .expect("Work done sender");
+    /// Ongoing work done progresses, in the order they began.
+    pub fn progress(&self) -> Receiver<Vec<ProgressState>> {
+        self.progress_receiver.clone()
+    }
 
-            tokio::time::sleep(Duration::from_millis(DELAY_MS)).await;
+    /// Waits until no work done progress is ongoing, and none begins for a second.
+    ///
+    /// Does not borrow `self`, as [`initied`](Self::initied).
+    pub fn wait_for_work_done(&self) -> impl Future<Output = ()> + Send + 'static {
+        const DELAY_MS: u64 = 1000;
 
-            if let Ok(true) = self.work_done_receiver.has_changed() {
-                continue;
-            } else {
-                break;
+        let mut progress_receiver = self.progress_receiver.clone();
+
+        async move {
+            loop {
+                if progress_receiver
+                    .wait_for(|progresses| progresses.is_empty())
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+
+                tokio::time::sleep(Duration::from_millis(DELAY_MS)).await;
+
+                if progress_receiver.borrow().is_empty() {
+                    return;
+                }
             }
         }
     }
@@ -189,77 +334,246 @@ impl<W: AsyncWrite + Unpin> LspClient<W> {
     }
 
     pub fn response(&mut self) -> LspClientResponse<W> {
-        LspClientResponse { client: self }
+        self.responder.response()
     }
-}
 
-/// Private.
-impl<W: AsyncWrite + Unpin> LspClient<W> {
-    pub(crate) async fn send_notification<T: NotificationTrait>(
+    /// Answers server requests without borrowing the client, e.g. while it is locked.
+    pub fn responder(&self) -> LspResponder<W> {
+        self.responder.clone()
+    }
+
+    /// Sends a notification of type `T`, see [`notification`](Self::notification).
+    pub async fn send_notification<T: NotificationTrait>(
         &mut self,
         params: T::Params,
     ) -> io::Result<()> {
         Notification::new(T::METHOD.into(), T::params(params))
-            .write(&mut self.writer)
+            .write(&mut *self.writer.lock().await)
             .await
     }
 
-    pub(crate) async fn send_request<T: RequestTrait>(
+    /// Sends a request of type `T`, see [`request`](Self::request).
+    pub async fn send_request<T: RequestTrait>(
         &mut self,
         params: T::Params,
-    ) -> io::Result<impl '_ + Future<Output = io::Result<Result<T::Result, Error<T::Error>>>>> {
+    ) -> io::Result<PendingResponse<T>> {
         let id = Id::Integer({
             let id = self.id;
             self.id += 1;
             id
         });
 
-        self.state.lock().unwrap().insert(id.clone(), (None, None));
+        self.state.lock().unwrap().insert(id.clone())?;
 
         Request::new(id.clone(), T::METHOD.into(), T::params(params))
-            .write(&mut self.writer)
+            .write(&mut *self.writer.lock().await)
             .await?;
 
-        Ok(futures::future::poll_fn(move |cx| {
-            let mut state = self.state.lock().unwrap();
-            let Some((response, waker)) = state.get_mut(&id) else {
-                // Was polled to completion already...
-                return Poll::Pending;
-            };
+        // Does not borrow `self`: the client can be used while waiting for the response
+        Ok(PendingResponse::new(
+            self.state.clone(),
+            id,
+            Some(self.cancel_sender.clone()),
+        ))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Drop for LspClient<W> {
+    fn drop(&mut self) {
+        self.handle.abort();
+        self.cancel_handle.abort();
+        self.state.lock().unwrap().close();
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-            if let Some(response) = response.take() {
-                state.remove(&id);
+/// Deserializes a server `request`, with the error to answer it with if that fails.
+fn server_request(request: Request<Value>) -> (ServerMessage, Option<Error<()>>) {
+    let method = request.method.to_string();
 
-                Poll::Ready(T::deserialize_response(response))
-            } else {
-                if let Some(waker) = waker {
-                    waker.clone_from(cx.waker());
-                } else {
-                    *waker = Some(cx.waker().clone());
-                }
+    if !ServerRequest::METHODS.contains(&method.as_str()) {
+        let error = Error::new(Code::MethodNotFound, "Method not found".into(), None);
+        let message = ServerMessage::UnknownRequest {
+            id: request.id,
+            method,
+            params: request.params,
+        };
 
-                Poll::Pending
-            }
-        }))
+        return (message, Some(error));
     }
 
-    pub(crate) async fn send_response<T: RequestTrait>(
-        &mut self,
-        id: Option<Id>,
-        data: Result<T::Result, Error<T::Error>>,
-    ) -> io::Result<()> {
-        match data {
-            Ok(result) => Response::with_result(id, result),
-            Err(error) => Response::with_error(id, T::error(error)),
+    match ServerRequest::deserialize(request) {
+        Ok(request) => (ServerMessage::ServerRequest(request), None),
+        Err(err) => {
+            let error = Error::new(Code::InvalidParams, err.to_string().into(), None);
+            let message = ServerMessage::Error(ProtocolError::InvalidParams {
+                method,
+                error: err.to_string(),
+            });
+
+            (message, Some(error))
         }
-        .write(&mut self.writer)
-        .await
     }
 }
 
-impl<W: AsyncWrite + Unpin> Drop for LspClient<W> {
+/// Deserializes a server `notification`.
+fn server_notification(notification: Notification<Value>) -> ServerMessage {
+    let method = notification.method.to_string();
+
+    if !ServerNotification::METHODS.contains(&method.as_str()) {
+        return ServerMessage::UnknownNotification {
+            method,
+            params: notification.params,
+        };
+    }
+
+    match ServerNotification::deserialize(notification) {
+        Ok(notification) => ServerMessage::ServerNotification(notification),
+        Err(err) => ServerMessage::Error(ProtocolError::InvalidParams {
+            method,
+            error: err.to_string(),
+        }),
+    }
+}
+
+/// Stores `response` in `state`, waking the task waiting for it.
+pub(crate) fn receive_response(
+    state: &Mutex<State>,
+    response: Response<Value, Value>,
+) -> Result<(), ProtocolError> {
+    let mut state = state.lock().expect("State lock");
+
+    if let Some(id) = &response.id {
+        if state.cancelled.remove(id) {
+            return Ok(());
+        }
+    }
+
+    let Some((response_slot, waker)) = response
+        .id
+        .as_ref()
+        .and_then(|id| state.pending.get_mut(id))
+    else {
+        return Err(ProtocolError::UnexpectedResponse(response.id));
+    };
+
+    debug_assert!(response_slot.is_none(), "Got two responses");
+    *response_slot = Some(response);
+
+    if let Some(waker) = waker.take() {
+        waker.wake();
+    }
+
+    Ok(())
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                        PendingResponse                                         //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// The response to a request of type `T`, resolving when received.
+///
+/// Dropping it before cancels the request: its response will be ignored, and the client sends
+/// `$/cancelRequest`.
+pub struct PendingResponse<T: RequestTrait> {
+    state: Arc<Mutex<State>>,
+    id: Id,
+    cancel_sender: Option<UnboundedSender<Id>>,
+    done: bool,
+    request: PhantomData<fn() -> T>,
+}
+
+impl<T: RequestTrait> PendingResponse<T> {
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Cancels the request.
+    pub fn cancel(self) {}
+
+    /// Cancels the request if not answered within `duration`, failing with
+    /// [`TimedOut`](io::ErrorKind::TimedOut).
+    pub async fn timeout(
+        self,
+        duration: Duration,
+    ) -> io::Result<Result<T::Result, Error<T::Error>>> {
+        tokio::time::timeout(duration, self)
+            .await
+            .unwrap_or_else(|elapsed| Err(io::Error::new(io::ErrorKind::TimedOut, elapsed)))
+    }
+}
+
+/// Private.
+impl<T: RequestTrait> PendingResponse<T> {
+    pub(crate) fn new(
+        state: Arc<Mutex<State>>,
+        id: Id,
+        cancel_sender: Option<UnboundedSender<Id>>,
+    ) -> Self {
+        Self {
+            state,
+            id,
+            cancel_sender,
+            done: false,
+            request: PhantomData,
+        }
+    }
+}
+
+impl<T: RequestTrait> Future for PendingResponse<T> {
+    type Output = io::Result<Result<T::Result, Error<T::Error>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        let closed = state.closed;
+        let Some((response, waker)) = state.pending.get_mut(&self.id) else {
+            // Was polled to completion already...
+            return Poll::Pending;
+        };
+
+        if let Some(response) = response.take() {
+            state.pending.remove(&self.id);
+            drop(state);
+            self.done = true;
+
+            Poll::Ready(T::deserialize_response(response))
+        } else if closed {
+            state.pending.remove(&self.id);
+            drop(state);
+            self.done = true;
+
+            Poll::Ready(Err(connection_closed()))
+        } else {
+            if let Some(waker) = waker {
+                waker.clone_from(cx.waker());
+            } else {
+                *waker = Some(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: RequestTrait> Drop for PendingResponse<T> {
     fn drop(&mut self) {
-        self.handle.abort();
+        if self.done {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        if let Some((response, _)) = state.pending.remove(&self.id) {
+            // Not answered yet
+            if response.is_none() {
+                state.cancelled.insert(self.id.clone());
+
+                if let Some(cancel_sender) = &self.cancel_sender {
+                    let _ = cancel_sender.send(self.id.clone());
+                }
+            }
+        }
     }
 }
 
@@ -284,80 +598,357 @@ pub struct LspClientRequest<'client, W: AsyncWrite + Unpin> {
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
 pub struct LspClientResponse<'client, W: AsyncWrite + Unpin> {
-    pub(crate) client: &'client mut LspClient<W>,
+    pub(crate) client: &'client mut LspResponder<W>,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
-//                                            WorkDone                                            //
+//                                          LspResponder                                          //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-// TODO It could be useful to have correctly deserialized progress backed in
+/// Answers server requests, see [`LspClient::responder`].
+pub struct LspResponder<W: AsyncWrite + Unpin = LspWriter> {
+    writer: Arc<tokio::sync::Mutex<W>>,
+}
 
-struct WorkDone {
-    token: Token,
-    kind: Kind,
+impl<W: AsyncWrite + Unpin> LspResponder<W> {
+    pub fn response(&mut self) -> LspClientResponse<'_, W> {
+        LspClientResponse { client: self }
+    }
 }
 
-impl WorkDone {
-    const KIND: &'static str = "kind";
+/// Private.
+impl<W: AsyncWrite + Unpin> LspResponder<W> {
+    pub(crate) async fn send_response<T: RequestTrait>(
+        &mut self,
+        id: Option<Id>,
+        data: Result<T::Result, Error<T::Error>>,
+    ) -> io::Result<()> {
+        match data {
+            Ok(result) => Response::with_result(id, result),
+            Err(error) => Response::with_error(id, T::error(error)),
+        }
+        .write(&mut *self.writer.lock().await)
+        .await
+    }
 }
 
-impl TryFrom<&ProgressParams> for WorkDone {
-    type Error = ();
+impl<W: AsyncWrite + Unpin> Clone for LspResponder<W> {
+    fn clone(&self) -> Self {
+        Self {
+            writer: self.writer.clone(),
+        }
+    }
+}
 
-    fn try_from(progress: &ProgressParams) -> Result<Self, Self::Error> {
-        let token = match &progress.token {
-            ProgressToken::Integer(integer) => Token::Integer(*integer),
-            ProgressToken::String(string) => Token::String(string.clone()),
-        };
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            WorkDone                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-        match &progress.value {
-            LspAny::LspObject(value) => value.get(Self::KIND),
-            _ => None,
-        }
-        .and_then(|kind| match kind {
-            LspAny::String(kind) => Some(WorkDone {
-                token,
-                kind: kind.parse().ok()?,
-            }),
-            _ => None,
-        })
-        .ok_or(())
+/// The value of a `$/progress` notification for work done progress.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum WorkDone {
+    Begin(WorkDoneProgressBegin),
+    Report(WorkDoneProgressReport),
+    End(WorkDoneProgressEnd),
+}
+
+impl TryFrom<&LspAny> for WorkDone {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &LspAny) -> Result<Self, Self::Error> {
+        serde_json::from_value(serde_json::to_value(value)?)
     }
 }
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
+/// The state of an ongoing work done progress.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ProgressState {
+    pub title: String,
+    pub message: Option<String>,
+    pub percentage: Option<UInteger>,
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 enum Token {
     Integer(Integer),
     String(String),
 }
 
+impl From<&ProgressToken> for Token {
+    fn from(token: &ProgressToken) -> Self {
+        match token {
+            ProgressToken::Integer(integer) => Self::Integer(*integer),
+            ProgressToken::String(string) => Self::String(string.clone()),
+        }
+    }
+}
+
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-#[derive(PartialEq)]
-enum Kind {
-    Begin,
-    Report,
-    End,
-}
+/// Ongoing progresses, in the order they began.
+#[derive(Default)]
+struct Progresses(Vec<(Token, ProgressState)>);
+
+impl Progresses {
+    /// Applies a `$/progress` notification, returning `false` if it is not work done progress.
+    fn update(&mut self, params: &ProgressParams) -> bool {
+        let Ok(work_done) = WorkDone::try_from(&params.value) else {
+            return false;
+        };
+        let token = Token::from(&params.token);
+        let index = self.0.iter().position(|(t, _)| *t == token);
+
+        match (work_done, index) {
+            (WorkDone::Begin(begin), index) => {
+                let progress = ProgressState {
+                    title: begin.title,
+                    message: begin.message,
+                    percentage: begin.percentage,
+                };
+
+                match index {
+                    Some(index) => self.0[index].1 = progress,
+                    None => self.0.push((token, progress)),
+                }
+            }
+            (WorkDone::Report(report), Some(index)) => {
+                let progress = &mut self.0[index].1;
+
+                if report.message.is_some() {
+                    progress.message = report.message;
+                }
+                if report.percentage.is_some() {
+                    progress.percentage = report.percentage;
+                }
+            }
+            (WorkDone::End(_), Some(index)) => {
+                self.0.remove(index);
+            }
+            (WorkDone::Report(_) | WorkDone::End(_), None) => {}
+        }
+
+        true
+    }
 
-impl Kind {
-    const BEGIN: &'static str = "begin";
-    const REPORT: &'static str = "report";
-    const END: &'static str = "end";
+    fn to_vec(&self) -> Vec<ProgressState> {
+        self.0
+            .iter()
+            .map(|(_, progress)| progress.clone())
+            .collect()
+    }
 }
 
-impl FromStr for Kind {
-    type Err = ();
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::{io::BufReader, sync::mpsc::unbounded_channel};
+
+    #[tokio::test]
+    async fn survives_unknown_messages() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, client_writer) = tokio::io::split(client_stream);
+        let (server_reader, mut server_writer) = tokio::io::split(server_stream);
+        let mut server_reader = BufReader::new(server_reader);
+        let (sender, mut receiver) = unbounded_channel();
+        let _client = LspClient::new(BufReader::new(client_reader), client_writer, sender);
+
+        // Unknown notification
+        Notification::new("experimental/serverStatus".into(), Some(Value::Bool(true)))
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+        assert_eq!(
+            receiver.recv().await,
+            Some(ServerMessage::UnknownNotification {
+                method: String::from("experimental/serverStatus"),
+                params: Some(Value::Bool(true)),
+            })
+        );
+
+        // Unknown request, answered with `MethodNotFound`
+        Request::<Value>::new(Id::Integer(1), "experimental/unknown".into(), None)
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+        assert_eq!(
+            receiver.recv().await,
+            Some(ServerMessage::UnknownRequest {
+                id: Id::Integer(1),
+                method: String::from("experimental/unknown"),
+                params: None,
+            })
+        );
+        let Message::<Value, Value>::Response(response) =
+            Message::read(&mut server_reader).await.unwrap()
+        else {
+            panic!("Expected a response");
+        };
+        assert_eq!(response.id, Some(Id::Integer(1)));
+        assert_eq!(response.error.unwrap().code, Code::MethodNotFound);
+
+        // Invalid params of a known notification
+        Notification::new("window/logMessage".into(), Some(Value::Bool(true)))
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+        assert!(matches!(
+            receiver.recv().await,
+            Some(ServerMessage::Error(ProtocolError::InvalidParams { .. }))
+        ));
+
+        // Response to no request
+        Response::<Value, Value>::with_result(Some(Id::Integer(42)), Value::Null)
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+        assert_eq!(
+            receiver.recv().await,
+            Some(ServerMessage::Error(ProtocolError::UnexpectedResponse(
+                Some(Id::Integer(42))
+            )))
+        );
+
+        // Closed connection
+        drop((server_reader, server_writer));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(ServerMessage::Error(ProtocolError::Transport(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancels_requests() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, client_writer) = tokio::io::split(client_stream);
+        let (server_reader, mut server_writer) = tokio::io::split(server_stream);
+        let mut server_reader = BufReader::new(server_reader);
+        let (sender, mut receiver) = unbounded_channel();
+        let mut client = LspClient::new(BufReader::new(client_reader), client_writer, sender);
+        let cancelled = |message| match message {
+            Message::<Value, Value>::Notification(notification) => {
+                assert_eq!(notification.method, "$/cancelRequest");
+                notification.params.unwrap()["id"].clone()
+            }
+            message => panic!("Expected a cancel notification, got {message:?}"),
+        };
+
+        // Dropped
+        let response = client.request().shutdown().await.unwrap();
+        Message::<Value, Value>::read(&mut server_reader)
+            .await
+            .unwrap();
+        drop(response);
+        assert_eq!(
+            cancelled(Message::read(&mut server_reader).await.unwrap()),
+            0
+        );
+
+        // Timed out
+        let response = client.request().shutdown().await.unwrap();
+        assert_eq!(
+            response
+                .timeout(Duration::from_millis(10))
+                .await
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::TimedOut
+        );
+        Message::<Value, Value>::read(&mut server_reader)
+            .await
+            .unwrap();
+        assert_eq!(
+            cancelled(Message::read(&mut server_reader).await.unwrap()),
+            1
+        );
+
+        // Late responses are ignored
+        for id in [0, 1] {
+            Response::<Value, ()>::with_result(Some(Id::Integer(id)), Value::Null)
+                .write(&mut server_writer)
+                .await
+                .unwrap();
+        }
+        Notification::new("experimental/serverStatus".into(), None::<Value>)
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+        assert!(matches!(
+            receiver.recv().await,
+            Some(ServerMessage::UnknownNotification { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn fails_pending_requests() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, client_writer) = tokio::io::split(client_stream);
+        let (sender, _receiver) = unbounded_channel();
+        let mut client = LspClient::new(BufReader::new(client_reader), client_writer, sender);
+
+        let response = client.request().shutdown().await.unwrap();
+        drop(server_stream);
+        assert_eq!(
+            response.await.unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+
+        // Once closed, requests fail right away
+        assert!(client.request().shutdown().await.is_err());
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            Self::BEGIN => Ok(Self::Begin),
-            Self::REPORT => Ok(Self::Report),
-            Self::END => Ok(Self::End),
-            _ => Err(()),
+    #[tokio::test]
+    async fn tracks_progress() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, client_writer) = tokio::io::split(client_stream);
+        let (_server_reader, mut server_writer) = tokio::io::split(server_stream);
+        let (sender, mut receiver) = unbounded_channel();
+        let client = LspClient::new(BufReader::new(client_reader), client_writer, sender);
+        let progress = client.progress();
+        let indexing = |message: Option<&str>, percentage| ProgressState {
+            title: String::from("Indexing"),
+            message: message.map(String::from),
+            percentage,
+        };
+
+        for (value, expected) in [
+            (
+                json!({ "kind": "begin", "title": "Indexing", "percentage": 0 }),
+                vec![indexing(None, Some(0))],
+            ),
+            (
+                json!({ "kind": "report", "message": "1/2 (core)", "percentage": 43 }),
+                vec![indexing(Some("1/2 (core)"), Some(43))],
+            ),
+            (
+                json!({ "kind": "report", "percentage": 50 }),
+                vec![indexing(Some("1/2 (core)"), Some(50))],
+            ),
+            (json!({ "kind": "end" }), vec![]),
+        ] {
+            Notification::new(
+                "$/progress".into(),
+                Some(json!({ "token": "indexing", "value": value })),
+            )
+            .write(&mut server_writer)
+            .await
+            .unwrap();
+            assert!(matches!(
+                receiver.recv().await,
+                Some(ServerMessage::ServerNotification(
+                    ServerNotification::Progress(_)
+                ))
+            ));
+            assert_eq!(*progress.borrow(), expected);
         }
     }
 }
@@ -1,64 +1,479 @@
-use crate::{LspClient, ServerMessageSender};
-use std::{process::Stdio, sync::Arc};
-use tokio::{io::BufReader, process::Command, sync::Mutex};
+use crate::{LspClient, LspResponder, ServerMessage, ServerMessageSender};
+use futures::{Future, FutureExt};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    process::Stdio,
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader, DuplexStream},
+    process::{Child, Command},
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        Mutex,
+    },
+};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
-//                                           LspClients                                           //
+//                                          LspTransport                                          //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
-// TODO handle dead children
-enum State {
-    None {
-        command: Option<Command>,
-        server_message_sender: Option<ServerMessageSender>,
-    },
-    Initialized {
-        client: Arc<Mutex<LspClient>>,
-    },
+/// How to reach a language server.
+pub enum LspTransport {
+    /// Spawns the server, talking over its stdio.
+    Command(Command),
+    /// Talks over an in-memory stream, e.g. to a [`MockServer`](crate::MockServer).
+    Stream(DuplexStream),
+}
+
+impl From<Command> for LspTransport {
+    fn from(command: Command) -> Self {
+        Self::Command(command)
+    }
+}
+
+impl From<DuplexStream> for LspTransport {
+    fn from(stream: DuplexStream) -> Self {
+        Self::Stream(stream)
+    }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           LspClients                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Identifies a server in [`LspClients`].
+pub type LspServerId = usize;
+
+pub type LspServerMessageSender = UnboundedSender<(LspServerId, ServerMessage)>;
+pub type LspServerMessageReceiver = UnboundedReceiver<(LspServerId, ServerMessage)>;
+
+/// The configuration of a language server.
+pub struct LspServerConfig {
+    /// Shown to users, e.g. `rust-analyzer`.
+    pub name: String,
+    /// How to reach the server, e.g. a [`Command`] with its arguments and environment.
+    pub transport: LspTransport,
+    /// Sent as `initializationOptions`.
+    pub initialization_options: Option<Value>,
+    /// Files or directories marking the workspace root, e.g. `Cargo.toml`.
+    pub root_markers: Vec<String>,
+}
+
+/// Language servers, by language ID.
+///
+/// Servers are spawned on first use. Several servers can handle a language: the first one added
+/// is its main server.
 pub struct LspClients {
-    rust: State,
+    servers: Vec<Server>,
+    languages: HashMap<String, Vec<LspServerId>>,
+    server_message_sender: LspServerMessageSender,
 }
 
 impl LspClients {
-    pub fn new((rust_command, rust_server_message_sender): (Command, ServerMessageSender)) -> Self {
+    /// Messages from all servers are sent to `server_message_sender`, with the server ID.
+    pub fn new(server_message_sender: LspServerMessageSender) -> Self {
         Self {
-            rust: State::None {
-                command: Some(rust_command),
-                server_message_sender: Some(rust_server_message_sender),
-            },
+            servers: Default::default(),
+            languages: Default::default(),
+            server_message_sender,
         }
     }
 
-    pub fn rust(&mut self) -> Arc<Mutex<LspClient>> {
-        match &mut self.rust {
-            State::None {
-                command,
-                server_message_sender,
-            } => {
-                let (mut command, server_message_sender) = (
-                    command.take().unwrap(),
-                    server_message_sender.take().unwrap(),
-                );
-                let mut child = command
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()
-                    .unwrap();
-                let (stdin, stdout) = (
-                    child.stdin.take().expect("Child stdin"),
-                    child.stdout.take().expect("Child stdout"),
-                );
-                let client = LspClient::new(BufReader::new(stdout), stdin, server_message_sender);
-                let client = Arc::new(Mutex::new(client));
-                let clone = client.clone();
+    /// Adds a server for `language_id`.
+    pub fn add(&mut self, language_id: String, config: LspServerConfig) -> LspServerId {
+        let id = self.servers.len();
+
+        self.servers.push(Server::new(config));
+        self.languages.entry(language_id).or_default().push(id);
+
+        id
+    }
+
+    /// Returns the servers of `language_id`, main server first.
+    pub fn servers(&self, language_id: &str) -> &[LspServerId] {
+        self.languages
+            .get(language_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of server `id`.
+    pub fn name(&self, id: LspServerId) -> &str {
+        &self.servers[id].name
+    }
+
+    /// Returns the `initializationOptions` of server `id`.
+    pub fn initialization_options(&self, id: LspServerId) -> Option<&Value> {
+        self.servers[id].initialization_options.as_ref()
+    }
+
+    /// Returns the root markers of server `id`.
+    pub fn root_markers(&self, id: LspServerId) -> &[String] {
+        &self.servers[id].root_markers
+    }
+
+    /// Returns the client of server `id`, spawning the server if needed.
+    ///
+    /// Once the server [`exited`](Self::exited), returns its dead client (whose requests fail)
+    /// until [`restart`](Self::restart)ed.
+    pub fn client(&mut self, id: LspServerId) -> Arc<Mutex<LspClient>> {
+        let sender = self.server_message_sender.clone();
+
+        self.servers[id].client(id, sender)
+    }
+
+    /// Returns the responder of server `id`'s client, to answer its requests without locking the
+    /// client.
+    pub fn responder(&mut self, id: LspServerId) -> LspResponder {
+        self.client(id);
+
+        self.servers[id].running.as_ref().unwrap().responder.clone()
+    }
+
+    /// Returns the last lines of server `id`'s stderr.
+    pub fn log(&self, id: LspServerId) -> Vec<String> {
+        self.servers[id]
+            .log
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Tears server `id` down after its connection broke.
+    ///
+    /// Returns how long to wait before restarting it (with [`restart`](Self::restart)), or
+    /// `None` if it cannot be restarted.
+    pub fn exited(&mut self, id: LspServerId) -> Option<Duration> {
+        self.servers[id].exited()
+    }
+
+    /// Respawns server `id` after it exited, returning its new client.
+    pub fn restart(&mut self, id: LspServerId) -> Arc<Mutex<LspClient>> {
+        if self.servers[id]
+            .running
+            .as_ref()
+            .is_some_and(|running| running.exited)
+        {
+            self.servers[id].running = None;
+        }
+
+        self.client(id)
+    }
+
+    /// Sends `shutdown` and `exit` to the running servers, killing those that do not exit within
+    /// `timeout`.
+    ///
+    /// Servers are not restarted afterwards.
+    pub fn shutdown(&mut self, timeout: Duration) -> impl 'static + Send + Future<Output = ()> {
+        futures::future::join_all(
+            self.servers
+                .iter_mut()
+                .map(|server| server.shutdown(timeout))
+                .collect::<Vec<_>>(),
+        )
+        .map(|_| ())
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// A supervised language server.
+struct Server {
+    /// `None` once the stream is taken, or after shutdown.
+    transport: Option<LspTransport>,
+    name: String,
+    initialization_options: Option<Value>,
+    root_markers: Vec<String>,
+    log: Arc<StdMutex<VecDeque<String>>>,
+    running: Option<Running>,
+    restarts: u32,
+}
+
+/// A spawned language server.
+struct Running {
+    client: Arc<Mutex<LspClient>>,
+    responder: LspResponder,
+    child: Option<Child>,
+    spawned_at: Instant,
+    /// Whether its connection broke. Kept until restarted, so that it is not respawned before.
+    exited: bool,
+}
+
+impl Server {
+    const LOG_LINES: usize = 1000;
+    const MAX_RESTARTS: u32 = 5;
+    const BACKOFF: Duration = Duration::from_millis(500);
+    /// Restarts of servers running for longer than this are not backed off.
+    const STABLE: Duration = Duration::from_secs(60);
+
+    fn new(config: LspServerConfig) -> Self {
+        Self {
+            transport: Some(config.transport),
+            name: config.name,
+            initialization_options: config.initialization_options,
+            root_markers: config.root_markers,
+            log: Default::default(),
+            running: None,
+            restarts: 0,
+        }
+    }
+
+    fn client(&mut self, id: LspServerId, sender: LspServerMessageSender) -> Arc<Mutex<LspClient>> {
+        if self.running.is_none() {
+            // Tags messages with the server ID
+            let (server_message_sender, mut server_message_receiver) = unbounded_channel();
+
+            tokio::spawn(async move {
+                while let Some(message) = server_message_receiver.recv().await {
+                    if sender.send((id, message)).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            self.running = Some(self.spawn(server_message_sender));
+        }
+
+        self.running.as_ref().unwrap().client.clone()
+    }
 
-                self.rust = State::Initialized { client };
+    fn exited(&mut self) -> Option<Duration> {
+        let running = self.running.as_mut().filter(|running| !running.exited)?;
+        running.exited = true;
+        let spawned_at = running.spawned_at;
 
-                clone
+        if let Some(mut child) = running.child.take() {
+            match child.try_wait() {
+                Ok(Some(status)) => self.push_log(format!("Server exited: {status}")),
+                Ok(None) => {
+                    self.push_log(String::from("Server closed its connection, killing it"));
+                    let _ = child.start_kill();
+                }
+                Err(err) => self.push_log(format!("Server status: {err}")),
             }
-            State::Initialized { client } => client.clone(),
         }
+
+        if !matches!(self.transport, Some(LspTransport::Command(_))) {
+            return None;
+        }
+
+        if spawned_at.elapsed() > Self::STABLE {
+            self.restarts = 0;
+        }
+
+        if self.restarts == Self::MAX_RESTARTS {
+            self.push_log(String::from("Server restarted too many times, giving up"));
+            self.transport = None;
+            return None;
+        }
+
+        let delay = Self::BACKOFF * 2u32.pow(self.restarts);
+        self.restarts += 1;
+        self.push_log(format!("Restarting server in {delay:?}"));
+
+        Some(delay)
+    }
+
+    fn shutdown(&mut self, timeout: Duration) -> impl 'static + Send + Future<Output = ()> {
+        let running = self.running.take();
+        let log = self.log.clone();
+        self.transport = None;
+
+        async move {
+            let Some(Running { client, child, .. }) = running else {
+                return;
+            };
+
+            let shutdown = async {
+                let mut client = client.lock().await;
+                let response = client.request().shutdown().await?;
+                response.await?.ok();
+                client.notification().exit().await
+            };
+
+            match tokio::time::timeout(timeout, shutdown).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => push_log(&log, format!("Cannot shut down: {err}")),
+                Err(_) => push_log(&log, String::from("Shutdown timed out")),
+            }
+
+            if let Some(mut child) = child {
+                if tokio::time::timeout(timeout, child.wait()).await.is_err() {
+                    let _ = child.kill().await;
+                }
+            }
+        }
+    }
+}
+
+/// Private.
+impl Server {
+    fn spawn(&mut self, server_message_sender: ServerMessageSender) -> Running {
+        let (client, child) = match self.transport.take() {
+            Some(LspTransport::Command(mut command)) => {
+                let spawned = self.spawn_command(&mut command, server_message_sender.clone());
+                self.transport = Some(LspTransport::Command(command));
+
+                match spawned {
+                    Ok((client, child)) => (client, Some(child)),
+                    Err(err) => {
+                        self.push_log(format!("Cannot spawn server: {err}"));
+                        (disconnected(server_message_sender), None)
+                    }
+                }
+            }
+            Some(LspTransport::Stream(stream)) => {
+                let (reader, writer) = tokio::io::split(stream);
+                let client: LspClient = LspClient::new(
+                    BufReader::new(reader),
+                    Box::new(writer),
+                    server_message_sender,
+                );
+
+                (client, None)
+            }
+            None => (disconnected(server_message_sender), None),
+        };
+
+        Running {
+            responder: client.responder(),
+            client: Arc::new(Mutex::new(client)),
+            child,
+            spawned_at: Instant::now(),
+            exited: false,
+        }
+    }
+
+    fn spawn_command(
+        &self,
+        command: &mut Command,
+        server_message_sender: ServerMessageSender,
+    ) -> std::io::Result<(LspClient, Child)> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (stdin, stdout, stderr) = (
+            child.stdin.take().expect("Child stdin"),
+            child.stdout.take().expect("Child stdout"),
+            child.stderr.take().expect("Child stderr"),
+        );
+
+        tokio::spawn({
+            let log = self.log.clone();
+
+            async move {
+                let mut lines = BufReader::new(stderr).lines();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    push_log(&log, line);
+                }
+            }
+        });
+
+        let client: LspClient = LspClient::new(
+            BufReader::new(stdout),
+            Box::new(stdin),
+            server_message_sender,
+        );
+
+        Ok((client, child))
+    }
+
+    fn push_log(&self, line: String) {
+        push_log(&self.log, line);
+    }
+}
+
+/// A client whose connection is already broken.
+fn disconnected(server_message_sender: ServerMessageSender) -> LspClient {
+    LspClient::new(
+        BufReader::new(tokio::io::empty()),
+        Box::new(tokio::io::sink()),
+        server_message_sender,
+    )
+}
+
+fn push_log(log: &StdMutex<VecDeque<String>>, line: String) {
+    let mut log = log.lock().unwrap();
+
+    if log.len() == Server::LOG_LINES {
+        log.pop_front();
+    }
+
+    log.push_back(line);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientNotification, ClientRequest, MockServer, Null};
+
+    #[tokio::test]
+    async fn shuts_down() {
+        let (mut server, stream) = MockServer::new();
+        let (sender, _receiver) = unbounded_channel();
+        let mut clients = LspClients::new(sender);
+        let rust = clients.add(
+            String::from("rust"),
+            LspServerConfig {
+                name: String::from("mock"),
+                transport: stream.into(),
+                initialization_options: None,
+                root_markers: Vec::new(),
+            },
+        );
+        assert_eq!(clients.servers("rust"), [rust]);
+        clients.client(rust);
+
+        let shutdown = tokio::spawn(clients.shutdown(Duration::from_secs(1)));
+        let ClientRequest::Shutdown(id) = server.request().await else {
+            panic!("Expected a shutdown request");
+        };
+        server
+            .server()
+            .response()
+            .shutdown(Some(id), Ok(Null))
+            .await
+            .unwrap();
+        assert_eq!(server.notification().await, ClientNotification::Exit);
+        shutdown.await.unwrap();
+
+        // Not restarted
+        assert_eq!(clients.exited(rust), None);
+    }
+
+    #[tokio::test]
+    async fn backs_off() {
+        let (sender, _receiver) = unbounded_channel();
+        let mut clients = LspClients::new(sender);
+        let rust = clients.add(
+            String::from("rust"),
+            LspServerConfig {
+                name: String::from("true"),
+                transport: Command::new("true").into(),
+                initialization_options: None,
+                root_markers: Vec::new(),
+            },
+        );
+        let client = clients.client(rust);
+        assert_eq!(clients.exited(rust), Some(Server::BACKOFF));
+        assert_eq!(clients.exited(rust), None);
+
+        // Not respawned before restarted
+        assert!(Arc::ptr_eq(&client, &clients.client(rust)));
+        assert!(!Arc::ptr_eq(&client, &clients.restart(rust)));
+        assert_eq!(clients.exited(rust), Some(Server::BACKOFF * 2));
     }
 }
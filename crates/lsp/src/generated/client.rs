@@ -5,6 +5,544 @@ use super::*;
 use super::notifications::*;
 use super::requests::*;
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                       ClientNotification                                       //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Parameters of a client-to-server notification.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClientNotification {
+    /// @see [`CancelRequest`](super::notifications::CancelRequest).
+    CancelRequest(super::structures::CancelParams),
+    /// @see [`Progress`](super::notifications::Progress).
+    Progress(super::structures::ProgressParams),
+    /// @see [`SetTrace`](super::notifications::SetTrace).
+    SetTrace(super::structures::SetTraceParams),
+    /// @see [`Exit`](super::notifications::Exit).
+    Exit,
+    /// @see [`Initialized`](super::notifications::Initialized).
+    Initialized(super::structures::InitializedParams),
+    /// @see [`NotebookDocumentDidChange`](super::notifications::NotebookDocumentDidChange).
+    NotebookDocumentDidChange(super::structures::DidChangeNotebookDocumentParams),
+    /// @see [`NotebookDocumentDidClose`](super::notifications::NotebookDocumentDidClose).
+    NotebookDocumentDidClose(super::structures::DidCloseNotebookDocumentParams),
+    /// @see [`NotebookDocumentDidOpen`](super::notifications::NotebookDocumentDidOpen).
+    NotebookDocumentDidOpen(super::structures::DidOpenNotebookDocumentParams),
+    /// @see [`NotebookDocumentDidSave`](super::notifications::NotebookDocumentDidSave).
+    NotebookDocumentDidSave(super::structures::DidSaveNotebookDocumentParams),
+    /// @see [`TextDocumentDidChange`](super::notifications::TextDocumentDidChange).
+    TextDocumentDidChange(super::structures::DidChangeTextDocumentParams),
+    /// @see [`TextDocumentDidClose`](super::notifications::TextDocumentDidClose).
+    TextDocumentDidClose(super::structures::DidCloseTextDocumentParams),
+    /// @see [`TextDocumentDidOpen`](super::notifications::TextDocumentDidOpen).
+    TextDocumentDidOpen(super::structures::DidOpenTextDocumentParams),
+    /// @see [`TextDocumentDidSave`](super::notifications::TextDocumentDidSave).
+    TextDocumentDidSave(super::structures::DidSaveTextDocumentParams),
+    /// @see [`TextDocumentWillSave`](super::notifications::TextDocumentWillSave).
+    TextDocumentWillSave(super::structures::WillSaveTextDocumentParams),
+    /// @see [`WindowWorkDoneProgressCancel`](super::notifications::WindowWorkDoneProgressCancel).
+    WindowWorkDoneProgressCancel(super::structures::WorkDoneProgressCancelParams),
+    /// @see [`WorkspaceDidChangeConfiguration`](super::notifications::WorkspaceDidChangeConfiguration).
+    WorkspaceDidChangeConfiguration(super::structures::DidChangeConfigurationParams),
+    /// @see [`WorkspaceDidChangeWatchedFiles`](super::notifications::WorkspaceDidChangeWatchedFiles).
+    WorkspaceDidChangeWatchedFiles(super::structures::DidChangeWatchedFilesParams),
+    /// @see [`WorkspaceDidChangeWorkspaceFolders`](super::notifications::WorkspaceDidChangeWorkspaceFolders).
+    WorkspaceDidChangeWorkspaceFolders(super::structures::DidChangeWorkspaceFoldersParams),
+    /// @see [`WorkspaceDidCreateFiles`](super::notifications::WorkspaceDidCreateFiles).
+    WorkspaceDidCreateFiles(super::structures::CreateFilesParams),
+    /// @see [`WorkspaceDidDeleteFiles`](super::notifications::WorkspaceDidDeleteFiles).
+    WorkspaceDidDeleteFiles(super::structures::DeleteFilesParams),
+    /// @see [`WorkspaceDidRenameFiles`](super::notifications::WorkspaceDidRenameFiles).
+    WorkspaceDidRenameFiles(super::structures::RenameFilesParams),
+}
+
+impl ClientNotification {
+    /// The methods of this enumeration.
+    pub const METHODS: &'static [&'static str] = &[
+        "$/cancelRequest",
+        "$/progress",
+        "$/setTrace",
+        "exit",
+        "initialized",
+        "notebookDocument/didChange",
+        "notebookDocument/didClose",
+        "notebookDocument/didOpen",
+        "notebookDocument/didSave",
+        "textDocument/didChange",
+        "textDocument/didClose",
+        "textDocument/didOpen",
+        "textDocument/didSave",
+        "textDocument/willSave",
+        "window/workDoneProgress/cancel",
+        "workspace/didChangeConfiguration",
+        "workspace/didChangeWatchedFiles",
+        "workspace/didChangeWorkspaceFolders",
+        "workspace/didCreateFiles",
+        "workspace/didDeleteFiles",
+        "workspace/didRenameFiles",
+    ];
+    pub fn deserialize(notification: Notification<Value>) -> std::io::Result<Self> {
+        Ok(match notification.method.as_ref() {
+            "$/cancelRequest" => Self::CancelRequest(
+                <CancelRequest as NotificationTrait>::deserialize(notification)?,
+            ),
+            "$/progress" => {
+                Self::Progress(<Progress as NotificationTrait>::deserialize(notification)?)
+            }
+            "$/setTrace" => {
+                Self::SetTrace(<SetTrace as NotificationTrait>::deserialize(notification)?)
+            }
+            "exit" => {
+                debug_assert!(notification.params.is_none());
+                Self::Exit
+            }
+            "initialized" => Self::Initialized(<Initialized as NotificationTrait>::deserialize(
+                notification,
+            )?),
+            "notebookDocument/didChange" => Self::NotebookDocumentDidChange(
+                <NotebookDocumentDidChange as NotificationTrait>::deserialize(notification)?,
+            ),
+            "notebookDocument/didClose" => Self::NotebookDocumentDidClose(
+                <NotebookDocumentDidClose as NotificationTrait>::deserialize(notification)?,
+            ),
+            "notebookDocument/didOpen" => Self::NotebookDocumentDidOpen(
+                <NotebookDocumentDidOpen as NotificationTrait>::deserialize(notification)?,
+            ),
+            "notebookDocument/didSave" => Self::NotebookDocumentDidSave(
+                <NotebookDocumentDidSave as NotificationTrait>::deserialize(notification)?,
+            ),
+            "textDocument/didChange" => Self::TextDocumentDidChange(
+                <TextDocumentDidChange as NotificationTrait>::deserialize(notification)?,
+            ),
+            "textDocument/didClose" => Self::TextDocumentDidClose(
+                <TextDocumentDidClose as NotificationTrait>::deserialize(notification)?,
+            ),
+            "textDocument/didOpen" => Self::TextDocumentDidOpen(
+                <TextDocumentDidOpen as NotificationTrait>::deserialize(notification)?,
+            ),
+            "textDocument/didSave" => Self::TextDocumentDidSave(
+                <TextDocumentDidSave as NotificationTrait>::deserialize(notification)?,
+            ),
+            "textDocument/willSave" => Self::TextDocumentWillSave(
+                <TextDocumentWillSave as NotificationTrait>::deserialize(notification)?,
+            ),
+            "window/workDoneProgress/cancel" => Self::WindowWorkDoneProgressCancel(
+                <WindowWorkDoneProgressCancel as NotificationTrait>::deserialize(notification)?,
+            ),
+            "workspace/didChangeConfiguration" => Self::WorkspaceDidChangeConfiguration(
+                <WorkspaceDidChangeConfiguration as NotificationTrait>::deserialize(notification)?,
+            ),
+            "workspace/didChangeWatchedFiles" => Self::WorkspaceDidChangeWatchedFiles(
+                <WorkspaceDidChangeWatchedFiles as NotificationTrait>::deserialize(notification)?,
+            ),
+            "workspace/didChangeWorkspaceFolders" => Self::WorkspaceDidChangeWorkspaceFolders(
+                <WorkspaceDidChangeWorkspaceFolders as NotificationTrait>::deserialize(
+                    notification,
+                )?,
+            ),
+            "workspace/didCreateFiles" => Self::WorkspaceDidCreateFiles(
+                <WorkspaceDidCreateFiles as NotificationTrait>::deserialize(notification)?,
+            ),
+            "workspace/didDeleteFiles" => Self::WorkspaceDidDeleteFiles(
+                <WorkspaceDidDeleteFiles as NotificationTrait>::deserialize(notification)?,
+            ),
+            "workspace/didRenameFiles" => Self::WorkspaceDidRenameFiles(
+                <WorkspaceDidRenameFiles as NotificationTrait>::deserialize(notification)?,
+            ),
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Unknown method",
+                ));
+            }
+        })
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         ClientRequest                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Parameters of a client-to-server request.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClientRequest {
+    /// @see [`CallHierarchyIncomingCalls`](super::requests::CallHierarchyIncomingCalls).
+    CallHierarchyIncomingCalls(Id, super::structures::CallHierarchyIncomingCallsParams),
+    /// @see [`CallHierarchyOutgoingCalls`](super::requests::CallHierarchyOutgoingCalls).
+    CallHierarchyOutgoingCalls(Id, super::structures::CallHierarchyOutgoingCallsParams),
+    /// @see [`CodeActionResolve`](super::requests::CodeActionResolve).
+    CodeActionResolve(Id, super::structures::CodeAction),
+    /// @see [`CodeLensResolve`](super::requests::CodeLensResolve).
+    CodeLensResolve(Id, super::structures::CodeLens),
+    /// @see [`CompletionItemResolve`](super::requests::CompletionItemResolve).
+    CompletionItemResolve(Id, super::structures::CompletionItem),
+    /// @see [`DocumentLinkResolve`](super::requests::DocumentLinkResolve).
+    DocumentLinkResolve(Id, super::structures::DocumentLink),
+    /// @see [`Initialize`](super::requests::Initialize).
+    Initialize(Id, super::structures::InitializeParams),
+    /// @see [`InlayHintResolve`](super::requests::InlayHintResolve).
+    InlayHintResolve(Id, super::structures::InlayHint),
+    /// @see [`Shutdown`](super::requests::Shutdown).
+    Shutdown(Id),
+    /// @see [`TextDocumentCodeAction`](super::requests::TextDocumentCodeAction).
+    TextDocumentCodeAction(Id, super::structures::CodeActionParams),
+    /// @see [`TextDocumentCodeLens`](super::requests::TextDocumentCodeLens).
+    TextDocumentCodeLens(Id, super::structures::CodeLensParams),
+    /// @see [`TextDocumentColorPresentation`](super::requests::TextDocumentColorPresentation).
+    TextDocumentColorPresentation(Id, super::structures::ColorPresentationParams),
+    /// @see [`TextDocumentCompletion`](super::requests::TextDocumentCompletion).
+    TextDocumentCompletion(Id, super::structures::CompletionParams),
+    /// @see [`TextDocumentDeclaration`](super::requests::TextDocumentDeclaration).
+    TextDocumentDeclaration(Id, super::structures::DeclarationParams),
+    /// @see [`TextDocumentDefinition`](super::requests::TextDocumentDefinition).
+    TextDocumentDefinition(Id, super::structures::DefinitionParams),
+    /// @see [`TextDocumentDiagnostic`](super::requests::TextDocumentDiagnostic).
+    TextDocumentDiagnostic(Id, super::structures::DocumentDiagnosticParams),
+    /// @see [`TextDocumentDocumentColor`](super::requests::TextDocumentDocumentColor).
+    TextDocumentDocumentColor(Id, super::structures::DocumentColorParams),
+    /// @see [`TextDocumentDocumentHighlight`](super::requests::TextDocumentDocumentHighlight).
+    TextDocumentDocumentHighlight(Id, super::structures::DocumentHighlightParams),
+    /// @see [`TextDocumentDocumentLink`](super::requests::TextDocumentDocumentLink).
+    TextDocumentDocumentLink(Id, super::structures::DocumentLinkParams),
+    /// @see [`TextDocumentDocumentSymbol`](super::requests::TextDocumentDocumentSymbol).
+    TextDocumentDocumentSymbol(Id, super::structures::DocumentSymbolParams),
+    /// @see [`TextDocumentFoldingRange`](super::requests::TextDocumentFoldingRange).
+    TextDocumentFoldingRange(Id, super::structures::FoldingRangeParams),
+    /// @see [`TextDocumentFormatting`](super::requests::TextDocumentFormatting).
+    TextDocumentFormatting(Id, super::structures::DocumentFormattingParams),
+    /// @see [`TextDocumentHover`](super::requests::TextDocumentHover).
+    TextDocumentHover(Id, super::structures::HoverParams),
+    /// @see [`TextDocumentImplementation`](super::requests::TextDocumentImplementation).
+    TextDocumentImplementation(Id, super::structures::ImplementationParams),
+    /// @see [`TextDocumentInlayHint`](super::requests::TextDocumentInlayHint).
+    TextDocumentInlayHint(Id, super::structures::InlayHintParams),
+    /// @see [`TextDocumentInlineValue`](super::requests::TextDocumentInlineValue).
+    TextDocumentInlineValue(Id, super::structures::InlineValueParams),
+    /// @see [`TextDocumentLinkedEditingRange`](super::requests::TextDocumentLinkedEditingRange).
+    TextDocumentLinkedEditingRange(Id, super::structures::LinkedEditingRangeParams),
+    /// @see [`TextDocumentMoniker`](super::requests::TextDocumentMoniker).
+    TextDocumentMoniker(Id, super::structures::MonikerParams),
+    /// @see [`TextDocumentOnTypeFormatting`](super::requests::TextDocumentOnTypeFormatting).
+    TextDocumentOnTypeFormatting(Id, super::structures::DocumentOnTypeFormattingParams),
+    /// @see [`TextDocumentPrepareCallHierarchy`](super::requests::TextDocumentPrepareCallHierarchy).
+    TextDocumentPrepareCallHierarchy(Id, super::structures::CallHierarchyPrepareParams),
+    /// @see [`TextDocumentPrepareRename`](super::requests::TextDocumentPrepareRename).
+    TextDocumentPrepareRename(Id, super::structures::PrepareRenameParams),
+    /// @see [`TextDocumentPrepareTypeHierarchy`](super::requests::TextDocumentPrepareTypeHierarchy).
+    TextDocumentPrepareTypeHierarchy(Id, super::structures::TypeHierarchyPrepareParams),
+    /// @see [`TextDocumentRangeFormatting`](super::requests::TextDocumentRangeFormatting).
+    TextDocumentRangeFormatting(Id, super::structures::DocumentRangeFormattingParams),
+    /// @see [`TextDocumentReferences`](super::requests::TextDocumentReferences).
+    TextDocumentReferences(Id, super::structures::ReferenceParams),
+    /// @see [`TextDocumentRename`](super::requests::TextDocumentRename).
+    TextDocumentRename(Id, super::structures::RenameParams),
+    /// @see [`TextDocumentSelectionRange`](super::requests::TextDocumentSelectionRange).
+    TextDocumentSelectionRange(Id, super::structures::SelectionRangeParams),
+    /// @see [`TextDocumentSemanticTokensFull`](super::requests::TextDocumentSemanticTokensFull).
+    TextDocumentSemanticTokensFull(Id, super::structures::SemanticTokensParams),
+    /// @see [`TextDocumentSemanticTokensFullDelta`](super::requests::TextDocumentSemanticTokensFullDelta).
+    TextDocumentSemanticTokensFullDelta(Id, super::structures::SemanticTokensDeltaParams),
+    /// @see [`TextDocumentSemanticTokensRange`](super::requests::TextDocumentSemanticTokensRange).
+    TextDocumentSemanticTokensRange(Id, super::structures::SemanticTokensRangeParams),
+    /// @see [`TextDocumentSignatureHelp`](super::requests::TextDocumentSignatureHelp).
+    TextDocumentSignatureHelp(Id, super::structures::SignatureHelpParams),
+    /// @see [`TextDocumentTypeDefinition`](super::requests::TextDocumentTypeDefinition).
+    TextDocumentTypeDefinition(Id, super::structures::TypeDefinitionParams),
+    /// @see [`TextDocumentWillSaveWaitUntil`](super::requests::TextDocumentWillSaveWaitUntil).
+    TextDocumentWillSaveWaitUntil(Id, super::structures::WillSaveTextDocumentParams),
+    /// @see [`TypeHierarchySubtypes`](super::requests::TypeHierarchySubtypes).
+    TypeHierarchySubtypes(Id, super::structures::TypeHierarchySubtypesParams),
+    /// @see [`TypeHierarchySupertypes`](super::requests::TypeHierarchySupertypes).
+    TypeHierarchySupertypes(Id, super::structures::TypeHierarchySupertypesParams),
+    /// @see [`WorkspaceDiagnostic`](super::requests::WorkspaceDiagnostic).
+    WorkspaceDiagnostic(Id, super::structures::WorkspaceDiagnosticParams),
+    /// @see [`WorkspaceExecuteCommand`](super::requests::WorkspaceExecuteCommand).
+    WorkspaceExecuteCommand(Id, super::structures::ExecuteCommandParams),
+    /// @see [`WorkspaceSymbol`](super::requests::WorkspaceSymbol).
+    WorkspaceSymbol(Id, super::structures::WorkspaceSymbolParams),
+    /// @see [`WorkspaceWillCreateFiles`](super::requests::WorkspaceWillCreateFiles).
+    WorkspaceWillCreateFiles(Id, super::structures::CreateFilesParams),
+    /// @see [`WorkspaceWillDeleteFiles`](super::requests::WorkspaceWillDeleteFiles).
+    WorkspaceWillDeleteFiles(Id, super::structures::DeleteFilesParams),
+    /// @see [`WorkspaceWillRenameFiles`](super::requests::WorkspaceWillRenameFiles).
+    WorkspaceWillRenameFiles(Id, super::structures::RenameFilesParams),
+    /// @see [`WorkspaceSymbolResolve`](super::requests::WorkspaceSymbolResolve).
+    WorkspaceSymbolResolve(Id, super::structures::WorkspaceSymbol),
+}
+
+impl ClientRequest {
+    /// The methods of this enumeration.
+    pub const METHODS: &'static [&'static str] = &[
+        "callHierarchy/incomingCalls",
+        "callHierarchy/outgoingCalls",
+        "codeAction/resolve",
+        "codeLens/resolve",
+        "completionItem/resolve",
+        "documentLink/resolve",
+        "initialize",
+        "inlayHint/resolve",
+        "shutdown",
+        "textDocument/codeAction",
+        "textDocument/codeLens",
+        "textDocument/colorPresentation",
+        "textDocument/completion",
+        "textDocument/declaration",
+        "textDocument/definition",
+        "textDocument/diagnostic",
+        "textDocument/documentColor",
+        "textDocument/documentHighlight",
+        "textDocument/documentLink",
+        "textDocument/documentSymbol",
+        "textDocument/foldingRange",
+        "textDocument/formatting",
+        "textDocument/hover",
+        "textDocument/implementation",
+        "textDocument/inlayHint",
+        "textDocument/inlineValue",
+        "textDocument/linkedEditingRange",
+        "textDocument/moniker",
+        "textDocument/onTypeFormatting",
+        "textDocument/prepareCallHierarchy",
+        "textDocument/prepareRename",
+        "textDocument/prepareTypeHierarchy",
+        "textDocument/rangeFormatting",
+        "textDocument/references",
+        "textDocument/rename",
+        "textDocument/selectionRange",
+        "textDocument/semanticTokens/full",
+        "textDocument/semanticTokens/full/delta",
+        "textDocument/semanticTokens/range",
+        "textDocument/signatureHelp",
+        "textDocument/typeDefinition",
+        "textDocument/willSaveWaitUntil",
+        "typeHierarchy/subtypes",
+        "typeHierarchy/supertypes",
+        "workspace/diagnostic",
+        "workspace/executeCommand",
+        "workspace/symbol",
+        "workspace/willCreateFiles",
+        "workspace/willDeleteFiles",
+        "workspace/willRenameFiles",
+        "workspaceSymbol/resolve",
+    ];
+    pub fn deserialize(request: Request<Value>) -> std::io::Result<Self> {
+        Ok(match request.method.as_ref() {
+            "callHierarchy/incomingCalls" => Self::CallHierarchyIncomingCalls(
+                request.id.clone(),
+                <CallHierarchyIncomingCalls as RequestTrait>::deserialize_request(request)?,
+            ),
+            "callHierarchy/outgoingCalls" => Self::CallHierarchyOutgoingCalls(
+                request.id.clone(),
+                <CallHierarchyOutgoingCalls as RequestTrait>::deserialize_request(request)?,
+            ),
+            "codeAction/resolve" => Self::CodeActionResolve(
+                request.id.clone(),
+                <CodeActionResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            "codeLens/resolve" => Self::CodeLensResolve(
+                request.id.clone(),
+                <CodeLensResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            "completionItem/resolve" => Self::CompletionItemResolve(
+                request.id.clone(),
+                <CompletionItemResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            "documentLink/resolve" => Self::DocumentLinkResolve(
+                request.id.clone(),
+                <DocumentLinkResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            "initialize" => Self::Initialize(
+                request.id.clone(),
+                <Initialize as RequestTrait>::deserialize_request(request)?,
+            ),
+            "inlayHint/resolve" => Self::InlayHintResolve(
+                request.id.clone(),
+                <InlayHintResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            "shutdown" => {
+                debug_assert!(request.params.is_none());
+                Self::Shutdown(request.id)
+            }
+            "textDocument/codeAction" => Self::TextDocumentCodeAction(
+                request.id.clone(),
+                <TextDocumentCodeAction as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/codeLens" => Self::TextDocumentCodeLens(
+                request.id.clone(),
+                <TextDocumentCodeLens as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/colorPresentation" => Self::TextDocumentColorPresentation(
+                request.id.clone(),
+                <TextDocumentColorPresentation as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/completion" => Self::TextDocumentCompletion(
+                request.id.clone(),
+                <TextDocumentCompletion as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/declaration" => Self::TextDocumentDeclaration(
+                request.id.clone(),
+                <TextDocumentDeclaration as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/definition" => Self::TextDocumentDefinition(
+                request.id.clone(),
+                <TextDocumentDefinition as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/diagnostic" => Self::TextDocumentDiagnostic(
+                request.id.clone(),
+                <TextDocumentDiagnostic as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/documentColor" => Self::TextDocumentDocumentColor(
+                request.id.clone(),
+                <TextDocumentDocumentColor as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/documentHighlight" => Self::TextDocumentDocumentHighlight(
+                request.id.clone(),
+                <TextDocumentDocumentHighlight as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/documentLink" => Self::TextDocumentDocumentLink(
+                request.id.clone(),
+                <TextDocumentDocumentLink as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/documentSymbol" => Self::TextDocumentDocumentSymbol(
+                request.id.clone(),
+                <TextDocumentDocumentSymbol as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/foldingRange" => Self::TextDocumentFoldingRange(
+                request.id.clone(),
+                <TextDocumentFoldingRange as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/formatting" => Self::TextDocumentFormatting(
+                request.id.clone(),
+                <TextDocumentFormatting as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/hover" => Self::TextDocumentHover(
+                request.id.clone(),
+                <TextDocumentHover as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/implementation" => Self::TextDocumentImplementation(
+                request.id.clone(),
+                <TextDocumentImplementation as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/inlayHint" => Self::TextDocumentInlayHint(
+                request.id.clone(),
+                <TextDocumentInlayHint as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/inlineValue" => Self::TextDocumentInlineValue(
+                request.id.clone(),
+                <TextDocumentInlineValue as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/linkedEditingRange" => Self::TextDocumentLinkedEditingRange(
+                request.id.clone(),
+                <TextDocumentLinkedEditingRange as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/moniker" => Self::TextDocumentMoniker(
+                request.id.clone(),
+                <TextDocumentMoniker as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/onTypeFormatting" => Self::TextDocumentOnTypeFormatting(
+                request.id.clone(),
+                <TextDocumentOnTypeFormatting as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/prepareCallHierarchy" => Self::TextDocumentPrepareCallHierarchy(
+                request.id.clone(),
+                <TextDocumentPrepareCallHierarchy as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/prepareRename" => Self::TextDocumentPrepareRename(
+                request.id.clone(),
+                <TextDocumentPrepareRename as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/prepareTypeHierarchy" => Self::TextDocumentPrepareTypeHierarchy(
+                request.id.clone(),
+                <TextDocumentPrepareTypeHierarchy as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/rangeFormatting" => Self::TextDocumentRangeFormatting(
+                request.id.clone(),
+                <TextDocumentRangeFormatting as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/references" => Self::TextDocumentReferences(
+                request.id.clone(),
+                <TextDocumentReferences as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/rename" => Self::TextDocumentRename(
+                request.id.clone(),
+                <TextDocumentRename as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/selectionRange" => Self::TextDocumentSelectionRange(
+                request.id.clone(),
+                <TextDocumentSelectionRange as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/semanticTokens/full" => Self::TextDocumentSemanticTokensFull(
+                request.id.clone(),
+                <TextDocumentSemanticTokensFull as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/semanticTokens/full/delta" => Self::TextDocumentSemanticTokensFullDelta(
+                request.id.clone(),
+                <TextDocumentSemanticTokensFullDelta as RequestTrait>::deserialize_request(
+                    request,
+                )?,
+            ),
+            "textDocument/semanticTokens/range" => Self::TextDocumentSemanticTokensRange(
+                request.id.clone(),
+                <TextDocumentSemanticTokensRange as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/signatureHelp" => Self::TextDocumentSignatureHelp(
+                request.id.clone(),
+                <TextDocumentSignatureHelp as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/typeDefinition" => Self::TextDocumentTypeDefinition(
+                request.id.clone(),
+                <TextDocumentTypeDefinition as RequestTrait>::deserialize_request(request)?,
+            ),
+            "textDocument/willSaveWaitUntil" => Self::TextDocumentWillSaveWaitUntil(
+                request.id.clone(),
+                <TextDocumentWillSaveWaitUntil as RequestTrait>::deserialize_request(request)?,
+            ),
+            "typeHierarchy/subtypes" => Self::TypeHierarchySubtypes(
+                request.id.clone(),
+                <TypeHierarchySubtypes as RequestTrait>::deserialize_request(request)?,
+            ),
+            "typeHierarchy/supertypes" => Self::TypeHierarchySupertypes(
+                request.id.clone(),
+                <TypeHierarchySupertypes as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/diagnostic" => Self::WorkspaceDiagnostic(
+                request.id.clone(),
+                <WorkspaceDiagnostic as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/executeCommand" => Self::WorkspaceExecuteCommand(
+                request.id.clone(),
+                <WorkspaceExecuteCommand as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/symbol" => Self::WorkspaceSymbol(
+                request.id.clone(),
+                <WorkspaceSymbol as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/willCreateFiles" => Self::WorkspaceWillCreateFiles(
+                request.id.clone(),
+                <WorkspaceWillCreateFiles as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/willDeleteFiles" => Self::WorkspaceWillDeleteFiles(
+                request.id.clone(),
+                <WorkspaceWillDeleteFiles as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspace/willRenameFiles" => Self::WorkspaceWillRenameFiles(
+                request.id.clone(),
+                <WorkspaceWillRenameFiles as RequestTrait>::deserialize_request(request)?,
+            ),
+            "workspaceSymbol/resolve" => Self::WorkspaceSymbolResolve(
+                request.id.clone(),
+                <WorkspaceSymbolResolve as RequestTrait>::deserialize_request(request)?,
+            ),
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Unknown method",
+                ));
+            }
+        })
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                     LspClientNotification                                      //
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -197,12 +735,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn call_hierarchy_incoming_calls(
         &mut self,
         params: super::structures::CallHierarchyIncomingCallsParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<CallHierarchyIncomingCallsResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<CallHierarchyIncomingCalls>> {
         self.client
             .send_request::<CallHierarchyIncomingCalls>(params)
             .await
@@ -211,12 +744,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn call_hierarchy_outgoing_calls(
         &mut self,
         params: super::structures::CallHierarchyOutgoingCallsParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<CallHierarchyOutgoingCallsResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<CallHierarchyOutgoingCalls>> {
         self.client
             .send_request::<CallHierarchyOutgoingCalls>(params)
             .await
@@ -225,36 +753,21 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn code_action_resolve(
         &mut self,
         params: super::structures::CodeAction,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::CodeAction, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<CodeActionResolve>> {
         self.client.send_request::<CodeActionResolve>(params).await
     }
     /// @see [`CodeLensResolve`](super::requests::CodeLensResolve).
     pub async fn code_lens_resolve(
         &mut self,
         params: super::structures::CodeLens,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::CodeLens, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<CodeLensResolve>> {
         self.client.send_request::<CodeLensResolve>(params).await
     }
     /// @see [`CompletionItemResolve`](super::requests::CompletionItemResolve).
     pub async fn completion_item_resolve(
         &mut self,
         params: super::structures::CompletionItem,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::CompletionItem, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<CompletionItemResolve>> {
         self.client
             .send_request::<CompletionItemResolve>(params)
             .await
@@ -263,12 +776,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn document_link_resolve(
         &mut self,
         params: super::structures::DocumentLink,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::DocumentLink, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<DocumentLinkResolve>> {
         self.client
             .send_request::<DocumentLinkResolve>(params)
             .await
@@ -277,48 +785,25 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn initialize(
         &mut self,
         params: super::structures::InitializeParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<
-                        super::structures::InitializeResult,
-                        Error<super::structures::InitializeError>,
-                    >,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<Initialize>> {
         self.client.send_request::<Initialize>(params).await
     }
     /// @see [`InlayHintResolve`](super::requests::InlayHintResolve).
     pub async fn inlay_hint_resolve(
         &mut self,
         params: super::structures::InlayHint,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::InlayHint, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<InlayHintResolve>> {
         self.client.send_request::<InlayHintResolve>(params).await
     }
     /// @see [`Shutdown`](super::requests::Shutdown).
-    pub async fn shutdown(
-        &mut self,
-    ) -> std::io::Result<impl '_ + futures::Future<Output = std::io::Result<Result<Null, Error<()>>>>>
-    {
+    pub async fn shutdown(&mut self) -> std::io::Result<PendingResponse<Shutdown>> {
         self.client.send_request::<Shutdown>(()).await
     }
     /// @see [`TextDocumentCodeAction`](super::requests::TextDocumentCodeAction).
     pub async fn text_document_code_action(
         &mut self,
         params: super::structures::CodeActionParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentCodeActionResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentCodeAction>> {
         self.client
             .send_request::<TextDocumentCodeAction>(params)
             .await
@@ -327,10 +812,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_code_lens(
         &mut self,
         params: super::structures::CodeLensParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<Output = std::io::Result<Result<TextDocumentCodeLensResult, Error<()>>>>,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentCodeLens>> {
         self.client
             .send_request::<TextDocumentCodeLens>(params)
             .await
@@ -339,14 +821,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_color_presentation(
         &mut self,
         params: super::structures::ColorPresentationParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<Vec<super::structures::ColorPresentation>, Error<()>>,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentColorPresentation>> {
         self.client
             .send_request::<TextDocumentColorPresentation>(params)
             .await
@@ -355,12 +830,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_completion(
         &mut self,
         params: super::structures::CompletionParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentCompletionResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentCompletion>> {
         self.client
             .send_request::<TextDocumentCompletion>(params)
             .await
@@ -369,12 +839,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_declaration(
         &mut self,
         params: super::structures::DeclarationParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentDeclarationResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDeclaration>> {
         self.client
             .send_request::<TextDocumentDeclaration>(params)
             .await
@@ -383,12 +848,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_definition(
         &mut self,
         params: super::structures::DefinitionParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentDefinitionResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDefinition>> {
         self.client
             .send_request::<TextDocumentDefinition>(params)
             .await
@@ -397,17 +857,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_diagnostic(
         &mut self,
         params: super::structures::DocumentDiagnosticParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<
-                        super::type_aliases::DocumentDiagnosticReport,
-                        Error<super::structures::DiagnosticServerCancellationData>,
-                    >,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDiagnostic>> {
         self.client
             .send_request::<TextDocumentDiagnostic>(params)
             .await
@@ -416,14 +866,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_document_color(
         &mut self,
         params: super::structures::DocumentColorParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<Vec<super::structures::ColorInformation>, Error<()>>,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDocumentColor>> {
         self.client
             .send_request::<TextDocumentDocumentColor>(params)
             .await
@@ -432,12 +875,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_document_highlight(
         &mut self,
         params: super::structures::DocumentHighlightParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentDocumentHighlightResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDocumentHighlight>> {
         self.client
             .send_request::<TextDocumentDocumentHighlight>(params)
             .await
@@ -446,12 +884,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_document_link(
         &mut self,
         params: super::structures::DocumentLinkParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentDocumentLinkResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDocumentLink>> {
         self.client
             .send_request::<TextDocumentDocumentLink>(params)
             .await
@@ -460,12 +893,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_document_symbol(
         &mut self,
         params: super::structures::DocumentSymbolParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentDocumentSymbolResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentDocumentSymbol>> {
         self.client
             .send_request::<TextDocumentDocumentSymbol>(params)
             .await
@@ -474,12 +902,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_folding_range(
         &mut self,
         params: super::structures::FoldingRangeParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentFoldingRangeResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentFoldingRange>> {
         self.client
             .send_request::<TextDocumentFoldingRange>(params)
             .await
@@ -488,12 +911,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_formatting(
         &mut self,
         params: super::structures::DocumentFormattingParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentFormattingResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentFormatting>> {
         self.client
             .send_request::<TextDocumentFormatting>(params)
             .await
@@ -502,21 +920,14 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_hover(
         &mut self,
         params: super::structures::HoverParams,
-    ) -> std::io::Result<
-        impl '_ + futures::Future<Output = std::io::Result<Result<TextDocumentHoverResult, Error<()>>>>,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentHover>> {
         self.client.send_request::<TextDocumentHover>(params).await
     }
     /// @see [`TextDocumentImplementation`](super::requests::TextDocumentImplementation).
     pub async fn text_document_implementation(
         &mut self,
         params: super::structures::ImplementationParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentImplementationResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentImplementation>> {
         self.client
             .send_request::<TextDocumentImplementation>(params)
             .await
@@ -525,12 +936,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_inlay_hint(
         &mut self,
         params: super::structures::InlayHintParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentInlayHintResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentInlayHint>> {
         self.client
             .send_request::<TextDocumentInlayHint>(params)
             .await
@@ -539,12 +945,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_inline_value(
         &mut self,
         params: super::structures::InlineValueParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentInlineValueResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentInlineValue>> {
         self.client
             .send_request::<TextDocumentInlineValue>(params)
             .await
@@ -553,12 +954,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_linked_editing_range(
         &mut self,
         params: super::structures::LinkedEditingRangeParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentLinkedEditingRangeResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentLinkedEditingRange>> {
         self.client
             .send_request::<TextDocumentLinkedEditingRange>(params)
             .await
@@ -567,10 +963,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_moniker(
         &mut self,
         params: super::structures::MonikerParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<Output = std::io::Result<Result<TextDocumentMonikerResult, Error<()>>>>,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentMoniker>> {
         self.client
             .send_request::<TextDocumentMoniker>(params)
             .await
@@ -579,12 +972,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_on_type_formatting(
         &mut self,
         params: super::structures::DocumentOnTypeFormattingParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentOnTypeFormattingResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentOnTypeFormatting>> {
         self.client
             .send_request::<TextDocumentOnTypeFormatting>(params)
             .await
@@ -593,12 +981,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_prepare_call_hierarchy(
         &mut self,
         params: super::structures::CallHierarchyPrepareParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentPrepareCallHierarchyResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentPrepareCallHierarchy>> {
         self.client
             .send_request::<TextDocumentPrepareCallHierarchy>(params)
             .await
@@ -607,12 +990,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_prepare_rename(
         &mut self,
         params: super::structures::PrepareRenameParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentPrepareRenameResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentPrepareRename>> {
         self.client
             .send_request::<TextDocumentPrepareRename>(params)
             .await
@@ -621,12 +999,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_prepare_type_hierarchy(
         &mut self,
         params: super::structures::TypeHierarchyPrepareParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentPrepareTypeHierarchyResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentPrepareTypeHierarchy>> {
         self.client
             .send_request::<TextDocumentPrepareTypeHierarchy>(params)
             .await
@@ -635,12 +1008,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_range_formatting(
         &mut self,
         params: super::structures::DocumentRangeFormattingParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentRangeFormattingResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentRangeFormatting>> {
         self.client
             .send_request::<TextDocumentRangeFormatting>(params)
             .await
@@ -649,12 +1017,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_references(
         &mut self,
         params: super::structures::ReferenceParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentReferencesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentReferences>> {
         self.client
             .send_request::<TextDocumentReferences>(params)
             .await
@@ -663,21 +1026,14 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_rename(
         &mut self,
         params: super::structures::RenameParams,
-    ) -> std::io::Result<
-        impl '_ + futures::Future<Output = std::io::Result<Result<TextDocumentRenameResult, Error<()>>>>,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentRename>> {
         self.client.send_request::<TextDocumentRename>(params).await
     }
     /// @see [`TextDocumentSelectionRange`](super::requests::TextDocumentSelectionRange).
     pub async fn text_document_selection_range(
         &mut self,
         params: super::structures::SelectionRangeParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentSelectionRangeResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentSelectionRange>> {
         self.client
             .send_request::<TextDocumentSelectionRange>(params)
             .await
@@ -686,12 +1042,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_semantic_tokens_full(
         &mut self,
         params: super::structures::SemanticTokensParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentSemanticTokensFullResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentSemanticTokensFull>> {
         self.client
             .send_request::<TextDocumentSemanticTokensFull>(params)
             .await
@@ -700,14 +1051,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_semantic_tokens_full_delta(
         &mut self,
         params: super::structures::SemanticTokensDeltaParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<TextDocumentSemanticTokensFullDeltaResult, Error<()>>,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentSemanticTokensFullDelta>> {
         self.client
             .send_request::<TextDocumentSemanticTokensFullDelta>(params)
             .await
@@ -716,12 +1060,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_semantic_tokens_range(
         &mut self,
         params: super::structures::SemanticTokensRangeParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentSemanticTokensRangeResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentSemanticTokensRange>> {
         self.client
             .send_request::<TextDocumentSemanticTokensRange>(params)
             .await
@@ -730,12 +1069,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_signature_help(
         &mut self,
         params: super::structures::SignatureHelpParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentSignatureHelpResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentSignatureHelp>> {
         self.client
             .send_request::<TextDocumentSignatureHelp>(params)
             .await
@@ -744,12 +1078,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_type_definition(
         &mut self,
         params: super::structures::TypeDefinitionParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentTypeDefinitionResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentTypeDefinition>> {
         self.client
             .send_request::<TextDocumentTypeDefinition>(params)
             .await
@@ -758,12 +1087,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn text_document_will_save_wait_until(
         &mut self,
         params: super::structures::WillSaveTextDocumentParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TextDocumentWillSaveWaitUntilResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TextDocumentWillSaveWaitUntil>> {
         self.client
             .send_request::<TextDocumentWillSaveWaitUntil>(params)
             .await
@@ -772,12 +1096,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn type_hierarchy_subtypes(
         &mut self,
         params: super::structures::TypeHierarchySubtypesParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TypeHierarchySubtypesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TypeHierarchySubtypes>> {
         self.client
             .send_request::<TypeHierarchySubtypes>(params)
             .await
@@ -786,12 +1105,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn type_hierarchy_supertypes(
         &mut self,
         params: super::structures::TypeHierarchySupertypesParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<TypeHierarchySupertypesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<TypeHierarchySupertypes>> {
         self.client
             .send_request::<TypeHierarchySupertypes>(params)
             .await
@@ -800,17 +1114,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_diagnostic(
         &mut self,
         params: super::structures::WorkspaceDiagnosticParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<
-                    Result<
-                        super::structures::WorkspaceDiagnosticReport,
-                        Error<super::structures::DiagnosticServerCancellationData>,
-                    >,
-                >,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceDiagnostic>> {
         self.client
             .send_request::<WorkspaceDiagnostic>(params)
             .await
@@ -819,12 +1123,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_execute_command(
         &mut self,
         params: super::structures::ExecuteCommandParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<WorkspaceExecuteCommandResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceExecuteCommand>> {
         self.client
             .send_request::<WorkspaceExecuteCommand>(params)
             .await
@@ -833,21 +1132,14 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_symbol(
         &mut self,
         params: super::structures::WorkspaceSymbolParams,
-    ) -> std::io::Result<
-        impl '_ + futures::Future<Output = std::io::Result<Result<WorkspaceSymbolResult, Error<()>>>>,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceSymbol>> {
         self.client.send_request::<WorkspaceSymbol>(params).await
     }
     /// @see [`WorkspaceWillCreateFiles`](super::requests::WorkspaceWillCreateFiles).
     pub async fn workspace_will_create_files(
         &mut self,
         params: super::structures::CreateFilesParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<WorkspaceWillCreateFilesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceWillCreateFiles>> {
         self.client
             .send_request::<WorkspaceWillCreateFiles>(params)
             .await
@@ -856,12 +1148,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_will_delete_files(
         &mut self,
         params: super::structures::DeleteFilesParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<WorkspaceWillDeleteFilesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceWillDeleteFiles>> {
         self.client
             .send_request::<WorkspaceWillDeleteFiles>(params)
             .await
@@ -870,12 +1157,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_will_rename_files(
         &mut self,
         params: super::structures::RenameFilesParams,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<WorkspaceWillRenameFilesResult, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceWillRenameFiles>> {
         self.client
             .send_request::<WorkspaceWillRenameFiles>(params)
             .await
@@ -884,12 +1166,7 @@ impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
     pub async fn workspace_symbol_resolve(
         &mut self,
         params: super::structures::WorkspaceSymbol,
-    ) -> std::io::Result<
-        impl '_
-            + futures::Future<
-                Output = std::io::Result<Result<super::structures::WorkspaceSymbol, Error<()>>>,
-            >,
-    > {
+    ) -> std::io::Result<PendingResponse<WorkspaceSymbolResolve>> {
         self.client
             .send_request::<WorkspaceSymbolResolve>(params)
             .await
@@ -29,6 +29,16 @@ pub enum ServerNotification {
 }
 
 impl ServerNotification {
+    /// The methods of this enumeration.
+    pub const METHODS: &'static [&'static str] = &[
+        "$/cancelRequest",
+        "$/logTrace",
+        "$/progress",
+        "telemetry/event",
+        "textDocument/publishDiagnostics",
+        "window/logMessage",
+        "window/showMessage",
+    ];
     pub fn deserialize(notification: Notification<Value>) -> std::io::Result<Self> {
         Ok(match notification.method.as_ref() {
             "$/cancelRequest" => Self::CancelRequest(
@@ -98,6 +108,22 @@ pub enum ServerRequest {
 }
 
 impl ServerRequest {
+    /// The methods of this enumeration.
+    pub const METHODS: &'static [&'static str] = &[
+        "client/registerCapability",
+        "client/unregisterCapability",
+        "window/showDocument",
+        "window/showMessageRequest",
+        "window/workDoneProgress/create",
+        "workspace/applyEdit",
+        "workspace/codeLens/refresh",
+        "workspace/configuration",
+        "workspace/diagnostic/refresh",
+        "workspace/inlayHint/refresh",
+        "workspace/inlineValue/refresh",
+        "workspace/semanticTokens/refresh",
+        "workspace/workspaceFolders",
+    ];
     pub fn deserialize(request: Request<Value>) -> std::io::Result<Self> {
         Ok(match request.method.as_ref() {
             "client/registerCapability" => Self::ClientRegisterCapability(
@@ -161,3 +187,699 @@ impl ServerRequest {
         })
     }
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                     LspServerNotification                                      //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+impl<'server, W: AsyncWrite + Unpin> super::LspServerNotification<'server, W> {
+    /// @see [`CancelRequest`](super::notifications::CancelRequest).
+    pub async fn cancel_request(
+        &mut self,
+        params: super::structures::CancelParams,
+    ) -> std::io::Result<()> {
+        self.server.send_notification::<CancelRequest>(params).await
+    }
+    /// @see [`LogTrace`](super::notifications::LogTrace).
+    pub async fn log_trace(
+        &mut self,
+        params: super::structures::LogTraceParams,
+    ) -> std::io::Result<()> {
+        self.server.send_notification::<LogTrace>(params).await
+    }
+    /// @see [`Progress`](super::notifications::Progress).
+    pub async fn progress(
+        &mut self,
+        params: super::structures::ProgressParams,
+    ) -> std::io::Result<()> {
+        self.server.send_notification::<Progress>(params).await
+    }
+    /// @see [`TelemetryEvent`](super::notifications::TelemetryEvent).
+    pub async fn telemetry_event(
+        &mut self,
+        params: super::type_aliases::LspAny,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_notification::<TelemetryEvent>(params)
+            .await
+    }
+    /// @see [`TextDocumentPublishDiagnostics`](super::notifications::TextDocumentPublishDiagnostics).
+    pub async fn text_document_publish_diagnostics(
+        &mut self,
+        params: super::structures::PublishDiagnosticsParams,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_notification::<TextDocumentPublishDiagnostics>(params)
+            .await
+    }
+    /// @see [`WindowLogMessage`](super::notifications::WindowLogMessage).
+    pub async fn window_log_message(
+        &mut self,
+        params: super::structures::LogMessageParams,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_notification::<WindowLogMessage>(params)
+            .await
+    }
+    /// @see [`WindowShowMessage`](super::notifications::WindowShowMessage).
+    pub async fn window_show_message(
+        &mut self,
+        params: super::structures::ShowMessageParams,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_notification::<WindowShowMessage>(params)
+            .await
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                        LspServerRequest                                        //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+impl<'server, W: AsyncWrite + Unpin> super::LspServerRequest<'server, W> {
+    /// @see [`ClientRegisterCapability`](super::requests::ClientRegisterCapability).
+    pub async fn client_register_capability(
+        &mut self,
+        params: super::structures::RegistrationParams,
+    ) -> std::io::Result<PendingResponse<ClientRegisterCapability>> {
+        self.server
+            .send_request::<ClientRegisterCapability>(params)
+            .await
+    }
+    /// @see [`ClientUnregisterCapability`](super::requests::ClientUnregisterCapability).
+    pub async fn client_unregister_capability(
+        &mut self,
+        params: super::structures::UnregistrationParams,
+    ) -> std::io::Result<PendingResponse<ClientUnregisterCapability>> {
+        self.server
+            .send_request::<ClientUnregisterCapability>(params)
+            .await
+    }
+    /// @see [`WindowShowDocument`](super::requests::WindowShowDocument).
+    pub async fn window_show_document(
+        &mut self,
+        params: super::structures::ShowDocumentParams,
+    ) -> std::io::Result<PendingResponse<WindowShowDocument>> {
+        self.server.send_request::<WindowShowDocument>(params).await
+    }
+    /// @see [`WindowShowMessageRequest`](super::requests::WindowShowMessageRequest).
+    pub async fn window_show_message_request(
+        &mut self,
+        params: super::structures::ShowMessageRequestParams,
+    ) -> std::io::Result<PendingResponse<WindowShowMessageRequest>> {
+        self.server
+            .send_request::<WindowShowMessageRequest>(params)
+            .await
+    }
+    /// @see [`WindowWorkDoneProgressCreate`](super::requests::WindowWorkDoneProgressCreate).
+    pub async fn window_work_done_progress_create(
+        &mut self,
+        params: super::structures::WorkDoneProgressCreateParams,
+    ) -> std::io::Result<PendingResponse<WindowWorkDoneProgressCreate>> {
+        self.server
+            .send_request::<WindowWorkDoneProgressCreate>(params)
+            .await
+    }
+    /// @see [`WorkspaceApplyEdit`](super::requests::WorkspaceApplyEdit).
+    pub async fn workspace_apply_edit(
+        &mut self,
+        params: super::structures::ApplyWorkspaceEditParams,
+    ) -> std::io::Result<PendingResponse<WorkspaceApplyEdit>> {
+        self.server.send_request::<WorkspaceApplyEdit>(params).await
+    }
+    /// @see [`WorkspaceCodeLensRefresh`](super::requests::WorkspaceCodeLensRefresh).
+    pub async fn workspace_code_lens_refresh(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceCodeLensRefresh>> {
+        self.server
+            .send_request::<WorkspaceCodeLensRefresh>(())
+            .await
+    }
+    /// @see [`WorkspaceConfiguration`](super::requests::WorkspaceConfiguration).
+    pub async fn workspace_configuration(
+        &mut self,
+        params: super::structures::ConfigurationParams,
+    ) -> std::io::Result<PendingResponse<WorkspaceConfiguration>> {
+        self.server
+            .send_request::<WorkspaceConfiguration>(params)
+            .await
+    }
+    /// @see [`WorkspaceDiagnosticRefresh`](super::requests::WorkspaceDiagnosticRefresh).
+    pub async fn workspace_diagnostic_refresh(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceDiagnosticRefresh>> {
+        self.server
+            .send_request::<WorkspaceDiagnosticRefresh>(())
+            .await
+    }
+    /// @see [`WorkspaceInlayHintRefresh`](super::requests::WorkspaceInlayHintRefresh).
+    pub async fn workspace_inlay_hint_refresh(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceInlayHintRefresh>> {
+        self.server
+            .send_request::<WorkspaceInlayHintRefresh>(())
+            .await
+    }
+    /// @see [`WorkspaceInlineValueRefresh`](super::requests::WorkspaceInlineValueRefresh).
+    pub async fn workspace_inline_value_refresh(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceInlineValueRefresh>> {
+        self.server
+            .send_request::<WorkspaceInlineValueRefresh>(())
+            .await
+    }
+    /// @see [`WorkspaceSemanticTokensRefresh`](super::requests::WorkspaceSemanticTokensRefresh).
+    pub async fn workspace_semantic_tokens_refresh(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceSemanticTokensRefresh>> {
+        self.server
+            .send_request::<WorkspaceSemanticTokensRefresh>(())
+            .await
+    }
+    /// @see [`WorkspaceWorkspaceFolders`](super::requests::WorkspaceWorkspaceFolders).
+    pub async fn workspace_workspace_folders(
+        &mut self,
+    ) -> std::io::Result<PendingResponse<WorkspaceWorkspaceFolders>> {
+        self.server
+            .send_request::<WorkspaceWorkspaceFolders>(())
+            .await
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                       LspServerResponse                                        //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+impl<'server, W: AsyncWrite + Unpin> super::LspServerResponse<'server, W> {
+    /// @see [`CallHierarchyIncomingCalls`](super::requests::CallHierarchyIncomingCalls).
+    pub async fn call_hierarchy_incoming_calls(
+        &mut self,
+        id: Option<Id>,
+        data: Result<CallHierarchyIncomingCallsResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<CallHierarchyIncomingCalls>(id, data)
+            .await
+    }
+    /// @see [`CallHierarchyOutgoingCalls`](super::requests::CallHierarchyOutgoingCalls).
+    pub async fn call_hierarchy_outgoing_calls(
+        &mut self,
+        id: Option<Id>,
+        data: Result<CallHierarchyOutgoingCallsResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<CallHierarchyOutgoingCalls>(id, data)
+            .await
+    }
+    /// @see [`CodeActionResolve`](super::requests::CodeActionResolve).
+    pub async fn code_action_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::CodeAction, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<CodeActionResolve>(id, data)
+            .await
+    }
+    /// @see [`CodeLensResolve`](super::requests::CodeLensResolve).
+    pub async fn code_lens_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::CodeLens, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server.send_response::<CodeLensResolve>(id, data).await
+    }
+    /// @see [`CompletionItemResolve`](super::requests::CompletionItemResolve).
+    pub async fn completion_item_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::CompletionItem, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<CompletionItemResolve>(id, data)
+            .await
+    }
+    /// @see [`DocumentLinkResolve`](super::requests::DocumentLinkResolve).
+    pub async fn document_link_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::DocumentLink, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<DocumentLinkResolve>(id, data)
+            .await
+    }
+    /// @see [`Initialize`](super::requests::Initialize).
+    pub async fn initialize(
+        &mut self,
+        id: Option<Id>,
+        data: Result<
+            super::structures::InitializeResult,
+            Error<super::structures::InitializeError>,
+        >,
+    ) -> std::io::Result<()> {
+        self.server.send_response::<Initialize>(id, data).await
+    }
+    /// @see [`InlayHintResolve`](super::requests::InlayHintResolve).
+    pub async fn inlay_hint_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::InlayHint, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<InlayHintResolve>(id, data)
+            .await
+    }
+    /// @see [`Shutdown`](super::requests::Shutdown).
+    pub async fn shutdown(
+        &mut self,
+        id: Option<Id>,
+        data: Result<Null, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server.send_response::<Shutdown>(id, data).await
+    }
+    /// @see [`TextDocumentCodeAction`](super::requests::TextDocumentCodeAction).
+    pub async fn text_document_code_action(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentCodeActionResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentCodeAction>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentCodeLens`](super::requests::TextDocumentCodeLens).
+    pub async fn text_document_code_lens(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentCodeLensResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentCodeLens>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentColorPresentation`](super::requests::TextDocumentColorPresentation).
+    pub async fn text_document_color_presentation(
+        &mut self,
+        id: Option<Id>,
+        data: Result<Vec<super::structures::ColorPresentation>, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentColorPresentation>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentCompletion`](super::requests::TextDocumentCompletion).
+    pub async fn text_document_completion(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentCompletionResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentCompletion>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDeclaration`](super::requests::TextDocumentDeclaration).
+    pub async fn text_document_declaration(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentDeclarationResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDeclaration>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDefinition`](super::requests::TextDocumentDefinition).
+    pub async fn text_document_definition(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentDefinitionResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDefinition>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDiagnostic`](super::requests::TextDocumentDiagnostic).
+    pub async fn text_document_diagnostic(
+        &mut self,
+        id: Option<Id>,
+        data: Result<
+            super::type_aliases::DocumentDiagnosticReport,
+            Error<super::structures::DiagnosticServerCancellationData>,
+        >,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDiagnostic>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDocumentColor`](super::requests::TextDocumentDocumentColor).
+    pub async fn text_document_document_color(
+        &mut self,
+        id: Option<Id>,
+        data: Result<Vec<super::structures::ColorInformation>, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDocumentColor>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDocumentHighlight`](super::requests::TextDocumentDocumentHighlight).
+    pub async fn text_document_document_highlight(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentDocumentHighlightResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDocumentHighlight>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDocumentLink`](super::requests::TextDocumentDocumentLink).
+    pub async fn text_document_document_link(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentDocumentLinkResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDocumentLink>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentDocumentSymbol`](super::requests::TextDocumentDocumentSymbol).
+    pub async fn text_document_document_symbol(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentDocumentSymbolResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentDocumentSymbol>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentFoldingRange`](super::requests::TextDocumentFoldingRange).
+    pub async fn text_document_folding_range(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentFoldingRangeResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentFoldingRange>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentFormatting`](super::requests::TextDocumentFormatting).
+    pub async fn text_document_formatting(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentFormattingResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentFormatting>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentHover`](super::requests::TextDocumentHover).
+    pub async fn text_document_hover(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentHoverResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentHover>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentImplementation`](super::requests::TextDocumentImplementation).
+    pub async fn text_document_implementation(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentImplementationResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentImplementation>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentInlayHint`](super::requests::TextDocumentInlayHint).
+    pub async fn text_document_inlay_hint(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentInlayHintResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentInlayHint>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentInlineValue`](super::requests::TextDocumentInlineValue).
+    pub async fn text_document_inline_value(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentInlineValueResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentInlineValue>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentLinkedEditingRange`](super::requests::TextDocumentLinkedEditingRange).
+    pub async fn text_document_linked_editing_range(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentLinkedEditingRangeResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentLinkedEditingRange>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentMoniker`](super::requests::TextDocumentMoniker).
+    pub async fn text_document_moniker(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentMonikerResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentMoniker>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentOnTypeFormatting`](super::requests::TextDocumentOnTypeFormatting).
+    pub async fn text_document_on_type_formatting(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentOnTypeFormattingResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentOnTypeFormatting>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentPrepareCallHierarchy`](super::requests::TextDocumentPrepareCallHierarchy).
+    pub async fn text_document_prepare_call_hierarchy(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentPrepareCallHierarchyResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentPrepareCallHierarchy>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentPrepareRename`](super::requests::TextDocumentPrepareRename).
+    pub async fn text_document_prepare_rename(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentPrepareRenameResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentPrepareRename>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentPrepareTypeHierarchy`](super::requests::TextDocumentPrepareTypeHierarchy).
+    pub async fn text_document_prepare_type_hierarchy(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentPrepareTypeHierarchyResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentPrepareTypeHierarchy>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentRangeFormatting`](super::requests::TextDocumentRangeFormatting).
+    pub async fn text_document_range_formatting(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentRangeFormattingResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentRangeFormatting>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentReferences`](super::requests::TextDocumentReferences).
+    pub async fn text_document_references(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentReferencesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentReferences>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentRename`](super::requests::TextDocumentRename).
+    pub async fn text_document_rename(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentRenameResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentRename>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentSelectionRange`](super::requests::TextDocumentSelectionRange).
+    pub async fn text_document_selection_range(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentSelectionRangeResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentSelectionRange>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentSemanticTokensFull`](super::requests::TextDocumentSemanticTokensFull).
+    pub async fn text_document_semantic_tokens_full(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentSemanticTokensFullResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentSemanticTokensFull>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentSemanticTokensFullDelta`](super::requests::TextDocumentSemanticTokensFullDelta).
+    pub async fn text_document_semantic_tokens_full_delta(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentSemanticTokensFullDeltaResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentSemanticTokensFullDelta>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentSemanticTokensRange`](super::requests::TextDocumentSemanticTokensRange).
+    pub async fn text_document_semantic_tokens_range(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentSemanticTokensRangeResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentSemanticTokensRange>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentSignatureHelp`](super::requests::TextDocumentSignatureHelp).
+    pub async fn text_document_signature_help(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentSignatureHelpResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentSignatureHelp>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentTypeDefinition`](super::requests::TextDocumentTypeDefinition).
+    pub async fn text_document_type_definition(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentTypeDefinitionResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentTypeDefinition>(id, data)
+            .await
+    }
+    /// @see [`TextDocumentWillSaveWaitUntil`](super::requests::TextDocumentWillSaveWaitUntil).
+    pub async fn text_document_will_save_wait_until(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TextDocumentWillSaveWaitUntilResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TextDocumentWillSaveWaitUntil>(id, data)
+            .await
+    }
+    /// @see [`TypeHierarchySubtypes`](super::requests::TypeHierarchySubtypes).
+    pub async fn type_hierarchy_subtypes(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TypeHierarchySubtypesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TypeHierarchySubtypes>(id, data)
+            .await
+    }
+    /// @see [`TypeHierarchySupertypes`](super::requests::TypeHierarchySupertypes).
+    pub async fn type_hierarchy_supertypes(
+        &mut self,
+        id: Option<Id>,
+        data: Result<TypeHierarchySupertypesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<TypeHierarchySupertypes>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceDiagnostic`](super::requests::WorkspaceDiagnostic).
+    pub async fn workspace_diagnostic(
+        &mut self,
+        id: Option<Id>,
+        data: Result<
+            super::structures::WorkspaceDiagnosticReport,
+            Error<super::structures::DiagnosticServerCancellationData>,
+        >,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceDiagnostic>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceExecuteCommand`](super::requests::WorkspaceExecuteCommand).
+    pub async fn workspace_execute_command(
+        &mut self,
+        id: Option<Id>,
+        data: Result<WorkspaceExecuteCommandResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceExecuteCommand>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceSymbol`](super::requests::WorkspaceSymbol).
+    pub async fn workspace_symbol(
+        &mut self,
+        id: Option<Id>,
+        data: Result<WorkspaceSymbolResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server.send_response::<WorkspaceSymbol>(id, data).await
+    }
+    /// @see [`WorkspaceWillCreateFiles`](super::requests::WorkspaceWillCreateFiles).
+    pub async fn workspace_will_create_files(
+        &mut self,
+        id: Option<Id>,
+        data: Result<WorkspaceWillCreateFilesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceWillCreateFiles>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceWillDeleteFiles`](super::requests::WorkspaceWillDeleteFiles).
+    pub async fn workspace_will_delete_files(
+        &mut self,
+        id: Option<Id>,
+        data: Result<WorkspaceWillDeleteFilesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceWillDeleteFiles>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceWillRenameFiles`](super::requests::WorkspaceWillRenameFiles).
+    pub async fn workspace_will_rename_files(
+        &mut self,
+        id: Option<Id>,
+        data: Result<WorkspaceWillRenameFilesResult, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceWillRenameFiles>(id, data)
+            .await
+    }
+    /// @see [`WorkspaceSymbolResolve`](super::requests::WorkspaceSymbolResolve).
+    pub async fn workspace_symbol_resolve(
+        &mut self,
+        id: Option<Id>,
+        data: Result<super::structures::WorkspaceSymbol, Error<()>>,
+    ) -> std::io::Result<()> {
+        self.server
+            .send_response::<WorkspaceSymbolResolve>(id, data)
+            .await
+    }
+}
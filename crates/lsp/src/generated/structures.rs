@@ -1409,9 +1409,11 @@ pub struct ConfigurationParams {
 /// [docs]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#createFile (Documentation)
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct CreateFile {
-    /// A generic resource operation.
-    #[serde(flatten)]
-    pub resource_operation: ResourceOperation,
+    /// An optional annotation identifier describing the operation.
+    ///
+    /// @since 3.16.0
+    #[serde(rename = "annotationId")]
+    pub annotation_id: Option<super::type_aliases::ChangeAnnotationIdentifier>,
     /// A create
     #[serde(rename = "kind")]
     pub kind: CreateFileKind,
@@ -1611,9 +1613,11 @@ pub struct DefinitionRegistrationOptions {
 /// [docs]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#deleteFile (Documentation)
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct DeleteFile {
-    /// A generic resource operation.
-    #[serde(flatten)]
-    pub resource_operation: ResourceOperation,
+    /// An optional annotation identifier describing the operation.
+    ///
+    /// @since 3.16.0
+    #[serde(rename = "annotationId")]
+    pub annotation_id: Option<super::type_aliases::ChangeAnnotationIdentifier>,
     /// A delete
     #[serde(rename = "kind")]
     pub kind: DeleteFileKind,
@@ -5371,9 +5375,11 @@ pub struct RenameClientCapabilities {
 /// [docs]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#renameFile (Documentation)
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct RenameFile {
-    /// A generic resource operation.
-    #[serde(flatten)]
-    pub resource_operation: ResourceOperation,
+    /// An optional annotation identifier describing the operation.
+    ///
+    /// @since 3.16.0
+    #[serde(rename = "annotationId")]
+    pub annotation_id: Option<super::type_aliases::ChangeAnnotationIdentifier>,
     /// A rename
     #[serde(rename = "kind")]
     pub kind: RenameFileKind,
@@ -1,5 +1,7 @@
 mod client;
 mod clients;
+mod mock;
+mod server;
 mod generated {
     pub mod client;
     pub mod enumerations;
@@ -11,6 +13,7 @@ mod generated {
 
     use super::{
         client::{LspClientNotification, LspClientRequest, LspClientResponse},
+        server::{LspServerNotification, LspServerRequest, LspServerResponse},
         transport::{Notification, Request, Response},
         *,
     };
@@ -28,7 +31,11 @@ mod transport {
 
 pub use client::*;
 pub use clients::*;
-pub use generated::{enumerations, notifications, requests, server::*, structures, type_aliases};
+pub use generated::{
+    client::*, enumerations, notifications, requests, server::*, structures, type_aliases,
+};
+pub use mock::*;
+pub use server::*;
 pub use transport::{Code, Error, Id};
 
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,160 @@
+use crate::{
+    structures::{Diagnostic, InitializeParams, ProgressParams, PublishDiagnosticsParams},
+    type_aliases::ProgressToken,
+    ClientMessage, ClientMessageReceiver, ClientNotification, ClientRequest, DocumentUri,
+    LspServer,
+};
+use serde_json::Value;
+use std::collections::VecDeque;
+use tokio::{
+    io::{BufReader, DuplexStream, WriteHalf},
+    sync::mpsc::unbounded_channel,
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           MockServer                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// An in-memory language server for tests, scripted with its async methods.
+///
+/// Requests and notifications are received separately: waiting for one kind buffers the other.
+/// Answer requests and send anything else with [`server`](Self::server).
+pub struct MockServer {
+    server: LspServer<WriteHalf<DuplexStream>>,
+    receiver: ClientMessageReceiver,
+    requests: VecDeque<ClientRequest>,
+    notifications: VecDeque<ClientNotification>,
+}
+
+impl MockServer {
+    const BUFFER_SIZE: usize = 64 * 1024;
+
+    /// Returns the mock server and the client end of the stream, for
+    /// [`LspTransport::Stream`](crate::LspTransport::Stream).
+    pub fn new() -> (Self, DuplexStream) {
+        let (client_stream, server_stream) = tokio::io::duplex(Self::BUFFER_SIZE);
+        let (reader, writer) = tokio::io::split(server_stream);
+        let (sender, receiver) = unbounded_channel();
+
+        (
+            Self {
+                server: LspServer::new(BufReader::new(reader), writer, sender),
+                receiver,
+                requests: Default::default(),
+                notifications: Default::default(),
+            },
+            client_stream,
+        )
+    }
+
+    pub fn server(&mut self) -> &mut LspServer<WriteHalf<DuplexStream>> {
+        &mut self.server
+    }
+
+    /// Waits for the next request.
+    pub async fn request(&mut self) -> ClientRequest {
+        loop {
+            if let Some(request) = self.requests.pop_front() {
+                return request;
+            }
+
+            self.receive().await;
+        }
+    }
+
+    /// Waits for the next notification.
+    pub async fn notification(&mut self) -> ClientNotification {
+        loop {
+            if let Some(notification) = self.notifications.pop_front() {
+                return notification;
+            }
+
+            self.receive().await;
+        }
+    }
+
+    /// Waits for the next `didOpen`, `didChange`, `willSave`, `didSave` or `didClose`
+    /// notification, skipping others.
+    pub async fn text_document_notification(&mut self) -> ClientNotification {
+        loop {
+            let notification = self.notification().await;
+
+            if matches!(
+                notification,
+                ClientNotification::TextDocumentDidOpen(_)
+                    | ClientNotification::TextDocumentDidChange(_)
+                    | ClientNotification::TextDocumentWillSave(_)
+                    | ClientNotification::TextDocumentDidSave(_)
+                    | ClientNotification::TextDocumentDidClose(_)
+            ) {
+                return notification;
+            }
+        }
+    }
+
+    /// Answers the `initialize` request with `capabilities` and waits for `initialized`.
+    ///
+    /// Returns the parameters of the `initialize` request.
+    pub async fn initialize(&mut self, capabilities: Value) -> InitializeParams {
+        let ClientRequest::Initialize(id, params) = self.request().await else {
+            panic!("Expected an initialize request");
+        };
+
+        self.server
+            .response()
+            .initialize(
+                Some(id),
+                Ok(
+                    serde_json::from_value(serde_json::json!({ "capabilities": capabilities }))
+                        .expect("Initialize result"),
+                ),
+            )
+            .await
+            .unwrap();
+
+        let ClientNotification::Initialized(_) = self.notification().await else {
+            panic!("Expected an initialized notification");
+        };
+
+        params
+    }
+
+    /// Sends a `$/progress` notification with `value`.
+    pub async fn progress(&mut self, token: ProgressToken, value: Value) {
+        self.server
+            .notification()
+            .progress(ProgressParams {
+                token,
+                value: serde_json::from_value(value).expect("Progress value"),
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Sends a `textDocument/publishDiagnostics` notification.
+    pub async fn publish_diagnostics(&mut self, uri: DocumentUri, diagnostics: Vec<Diagnostic>) {
+        self.server
+            .notification()
+            .text_document_publish_diagnostics(PublishDiagnosticsParams {
+                uri,
+                version: None,
+                diagnostics,
+            })
+            .await
+            .unwrap();
+    }
+}
+
+/// Private.
+impl MockServer {
+    async fn receive(&mut self) {
+        match self.receiver.recv().await.expect("Client message") {
+            ClientMessage::ClientRequest(request) => self.requests.push_back(request),
+            ClientMessage::ClientNotification(notification) => {
+                self.notifications.push_back(notification)
+            }
+            ClientMessage::UnknownNotification { .. } | ClientMessage::UnknownRequest { .. } => {}
+            ClientMessage::Error(error) => panic!("{error:?}"),
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use crate::{
+    client::{receive_response, ProtocolError, State},
+    notifications::NotificationTrait,
+    requests::RequestTrait,
+    transport::{Message, Notification, Request, Response},
+    ClientNotification, ClientRequest, Code, Error, Id, Integer, PendingResponse,
+};
+use serde_json::Value;
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncBufRead, AsyncWrite, Stdout},
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         ClientMessage                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub type ClientMessageSender = UnboundedSender<ClientMessage>;
+pub type ClientMessageReceiver = UnboundedReceiver<ClientMessage>;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClientMessage {
+    ClientNotification(ClientNotification),
+    ClientRequest(ClientRequest),
+    /// A notification of a method we do not know of, e.g. `$/setTrace`.
+    UnknownNotification {
+        method: String,
+        params: Option<Value>,
+    },
+    /// A request of a method we do not know of, already answered with `MethodNotFound`.
+    UnknownRequest {
+        id: Id,
+        method: String,
+        params: Option<Value>,
+    },
+    Error(ProtocolError),
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           LspServer                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// The server half of the protocol: receives [`ClientMessage`]s, answers requests and sends
+/// notifications and requests to the client.
+pub struct LspServer<W: AsyncWrite + Unpin = Stdout> {
+    id: Integer,
+    /// Shared with the reader task, which answers requests it cannot deserialize.
+    writer: Arc<tokio::sync::Mutex<W>>,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl<W: AsyncWrite + Unpin> LspServer<W> {
+    pub fn new<R: AsyncBufRead + Send + Unpin + 'static>(
+        mut reader: R,
+        writer: W,
+        client_message_sender: ClientMessageSender,
+    ) -> Self
+    where
+        W: Send + 'static,
+    {
+        let writer = Arc::new(tokio::sync::Mutex::new(writer));
+        let state = Arc::new(Mutex::new(State::default()));
+        let handle = tokio::spawn({
+            let writer = writer.clone();
+            let state = state.clone();
+
+            async move {
+                loop {
+                    let message = match Message::<Value, Value>::try_read(&mut reader).await {
+                        Ok(Ok(message)) => message,
+                        Ok(Err(err)) => {
+                            let response = Response::<(), ()>::with_error(
+                                None,
+                                Error::new(Code::ParseError, err.to_string().into(), None),
+                            );
+                            let error = ProtocolError::InvalidMessage(err.to_string());
+
+                            if let Err(err) = response.write(&mut *writer.lock().await).await {
+                                let error = ProtocolError::Transport(err.to_string());
+                                let _ = client_message_sender.send(ClientMessage::Error(error));
+                                break;
+                            }
+
+                            if client_message_sender
+                                .send(ClientMessage::Error(error))
+                                .is_err()
+                            {
+                                break;
+                            }
+
+                            continue;
+                        }
+                        Err(err) => {
+                            let error = ProtocolError::Transport(err.to_string());
+                            let _ = client_message_sender.send(ClientMessage::Error(error));
+                            break;
+                        }
+                    };
+
+                    let message = match message {
+                        Message::Request(request) => {
+                            let id = request.id.clone();
+                            let (message, error) = client_request(request);
+
+                            if let Some(error) = error {
+                                let response = Response::<(), ()>::with_error(Some(id), error);
+
+                                if let Err(err) = response.write(&mut *writer.lock().await).await {
+                                    let error = ProtocolError::Transport(err.to_string());
+                                    let _ = client_message_sender.send(ClientMessage::Error(error));
+                                    break;
+                                }
+                            }
+
+                            message
+                        }
+                        Message::Notification(notification) => client_notification(notification),
+                        Message::Response(response) => match receive_response(&state, response) {
+                            Ok(()) => continue,
+                            Err(error) => ClientMessage::Error(error),
+                        },
+                    };
+
+                    if client_message_sender.send(message).is_err() {
+                        break;
+                    }
+                }
+
+                // No more responses
+                state.lock().unwrap().close();
+            }
+        });
+
+        Self {
+            id: 0,
+            writer,
+            state,
+            handle,
+        }
+    }
+
+    pub fn notification(&mut self) -> LspServerNotification<'_, W> {
+        LspServerNotification { server: self }
+    }
+
+    pub fn request(&mut self) -> LspServerRequest<'_, W> {
+        LspServerRequest { server: self }
+    }
+
+    pub fn response(&mut self) -> LspServerResponse<'_, W> {
+        LspServerResponse { server: self }
+    }
+}
+
+/// Private.
+impl<W: AsyncWrite + Unpin> LspServer<W> {
+    pub(crate) async fn send_notification<T: NotificationTrait>(
+        &mut self,
+        params: T::Params,
+    ) -> io::Result<()> {
+        Notification::new(T::METHOD.into(), T::params(params))
+            .write(&mut *self.writer.lock().await)
+            .await
+    }
+
+    pub(crate) async fn send_request<T: RequestTrait>(
+        &mut self,
+        params: T::Params,
+    ) -> io::Result<PendingResponse<T>> {
+        let id = Id::Integer({
+            let id = self.id;
+            self.id += 1;
+            id
+        });
+
+        self.state.lock().unwrap().insert(id.clone())?;
+
+        Request::new(id.clone(), T::METHOD.into(), T::params(params))
+            .write(&mut *self.writer.lock().await)
+            .await?;
+
+        // Cancelled requests are not notified to the client
+        Ok(PendingResponse::new(self.state.clone(), id, None))
+    }
+
+    pub(crate) async fn send_response<T: RequestTrait>(
+        &mut self,
+        id: Option<Id>,
+        data: Result<T::Result, Error<T::Error>>,
+    ) -> io::Result<()> {
+        match data {
+            Ok(result) => Response::with_result(id, result),
+            Err(error) => Response::with_error(id, T::error(error)),
+        }
+        .write(&mut *self.writer.lock().await)
+        .await
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Drop for LspServer<W> {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// Deserializes a client `request`, with the error to answer it with if invalid.
+fn client_request(request: Request<Value>) -> (ClientMessage, Option<Error<()>>) {
+    let method = request.method.to_string();
+
+    if !ClientRequest::METHODS.contains(&method.as_str()) {
+        let error = Error::new(Code::MethodNotFound, "Method not found".into(), None);
+        let message = ClientMessage::UnknownRequest {
+            id: request.id,
+            method,
+            params: request.params,
+        };
+
+        return (message, Some(error));
+    }
+
+    match ClientRequest::deserialize(request) {
+        Ok(request) => (ClientMessage::ClientRequest(request), None),
+        Err(err) => {
+            let error = Error::new(Code::InvalidParams, err.to_string().into(), None);
+            let message = ClientMessage::Error(ProtocolError::InvalidParams {
+                method,
+                error: err.to_string(),
+            });
+
+            (message, Some(error))
+        }
+    }
+}
+
+/// Deserializes a client `notification`.
+fn client_notification(notification: Notification<Value>) -> ClientMessage {
+    let method = notification.method.to_string();
+
+    if !ClientNotification::METHODS.contains(&method.as_str()) {
+        return ClientMessage::UnknownNotification {
+            method,
+            params: notification.params,
+        };
+    }
+
+    match ClientNotification::deserialize(notification) {
+        Ok(notification) => ClientMessage::ClientNotification(notification),
+        Err(err) => ClientMessage::Error(ProtocolError::InvalidParams {
+            method,
+            error: err.to_string(),
+        }),
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                     LspServerNotification                                      //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct LspServerNotification<'server, W: AsyncWrite + Unpin> {
+    pub(crate) server: &'server mut LspServer<W>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                        LspServerRequest                                        //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct LspServerRequest<'server, W: AsyncWrite + Unpin> {
+    pub(crate) server: &'server mut LspServer<W>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                       LspServerResponse                                        //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct LspServerResponse<'server, W: AsyncWrite + Unpin> {
+    pub(crate) server: &'server mut LspServer<W>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Tests                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        enumerations::MessageType, structures::LogMessageParams, LspClient, Null, ServerMessage,
+        ServerNotification,
+    };
+    use tokio::{
+        io::{AsyncWriteExt, BufReader},
+        sync::mpsc::unbounded_channel,
+    };
+
+    #[tokio::test]
+    async fn round_trip() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, client_writer) = tokio::io::split(client_stream);
+        let (server_reader, server_writer) = tokio::io::split(server_stream);
+        let (server_message_sender, mut server_message_receiver) = unbounded_channel();
+        let (client_message_sender, mut client_message_receiver) = unbounded_channel();
+        let mut client = LspClient::new(
+            BufReader::new(client_reader),
+            client_writer,
+            server_message_sender,
+        );
+        let mut server = LspServer::new(
+            BufReader::new(server_reader),
+            server_writer,
+            client_message_sender,
+        );
+
+        // Client to server request
+        let response = client.request().shutdown().await.unwrap();
+        let Some(ClientMessage::ClientRequest(ClientRequest::Shutdown(id))) =
+            client_message_receiver.recv().await
+        else {
+            panic!("Expected a shutdown request");
+        };
+        server
+            .response()
+            .shutdown(Some(id), Ok(Null))
+            .await
+            .unwrap();
+        assert_eq!(response.await.unwrap(), Ok(Null));
+
+        // Server to client notification
+        let params = LogMessageParams {
+            type_: MessageType::Info,
+            message: String::from("Hello"),
+        };
+        server
+            .notification()
+            .window_log_message(params.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            server_message_receiver.recv().await,
+            Some(ServerMessage::ServerNotification(
+                ServerNotification::WindowLogMessage(params)
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn answers_invalid_requests() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client_reader, mut client_writer) = tokio::io::split(client_stream);
+        let (server_reader, server_writer) = tokio::io::split(server_stream);
+        let mut client_reader = BufReader::new(client_reader);
+        let (client_message_sender, mut client_message_receiver) = unbounded_channel();
+        let _server = LspServer::new(
+            BufReader::new(server_reader),
+            server_writer,
+            client_message_sender,
+        );
+
+        // Invalid JSON is answered without an id, and skipped
+        client_writer
+            .write_all(b"Content-Length: 5\r\n\r\n{bad}")
+            .await
+            .unwrap();
+        let Message::<Value, Value>::Response(response) =
+            Message::read(&mut client_reader).await.unwrap()
+        else {
+            panic!("Expected a response");
+        };
+        assert_eq!(response.id, None);
+        assert_eq!(response.error.unwrap().code, Code::ParseError);
+        assert!(matches!(
+            client_message_receiver.recv().await,
+            Some(ClientMessage::Error(ProtocolError::InvalidMessage(_)))
+        ));
+
+        for (id, method, code) in [
+            (0, "experimental/unknown", Code::MethodNotFound),
+            (1, "textDocument/hover", Code::InvalidParams),
+        ] {
+            Request::new(Id::Integer(id), method.into(), Some(Value::Bool(true)))
+                .write(&mut client_writer)
+                .await
+                .unwrap();
+            let Message::<Value, Value>::Response(response) =
+                Message::read(&mut client_reader).await.unwrap()
+            else {
+                panic!("Expected a response");
+            };
+            assert_eq!(response.id, Some(Id::Integer(id)));
+            assert_eq!(response.error.unwrap().code, code);
+        }
+
+        assert!(matches!(
+            client_message_receiver.recv().await,
+            Some(ClientMessage::UnknownRequest { method, .. }) if method == "experimental/unknown"
+        ));
+        assert!(matches!(
+            client_message_receiver.recv().await,
+            Some(ClientMessage::Error(ProtocolError::InvalidParams { method, .. }))
+                if method == "textDocument/hover"
+        ));
+    }
+}
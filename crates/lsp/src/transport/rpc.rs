@@ -47,6 +47,8 @@ pub enum Message<T, E> {
 }
 
 impl<T, E> Message<T, E> {
+    /// Reads a message, failing on invalid ones (see [`Self::try_read`]).
+    #[cfg(test)]
     pub async fn read<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Self>
     where
         T: DeserializeOwned,
@@ -55,6 +57,20 @@ impl<T, E> Message<T, E> {
         read(reader).await
     }
 
+    /// Reads a message, telling transport errors (outer) from invalid messages (inner), which
+    /// can be skipped.
+    pub async fn try_read<R: AsyncBufRead + Unpin>(
+        reader: &mut R,
+    ) -> io::Result<serde_json::Result<Self>>
+    where
+        T: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        Ok(serde_json::from_slice(
+            lsp::Message::read(reader).await?.content(),
+        ))
+    }
+
     pub async fn write<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> io::Result<()>
     where
         T: Serialize,
@@ -4,13 +4,25 @@ pub mod ui;
 pub mod views {
     mod document;
     mod files;
+    mod list;
+    mod prompt;
+    mod references;
+    mod signature;
+    mod status;
 
     pub use document::*;
     pub use files::*;
+    pub use list::*;
+    pub use prompt::*;
+    pub use references::*;
+    pub use signature::*;
+    pub use status::*;
 }
 
 // For convenience.
-pub use virus_graphics::Catppuccin;
+pub use virus_graphics::{text::FontDiscoveryError, Catppuccin};
+
+use virus_graphics::text::{FontSize, LineHeight};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 //                                           LineColumn                                           //
@@ -21,3 +33,14 @@ pub struct LineColumn {
     pub line: usize,
     pub column: usize,
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                             Sizes                                              //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Scaled text sizes, shared by the views.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Sizes {
+    pub font_size: FontSize,
+    pub line_height: LineHeight,
+}
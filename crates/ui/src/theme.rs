@@ -6,10 +6,19 @@ use virus_graphics::{
     types::{Rgb, Rgba},
 };
 
+/// UI theme, with sizes in logical pixels, scaled by the window's scale factor.
 pub struct Theme {
     pub syntax: SyntaxTheme,
+    /// Family name of the system fonts.
+    pub font_family: String,
+    /// Family names of the fallback system fonts, in order, skipped when not installed.
+    pub font_fallbacks: Vec<String>,
     pub font_size: FontSize,
     pub line_height: LineHeight,
+    /// Text gamma (`> 1.` thins light text), see `Graphics::set_text_gamma`.
+    pub text_gamma: f32,
+    /// Text contrast (`> 0.` thickens dark text), see `Graphics::set_text_gamma`.
+    pub text_contrast: f32,
     pub scrollbar_color: Rgb,
     pub scroll_duration: Duration,
     pub scroll_tween: Tween,
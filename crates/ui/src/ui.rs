@@ -1,14 +1,21 @@
 use crate::{
     theme::Theme,
     tween::Tweened,
-    views::{DocumentView, FilesView},
+    views::{
+        DocumentView, FilesView, ListView, PromptView, ReferencesView, SignatureView, StatusView,
+    },
+    Catppuccin, Sizes,
+};
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+use virus_editor::{
+    document::Document,
+    lsp::{Location, Signature},
 };
-use std::{ops::Range, sync::Arc, time::Duration};
-use virus_editor::document::Document;
 use virus_graphics::{
-    text::{Context, Font, FontStyle, FontWeight, Fonts},
+    backend::Graphics,
+    text::{Context, FontDiscoveryError, FontFamilyKey, FontSize, Fonts, SystemFonts},
     types::{Rectangle, Rgba},
-    wgpu::Graphics,
+    wgpu,
 };
 use winit::window::Window;
 
@@ -17,66 +24,85 @@ use winit::window::Window;
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
 
 pub struct Ui {
-    window: Arc<Window>,
+    /// The window, `None` when rendering offscreen.
+    window: Option<Arc<Window>>,
     graphics: Graphics,
     context: Context,
     theme: Theme,
+    /// The window's scale factor, scaling the theme's sizes.
+    scale: f64,
+    /// The theme's sizes, scaled.
+    sizes: Sizes,
     document_view: DocumentView,
     scroll_top: Tweened<u32>,
     scrollbar_alpha: Tweened<u8>,
     files_view: FilesView,
+    references_view: ReferencesView,
+    prompt_view: PromptView,
+    list_view: ListView,
+    signature_view: SignatureView,
+    status_view: StatusView,
 }
 
 impl Ui {
-    pub fn new(window: Arc<Window>, theme: Theme) -> Self {
-        let graphics = Graphics::new(&window);
-        let context = Context::new(fonts());
-        let family = context.fonts().get("Victor").unwrap();
-
-        let document_view = DocumentView::new(
-            family.key(),
-            theme.syntax,
-            theme.font_size,
-            theme.line_height,
-        );
-        let files_view = FilesView::new(
-            family.key(),
-            theme.font_size,
-            theme.line_height,
-            Rgba::WHITE,
-        );
+    /// Creates the UI of `window`, with the `theme.font_family` system fonts
+    /// and the installed `theme.font_fallbacks`.
+    pub fn new(window: Arc<Window>, theme: Theme) -> Result<Self, FontDiscoveryError> {
+        let graphics = wgpu::Graphics::new(&window).into();
+        let scale = window.scale_factor();
 
-        Self {
-            window,
-            graphics,
-            context,
-            theme,
-            document_view,
-            scroll_top: Tweened::new(0),
-            scrollbar_alpha: Tweened::new(0),
-            files_view,
-        }
+        Self::with_graphics(Some(window), graphics, scale, theme)
+    }
+
+    /// Creates a windowless UI rendering with `graphics` (e.g. [`wgpu::Graphics::offscreen`], or
+    /// [`virus_graphics::cpu::Graphics`] without an adapter) at
+    /// `scale`, with the `theme.font_family` system fonts and the installed
+    /// `theme.font_fallbacks`.
+    pub fn offscreen(
+        graphics: Graphics,
+        scale: f64,
+        theme: Theme,
+    ) -> Result<Self, FontDiscoveryError> {
+        Self::with_graphics(None, graphics, scale, theme)
     }
 
-    pub fn window(&self) -> &Window {
-        &self.window
+    /// Returns the window, `None` when rendering offscreen.
+    pub fn window(&self) -> Option<&Window> {
+        self.window.as_deref()
+    }
+
+    /// Returns the graphics, e.g. to read offscreen frames back.
+    pub fn graphics(&self) -> &Graphics {
+        &self.graphics
+    }
+
+    /// Requests a redraw of the window, if any.
+    pub fn request_redraw(&self) {
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
     }
 
     pub fn theme(&self) -> &Theme {
         &self.theme
     }
 
+    /// Returns the scale factor, as of the last [`Self::rescale()`] or [`Self::set_scale()`].
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
     pub fn is_animating(&self) -> bool {
         self.scroll_top.is_animating() || self.scrollbar_alpha.is_animating()
     }
 
     pub fn scroll_up(&mut self) {
-        let scroll = self.screen_height_in_lines() / 2 * self.document_view.line_height();
+        let scroll = self.screen_height_in_lines() / 2 * self.sizes.line_height;
         self.scroll_to(self.scroll_top.end().saturating_sub(scroll))
     }
 
     pub fn scroll_down(&mut self) {
-        let line_height = self.document_view.line_height();
+        let line_height = self.sizes.line_height;
         let rope_lines = self.document_view.rope().len_lines() as u32;
         let screen_height_in_lines = self.screen_height_in_lines();
 
@@ -87,7 +113,7 @@ impl Ui {
     }
 
     pub fn ensure_visibility(&mut self, line: usize) {
-        let line_height = self.document_view.line_height();
+        let line_height = self.sizes.line_height;
         let screen_height_in_lines = self.screen_height_in_lines();
         let line = line as u32;
         let start = self.scroll_top.end() / line_height;
@@ -100,8 +126,52 @@ impl Ui {
         }
     }
 
+    /// Returns the lines of the document visible once scrolling ends.
+    pub fn visible_lines(&self) -> Range<usize> {
+        let start = self.scroll_top.end() / self.sizes.line_height;
+        let end = start + self.screen_height_in_lines();
+
+        start as usize..end as usize
+    }
+
+    /// Resizes to the window's size.
     pub fn resize(&mut self) {
-        self.graphics.resize(&self.window);
+        if let Some(window) = &self.window {
+            let size = window.inner_size();
+            self.graphics.resize(size.width, size.height);
+        }
+    }
+
+    /// Resizes to `width` by `height`, when rendering offscreen.
+    pub fn resize_offscreen(&mut self, width: u32, height: u32) {
+        debug_assert!(self.window.is_none(), "Windows resize with their window");
+        self.graphics.resize(width, height);
+    }
+
+    /// Scales the theme's sizes to the window's new scale factor.
+    pub fn rescale(&mut self) {
+        if let Some(window) = &self.window {
+            self.set_scale(window.scale_factor());
+        }
+    }
+
+    /// Scales the theme's sizes by `scale`, e.g. when rendering offscreen.
+    pub fn set_scale(&mut self, scale: f64) {
+        if scale == self.scale {
+            return;
+        }
+
+        let sizes = scale_sizes(&self.theme, scale);
+
+        // Keep the same top line
+        let top = self.scroll_top.end() / self.sizes.line_height;
+        self.scroll_top = Tweened::new(top * sizes.line_height);
+        self.sizes = sizes;
+
+        // Glyphs of the previous sizes won't be used anymore
+        self.graphics.clear_glyphs();
+        self.resize();
+        self.scale = scale;
     }
 
     pub fn update(&mut self, delta: Duration) {
@@ -118,11 +188,18 @@ impl Ui {
         caret_width: u32,
         selection_color: Rgba,
         search: Option<(&'a str, &'a [(String, isize, Vec<Range<usize>>)], usize)>,
+        references: Option<(&'a Path, &'a [(Location, String)], usize)>,
+        list: Option<(&'a str, &'a [String], usize)>,
+        prompt: Option<(&'a str, &'a str)>,
+        signature: Option<&'a Signature>,
+        status: &'a [String],
     ) {
         let region = self.region();
+        let caret_width = scale_pixels(caret_width, self.scale);
 
         self.document_view.render(
             &mut self.context,
+            self.sizes,
             &mut self.graphics.layer(region, 0),
             document,
             self.scroll_top.current(),
@@ -136,28 +213,170 @@ impl Ui {
             selection_color,
         );
 
+        if !status.is_empty() {
+            self.status_view.render(
+                &mut self.context,
+                self.sizes,
+                self.graphics.layer(region, 1),
+                status,
+            );
+        }
+
+        if let Some(signature) = signature {
+            self.signature_view.render(
+                &mut self.context,
+                self.sizes,
+                self.graphics.layer(region, 1),
+                self.document_view.caret(),
+                signature,
+            );
+        }
+
         if let Some((needle, haystack, selected)) = search {
             self.files_view.render(
                 &mut self.context,
+                self.sizes,
                 self.graphics.layer(region, 1),
                 needle,
                 haystack,
                 selected,
             );
         }
+
+        if let Some((root, references, selected)) = references {
+            let region = self.bottom_region();
+
+            self.references_view.render(
+                &mut self.context,
+                self.sizes,
+                self.graphics.layer(region, 1),
+                root,
+                references,
+                selected,
+            );
+        }
+
+        if let Some((title, items, selected)) = list {
+            let region = self.bottom_region();
+
+            self.list_view.render(
+                &mut self.context,
+                self.sizes,
+                self.graphics.layer(region, 1),
+                title,
+                items,
+                selected,
+            );
+        }
+
+        if let Some((label, input)) = prompt {
+            let region = self.prompt_region();
+
+            self.prompt_view.render(
+                &mut self.context,
+                self.sizes,
+                self.graphics.layer(region, 1),
+                label,
+                input,
+            );
+        }
+
         self.graphics.render();
     }
 }
 
 /// Private.
 impl Ui {
+    /// Creates the UI rendering with `graphics` at `scale`.
+    fn with_graphics(
+        window: Option<Arc<Window>>,
+        mut graphics: Graphics,
+        scale: f64,
+        theme: Theme,
+    ) -> Result<Self, FontDiscoveryError> {
+        let (fonts, family) = fonts(&theme.font_family, &theme.font_fallbacks)?;
+        graphics.set_clear_color(Catppuccin::default().crust);
+        graphics.set_text_gamma(theme.text_gamma, theme.text_contrast);
+        let context = Context::new(fonts);
+        let family = context.fonts().get(family).expect("Loaded family");
+        let sizes = scale_sizes(&theme, scale);
+
+        let document_view = DocumentView::new(family.key(), theme.syntax);
+        let files_view = FilesView::new(family.key(), Rgba::WHITE);
+        let references_view = {
+            let catppuccin = Catppuccin::default();
+
+            ReferencesView::new(
+                family.key(),
+                catppuccin.text,
+                catppuccin.blue,
+                catppuccin.mantle,
+            )
+        };
+        let list_view = {
+            let catppuccin = Catppuccin::default();
+
+            ListView::new(
+                family.key(),
+                catppuccin.text,
+                catppuccin.blue,
+                catppuccin.mantle,
+            )
+        };
+        let prompt_view = {
+            let catppuccin = Catppuccin::default();
+
+            PromptView::new(
+                family.key(),
+                catppuccin.text,
+                catppuccin.blue,
+                catppuccin.mantle,
+            )
+        };
+
+        let signature_view = {
+            let catppuccin = Catppuccin::default();
+
+            SignatureView::new(
+                family.key(),
+                catppuccin.text,
+                catppuccin.peach,
+                catppuccin.surface0,
+            )
+        };
+
+        let status_view = {
+            let catppuccin = Catppuccin::default();
+
+            StatusView::new(family.key(), catppuccin.subtext0, catppuccin.mantle)
+        };
+
+        Ok(Self {
+            window,
+            graphics,
+            context,
+            theme,
+            scale,
+            sizes,
+            document_view,
+            scroll_top: Tweened::new(0),
+            scrollbar_alpha: Tweened::new(0),
+            files_view,
+            references_view,
+            prompt_view,
+            list_view,
+            signature_view,
+            status_view,
+        })
+    }
+
     fn screen_height_in_lines(&self) -> u32 {
-        self.window.inner_size().height / self.document_view.line_height()
+        self.graphics.size().height / self.sizes.line_height
     }
 
     fn region(&self) -> Rectangle {
-        let size = self.window.inner_size();
-        let height = self.screen_height_in_lines() * self.document_view.line_height();
+        let size = self.graphics.size();
+        let height = self.screen_height_in_lines() * self.sizes.line_height;
 
         Rectangle {
             top: (size.height - height) as i32 / 2,
@@ -167,6 +386,33 @@ impl Ui {
         }
     }
 
+    /// The bottom third of the region, for pickers that preview in the document.
+    fn bottom_region(&self) -> Rectangle {
+        let region = self.region();
+        let line_height = self.sizes.line_height;
+        let height = region.height / 3 / line_height * line_height;
+
+        Rectangle {
+            top: region.bottom() - height as i32,
+            left: region.left,
+            width: region.width,
+            height,
+        }
+    }
+
+    /// The last line of the region, for prompts.
+    fn prompt_region(&self) -> Rectangle {
+        let region = self.region();
+        let line_height = self.sizes.line_height;
+
+        Rectangle {
+            top: region.bottom() - line_height as i32,
+            left: region.left,
+            width: region.width,
+            height: line_height,
+        }
+    }
+
     fn scroll_to(&mut self, scroll_top: u32) {
         self.scroll_top.to(
             scroll_top,
@@ -178,89 +424,53 @@ impl Ui {
     }
 }
 
-fn fonts() -> Fonts {
-    use virus_graphics::text::{FontStyle::*, FontWeight::*};
-
-    const EMOJI: &str = "/System/Library/Fonts/Apple Color Emoji.ttc";
-    const FOLDER: &str = "/Users/romain/Library/Fonts/";
-    const FONTS: &[(&str, &[(&str, FontWeight, FontStyle)])] = &[
-        (
-            "Victor",
-            &[
-                // Normal
-                ("VictorMono-Thin.ttf", Thin, Normal),
-                ("VictorMono-ExtraLight.ttf", ExtraLight, Normal),
-                ("VictorMono-Light.ttf", Light, Normal),
-                ("VictorMono-Regular.ttf", Regular, Normal),
-                ("VictorMono-Medium.ttf", Medium, Normal),
-                ("VictorMono-SemiBold.ttf", SemiBold, Normal),
-                ("VictorMono-Bold.ttf", Bold, Normal),
-                // Italic
-                ("VictorMono-ThinItalic.ttf", Thin, Italic),
-                ("VictorMono-ExtraLightItalic.ttf", ExtraLight, Italic),
-                ("VictorMono-LightItalic.ttf", Light, Italic),
-                ("VictorMono-Italic.ttf", Regular, Italic),
-                ("VictorMono-MediumItalic.ttf", Medium, Italic),
-                ("VictorMono-SemiBoldItalic.ttf", SemiBold, Italic),
-                ("VictorMono-BoldItalic.ttf", Bold, Italic),
-                // Oblique
-                ("VictorMono-ThinOblique.ttf", Thin, Oblique),
-                ("VictorMono-ExtraLightOblique.ttf", ExtraLight, Oblique),
-                ("VictorMono-LightOblique.ttf", Light, Oblique),
-                ("VictorMono-Oblique.ttf", Regular, Oblique),
-                ("VictorMono-MediumOblique.ttf", Medium, Oblique),
-                ("VictorMono-SemiBoldOblique.ttf", SemiBold, Oblique),
-                ("VictorMono-BoldOblique.ttf", Bold, Oblique),
-            ],
-        ),
-        (
-            "JetBrains",
-            &[
-                // Normal
-                ("JetBrainsMonoNerdFont-Thin.ttf", Thin, Normal),
-                ("JetBrainsMonoNerdFont-ExtraLight.ttf", ExtraLight, Normal),
-                ("JetBrainsMonoNerdFont-Light.ttf", Light, Normal),
-                ("JetBrainsMonoNerdFont-Regular.ttf", Regular, Normal),
-                ("JetBrainsMonoNerdFont-Medium.ttf", Medium, Normal),
-                ("JetBrainsMonoNerdFont-SemiBold.ttf", SemiBold, Normal),
-                ("JetBrainsMonoNerdFont-Bold.ttf", Bold, Normal),
-                ("JetBrainsMonoNerdFont-ExtraBold.ttf", ExtraBold, Normal),
-                //
-                ("JetBrainsMonoNerdFont-ThinItalic.ttf", Thin, Italic),
-                (
-                    "JetBrainsMonoNerdFont-ExtraLightItalic.ttf",
-                    ExtraLight,
-                    Italic,
-                ),
-                ("JetBrainsMonoNerdFont-LightItalic.ttf", Light, Italic),
-                ("JetBrainsMonoNerdFont-Italic.ttf", Regular, Italic),
-                ("JetBrainsMonoNerdFont-MediumItalic.ttf", Medium, Italic),
-                ("JetBrainsMonoNerdFont-SemiBoldItalic.ttf", SemiBold, Italic),
-                ("JetBrainsMonoNerdFont-BoldItalic.ttf", Bold, Italic),
-                (
-                    "JetBrainsMonoNerdFont-ExtraBoldItalic.ttf",
-                    ExtraBold,
-                    Italic,
-                ),
-            ],
-        ),
-    ];
-
-    let mut fonts = Fonts::new(Font::from_file(EMOJI).unwrap());
-
-    for (family, faces) in FONTS {
-        let family = fonts.set(String::from(*family)).unwrap();
-
-        for (font, font_weight, font_style) in *faces {
-            let font = fonts
-                .set(Font::from_file(String::from(FOLDER) + font).unwrap())
-                .unwrap();
-
-            fonts
-                .set((family, *font_weight, *font_style, font))
-                .unwrap();
+/// Scales the sizes of `theme` by `scale`.
+fn scale_sizes(theme: &Theme, scale: f64) -> Sizes {
+    Sizes {
+        font_size: scale_font_size(theme.font_size, scale),
+        line_height: scale_pixels(theme.line_height, scale),
+    }
+}
+
+/// Scales `font_size` by `scale`.
+fn scale_font_size(font_size: FontSize, scale: f64) -> FontSize {
+    (font_size as f64 * scale)
+        .round()
+        .clamp(1., FontSize::MAX as f64) as FontSize
+}
+
+/// Scales `pixels` by `scale`.
+fn scale_pixels(pixels: u32, scale: f64) -> u32 {
+    (pixels as f64 * scale).round() as u32
+}
+
+/// Loads `family` (or an installed monospace family) from the system fonts, with the regular
+/// faces of the installed `fallbacks` and an emoji fallback.
+fn fonts(family: &str, fallbacks: &[String]) -> Result<(Fonts, FontFamilyKey), FontDiscoveryError> {
+    let system_fonts = SystemFonts::scan();
+    let family = match system_fonts.family(family).next() {
+        Some(_) => family,
+        None => match system_fonts.monospace() {
+            Some(monospace) => {
+                eprintln!("Font family `{family}` not found, using `{monospace}`");
+                monospace
+            }
+            None => family,
+        },
+    };
+    let mut fonts = Fonts::new(system_fonts.emoji(family)?);
+    let family = system_fonts.load(&mut fonts, family)?;
+    let mut keys = Vec::new();
+
+    for fallback in fallbacks {
+        match system_fonts.load_regular(&mut fonts, fallback) {
+            Ok(key) => keys.push(key),
+            Err(FontDiscoveryError::FamilyNotFound(..)) => {}
+            Err(error) => return Err(error),
         }
     }
 
-    fonts
+    fonts.set_fallbacks(keys);
+
+    Ok((fonts, family))
 }
@@ -1,12 +1,13 @@
-use crate::LineColumn;
+use crate::{LineColumn, Sizes};
 use ropey::Rope;
+use std::ops::Range;
 use virus_editor::{document::Document, syntax::Theme};
 use virus_graphics::{
+    backend::Layer,
     text::{
         Advance, Context, FontFamilyKey, FontSize, FontStyle, FontWeight, Line, LineHeight, Styles,
     },
     types::{Position, Rectangle, Rgba},
-    wgpu::Layer,
 };
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -16,24 +17,17 @@ use virus_graphics::{
 pub struct DocumentView {
     family: FontFamilyKey,
     theme: Theme,
-    font_size: FontSize,
-    line_height: LineHeight,
     rope: Rope,
+    caret: Position,
 }
 
 impl DocumentView {
-    pub fn new(
-        family: FontFamilyKey,
-        theme: Theme,
-        font_size: FontSize,
-        line_height: LineHeight,
-    ) -> Self {
+    pub fn new(family: FontFamilyKey, theme: Theme) -> Self {
         Self {
             family,
             theme,
-            font_size,
-            line_height,
             rope: Default::default(),
+            caret: Default::default(),
         }
     }
 
@@ -41,21 +35,19 @@ impl DocumentView {
         self.family
     }
 
-    pub fn font_size(&self) -> FontSize {
-        self.font_size
-    }
-
-    pub fn line_height(&self) -> LineHeight {
-        self.line_height
-    }
-
     pub fn rope(&self) -> &Rope {
         &self.rope
     }
 
+    /// Returns the position of the caret (top left), as of the last render.
+    pub fn caret(&self) -> Position {
+        self.caret
+    }
+
     pub fn render(
         &mut self,
         context: &mut Context,
+        sizes: Sizes,
         layer: &mut Layer,
         document: &mut Document,
         scroll_top: u32,
@@ -66,13 +58,18 @@ impl DocumentView {
         caret_width: u32,
         selection_color: Rgba,
     ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
         // NOTE: I'd like this the be done outside this file (or even better outside this crate)
 
         self.rope = document.rope().clone();
 
         let rope_lines = document.rope().len_lines();
-        let region_height_in_lines = layer.size().height as f32 / self.line_height as f32;
-        let scroll_top_in_lines = scroll_top as f32 / self.line_height as f32;
+        let region_height_in_lines = layer.size().height as f32 / line_height as f32;
+        let scroll_top_in_lines = scroll_top as f32 / line_height as f32;
 
         let (start_line, end_line) = {
             let start = scroll_top_in_lines.floor() as usize;
@@ -88,7 +85,7 @@ impl DocumentView {
             .fonts()
             .get((self.family, FontWeight::Regular, FontStyle::Normal))
             .unwrap()
-            .advance_for_size(self.font_size);
+            .advance_for_size(font_size);
 
         let scrollbar_rectangle = if rope_lines <= region_height_in_lines as usize {
             Rectangle::default()
@@ -119,15 +116,15 @@ impl DocumentView {
             start_line..end_line,
             self.family,
             self.theme,
-            self.font_size,
+            font_size,
         );
 
-        Renderer {
+        self.caret = Renderer {
             context,
             layer,
             family: self.family,
-            font_size: self.font_size,
-            line_height: self.line_height,
+            font_size,
+            line_height,
             anchor,
             head,
             start_line,
@@ -143,7 +140,7 @@ impl DocumentView {
             caret_width,
             selection_color,
         }
-        .render()
+        .render();
     }
 }
 
@@ -176,11 +173,17 @@ struct Renderer<'context, 'layer, 'graphics, 'lines, 'outline_colors> {
 impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
     Renderer<'context, 'layer, 'graphics, 'lines, 'outline_colors>
 {
-    fn render(&mut self) {
+    /// Renders, and returns the caret position.
+    fn render(&mut self) -> Position {
         self.render_line_numbers();
         self.render_lines();
         self.render_selection();
         self.render_scrollbar();
+
+        Position {
+            top: self.row(self.head),
+            left: self.column(self.head),
+        }
     }
 
     fn render_line_numbers(&mut self) {
@@ -191,6 +194,7 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
             background: Rgba::TRANSPARENT,
             underline: false,
             strike: false,
+            features: Default::default(),
         };
 
         for number in self.start_line..self.start_line + self.lines.len() {
@@ -231,26 +235,6 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
 
     fn render_selection(&mut self) {
         let pos = |top, left| Position { top, left };
-        let row = |LineColumn { line, .. }| {
-            line as i32 * self.line_height as i32 - self.scroll_top as i32
-        };
-        let column = |LineColumn { line, column }| -> i32 {
-            self.line_numbers_width as i32
-                + if (self.start_line..self.start_line + self.lines.len()).contains(&line) {
-                    let line = &self.lines[line - self.start_line];
-
-                    line.glyphs()
-                        .iter()
-                        .find_map(|glyph| {
-                            // TODO: consecutive glyphs may have same range!
-                            (glyph.range.end as usize > column).then_some(glyph.offset)
-                        })
-                        .unwrap_or_else(|| line.advance())
-                        .round() as i32
-                } else {
-                    0
-                }
-        };
 
         let layer = 1;
         let (selection, is_forward) = if self.anchor <= self.head {
@@ -259,10 +243,10 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
             (self.head..self.anchor, false)
         };
         let (width, height) = (self.layer.size().width as i32, self.line_height as i32);
-        let top = row(selection.start);
-        let bottom = row(selection.end);
-        let start = column(selection.start);
-        let end = column(selection.end);
+        let top = self.row(selection.start);
+        let bottom = self.row(selection.end);
+        let start = self.column(selection.start);
+        let end = self.column(selection.end);
 
         let render_outline = |renderer: &mut Renderer, top, bottom, left, right| {
             for (i, color) in renderer.outline_colors.iter().copied().enumerate() {
@@ -327,7 +311,18 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
             } else {
                 render_outline(self, Some(top), Some(bottom), 0, start);
                 render_outline(self, Some(top), Some(bottom), end, width);
-                render_selection(self, top, start, end - start, height);
+                for columns in self.columns(
+                    selection.start.line,
+                    selection.start.column..selection.end.column,
+                ) {
+                    render_selection(
+                        self,
+                        top,
+                        columns.start,
+                        columns.end - columns.start,
+                        height,
+                    );
+                }
             }
             render_caret(self, top, if is_forward { end } else { start });
         }
@@ -340,9 +335,33 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
             } else {
                 render_outline(self, Some(top), None, 0, start);
                 render_outline(self, None, Some(bottom2), end, width);
-                render_selection(self, top, start, width - start, height);
+                let line_end = self.column(LineColumn {
+                    line: selection.start.line,
+                    column: usize::MAX,
+                });
+                for columns in
+                    self.columns(selection.start.line, selection.start.column..usize::MAX)
+                {
+                    render_selection(
+                        self,
+                        top,
+                        columns.start,
+                        columns.end - columns.start,
+                        height,
+                    );
+                }
+                render_selection(self, top, line_end, width - line_end, height);
                 render_selection(self, top2, 0, width, bottom - top2);
-                render_selection(self, bottom, 0, end, height);
+                render_selection(self, bottom, 0, self.line_numbers_width as i32, height);
+                for columns in self.columns(selection.end.line, 0..selection.end.column) {
+                    render_selection(
+                        self,
+                        bottom,
+                        columns.start,
+                        columns.end - columns.start,
+                        height,
+                    );
+                }
             }
             render_caret(
                 self,
@@ -352,6 +371,33 @@ impl<'context, 'layer, 'graphics, 'lines, 'outline_colors>
         }
     }
 
+    fn row(&self, LineColumn { line, .. }: LineColumn) -> i32 {
+        line as i32 * self.line_height as i32 - self.scroll_top as i32
+    }
+
+    fn column(&self, LineColumn { line, column }: LineColumn) -> i32 {
+        self.line_numbers_width as i32
+            + if (self.start_line..self.start_line + self.lines.len()).contains(&line) {
+                self.lines[line - self.start_line].position(column).round() as i32
+            } else {
+                0
+            }
+    }
+
+    /// Returns the visual ranges of `columns` in `line`, from left to right.
+    fn columns(&self, line: usize, columns: Range<usize>) -> Vec<Range<i32>> {
+        if !(self.start_line..self.start_line + self.lines.len()).contains(&line) {
+            return Vec::new();
+        }
+
+        let left = self.line_numbers_width as i32;
+
+        self.lines[line - self.start_line]
+            .ranges(columns)
+            .map(|range| left + range.start.round() as i32..left + range.end.round() as i32)
+            .collect()
+    }
+
     fn render_scrollbar(&mut self) {
         self.layer
             .draw(None, 0)
@@ -1,15 +1,16 @@
 #![allow(unused)]
 
+use crate::Sizes;
 use std::{
     ops::{Range, RangeBounds},
     usize,
 };
 use virus_graphics::{
+    backend::{Draw, Layer},
     text::{
         Context, FontFamilyKey, FontKey, FontSize, FontStyle, FontWeight, Line, LineHeight, Styles,
     },
     types::{Position, Rectangle, Rgba},
-    wgpu::{Draw, Layer},
 };
 
 const MIN_WIDTH: f32 = 0.5;
@@ -27,25 +28,16 @@ pub struct FilesView {
     haystack: Vec<(String, Vec<Range<usize>>)>,
     selected: usize,
     family: FontFamilyKey,
-    font_size: FontSize,
-    line_height: LineHeight,
     background: Rgba,
 }
 
 impl FilesView {
-    pub fn new(
-        family: FontFamilyKey,
-        font_size: FontSize,
-        line_height: LineHeight,
-        background: Rgba,
-    ) -> Self {
+    pub fn new(family: FontFamilyKey, background: Rgba) -> Self {
         Self {
             needle: Default::default(),
             haystack: Default::default(),
             selected: 0,
             family,
-            font_size,
-            line_height,
             background,
         }
     }
@@ -53,18 +45,24 @@ impl FilesView {
     pub fn render<'a>(
         &mut self,
         context: &'a mut Context,
+        sizes: Sizes,
         layer: Layer<'a>,
         needle: &'a str,
         haystacks: &'a [(String, isize, Vec<Range<usize>>)],
         selected: usize,
     ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
         Renderer::new(
             context,
             layer,
             self.background,
             self.family,
-            self.font_size,
-            self.line_height,
+            font_size,
+            line_height,
             needle,
             haystacks,
             selected,
@@ -150,6 +148,7 @@ impl<'a> Renderer<'a> {
                 background: Default::default(),
                 underline: false,
                 strike: false,
+                features: Default::default(),
             },
         )
         .shape(self.context, self.family, self.font_size, None, None);
@@ -199,6 +198,7 @@ impl<'a> Renderer<'a> {
                     background: Default::default(),
                     underline: false,
                     strike: false,
+                    features: Default::default(),
                 },
             );
 
@@ -220,6 +220,7 @@ impl<'a> Renderer<'a> {
                         background: Default::default(),
                         underline: false,
                         strike: false,
+                        features: Default::default(),
                     };
                     start = i;
                 }
@@ -0,0 +1,110 @@
+use crate::Sizes;
+use virus_graphics::{
+    backend::Layer,
+    text::{Context, FontFamilyKey, FontWeight, Line, Styles},
+    types::{Position, Rectangle, Rgba},
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            ListView                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A titled list of items, with a selected item.
+pub struct ListView {
+    family: FontFamilyKey,
+    foreground: Rgba,
+    title: Rgba,
+    background: Rgba,
+}
+
+impl ListView {
+    pub fn new(family: FontFamilyKey, foreground: Rgba, title: Rgba, background: Rgba) -> Self {
+        Self {
+            family,
+            foreground,
+            title,
+            background,
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        context: &mut Context,
+        sizes: Sizes,
+        mut layer: Layer,
+        title: &str,
+        items: &[String],
+        selected: usize,
+    ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
+        layer.draw(None, 0).rectangle(None, self.background);
+
+        let title = format!("{title} ({})", items.len());
+        let line = Line::shaper(&title, 0, self.styles(self.title, FontWeight::Bold)).shape(
+            context,
+            self.family,
+            font_size,
+            None,
+            None,
+        );
+        layer
+            .draw(None, 0)
+            .glyphs(context, Position::default(), &line, line_height);
+
+        let region = Rectangle {
+            top: line_height as i32,
+            left: 0,
+            width: layer.size().width,
+            height: layer.size().height.saturating_sub(line_height),
+        };
+        let range = {
+            let region_height_in_lines = (region.height / line_height) as usize;
+
+            if selected < region_height_in_lines {
+                0..region_height_in_lines.min(items.len())
+            } else {
+                selected + 1 - region_height_in_lines..selected + 1
+            }
+        };
+        let mut position = Position::default();
+
+        for (index, item) in items.iter().enumerate().take(range.end).skip(range.start) {
+            let weight = if index == selected {
+                FontWeight::Bold
+            } else {
+                Default::default()
+            };
+            let line = Line::shaper(
+                &format!("  {item}"),
+                0,
+                self.styles(self.foreground, weight),
+            )
+            .shape(context, self.family, font_size, None, None);
+
+            layer
+                .draw(region, 0)
+                .glyphs(context, position, &line, line_height);
+
+            position.top += line_height as i32;
+        }
+    }
+}
+
+/// Private.
+impl ListView {
+    fn styles(&self, foreground: Rgba, weight: FontWeight) -> Styles {
+        Styles {
+            weight,
+            style: Default::default(),
+            foreground,
+            background: Default::default(),
+            underline: false,
+            strike: false,
+            features: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::Sizes;
+use virus_graphics::{
+    backend::Layer,
+    text::{Context, FontFamilyKey, FontWeight, Line, Styles},
+    types::{Position, Rgba},
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           PromptView                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A single line prompt, e.g. for the new name of a symbol.
+pub struct PromptView {
+    family: FontFamilyKey,
+    foreground: Rgba,
+    label: Rgba,
+    background: Rgba,
+}
+
+impl PromptView {
+    pub fn new(family: FontFamilyKey, foreground: Rgba, label: Rgba, background: Rgba) -> Self {
+        Self {
+            family,
+            foreground,
+            label,
+            background,
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        context: &mut Context,
+        sizes: Sizes,
+        mut layer: Layer,
+        label: &str,
+        input: &str,
+    ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
+        let styles = |foreground, weight| Styles {
+            weight,
+            style: Default::default(),
+            foreground,
+            background: Default::default(),
+            underline: false,
+            strike: false,
+            features: Default::default(),
+        };
+
+        let label = format!("{label}: ");
+        let text = format!("{label}{input}▏");
+        let mut shaper = Line::shaper(&text, 0, styles(self.label, FontWeight::Bold));
+
+        for cluster in shaper.clusters_mut() {
+            if (label.len()..label.len() + input.len()).contains(&cluster.range().start) {
+                *cluster.styles_mut() = styles(self.foreground, Default::default());
+            }
+        }
+
+        let line = shaper.shape(context, self.family, font_size, None, None);
+
+        layer.draw(None, 0).rectangle(None, self.background);
+        layer
+            .draw(None, 0)
+            .glyphs(context, Position::default(), &line, line_height);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::Sizes;
+use std::path::Path;
+use virus_editor::lsp::Location;
+use virus_graphics::{
+    backend::Layer,
+    text::{Context, FontFamilyKey, FontSize, FontWeight, Line, LineHeight, Styles},
+    types::{Position, Rectangle, Rgba},
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         ReferencesView                                         //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+pub struct ReferencesView {
+    family: FontFamilyKey,
+    foreground: Rgba,
+    header: Rgba,
+    background: Rgba,
+}
+
+impl ReferencesView {
+    pub fn new(family: FontFamilyKey, foreground: Rgba, header: Rgba, background: Rgba) -> Self {
+        Self {
+            family,
+            foreground,
+            header,
+            background,
+        }
+    }
+
+    pub fn render<'a>(
+        &mut self,
+        context: &'a mut Context,
+        sizes: Sizes,
+        layer: Layer<'a>,
+        root: &'a Path,
+        references: &'a [(Location, String)],
+        selected: usize,
+    ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
+        Renderer {
+            context,
+            layer,
+            family: self.family,
+            font_size,
+            line_height,
+            foreground: self.foreground,
+            header: self.header,
+            background: self.background,
+            root,
+            references,
+            selected,
+        }
+        .render();
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                            Renderer                                            //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A row of the references list: a file header or a reference index.
+#[derive(Copy, Clone, Debug)]
+enum Row<'a> {
+    File(&'a Path),
+    Reference(usize),
+}
+
+struct Renderer<'a> {
+    context: &'a mut Context,
+    layer: Layer<'a>,
+    family: FontFamilyKey,
+    font_size: FontSize,
+    line_height: LineHeight,
+    foreground: Rgba,
+    header: Rgba,
+    background: Rgba,
+    root: &'a Path,
+    references: &'a [(Location, String)],
+    selected: usize,
+}
+
+impl<'a> Renderer<'a> {
+    fn render(&mut self) {
+        self.render_background();
+        self.render_title();
+        self.render_references();
+    }
+
+    fn render_background(&mut self) {
+        self.layer.draw(None, 0).rectangle(None, self.background);
+    }
+
+    fn render_title(&mut self) {
+        let title = format!("References ({})", self.references.len());
+        let line = Line::shaper(&title, 0, self.styles(self.header, FontWeight::Bold)).shape(
+            self.context,
+            self.family,
+            self.font_size,
+            None,
+            None,
+        );
+
+        self.layer
+            .draw(None, 0)
+            .glyphs(self.context, Position::default(), &line, self.line_height);
+    }
+
+    fn render_references(&mut self) {
+        if self.references.is_empty() {
+            return;
+        }
+
+        let region = Rectangle {
+            top: self.line_height as i32,
+            left: 0,
+            width: self.layer.size().width,
+            height: self.layer.size().height.saturating_sub(self.line_height),
+        };
+        let rows = self.rows();
+        let selected = rows
+            .iter()
+            .position(|row| matches!(row, Row::Reference(index) if *index == self.selected))
+            .unwrap_or_default();
+        let range = {
+            let region_height_in_lines = (region.height / self.line_height) as usize;
+
+            if selected < region_height_in_lines {
+                0..region_height_in_lines.min(rows.len())
+            } else {
+                selected + 1 - region_height_in_lines..selected + 1
+            }
+        };
+        let mut position = Position::default();
+
+        for row in &rows[range] {
+            let line = match *row {
+                Row::File(path) => {
+                    let path = path.strip_prefix(self.root).unwrap_or(path);
+
+                    Line::shaper(
+                        &path.to_string_lossy(),
+                        0,
+                        self.styles(self.header, FontWeight::Bold),
+                    )
+                }
+                Row::Reference(index) => {
+                    let (location, preview) = &self.references[index];
+                    let (line, column) = location.range.start;
+                    let weight = if index == self.selected {
+                        FontWeight::Bold
+                    } else {
+                        Default::default()
+                    };
+
+                    Line::shaper(
+                        &format!("  {:>5}:{:<4} {preview}", line + 1, column + 1),
+                        0,
+                        self.styles(self.foreground, weight),
+                    )
+                }
+            }
+            .shape(self.context, self.family, self.font_size, None, None);
+
+            self.layer
+                .draw(region, 0)
+                .glyphs(self.context, position, &line, self.line_height);
+
+            position.top += self.line_height as i32;
+        }
+    }
+
+    /// Returns references grouped under their file header.
+    fn rows(&self) -> Vec<Row<'a>> {
+        let mut rows = Vec::with_capacity(self.references.len());
+        let mut path = None;
+
+        for (index, (location, _)) in self.references.iter().enumerate() {
+            if path != Some(location.path.as_path()) {
+                path = Some(location.path.as_path());
+                rows.push(Row::File(location.path.as_path()));
+            }
+
+            rows.push(Row::Reference(index));
+        }
+
+        rows
+    }
+
+    fn styles(&self, foreground: Rgba, weight: FontWeight) -> Styles {
+        Styles {
+            weight,
+            style: Default::default(),
+            foreground,
+            background: Default::default(),
+            underline: false,
+            strike: false,
+            features: Default::default(),
+        }
+    }
+}
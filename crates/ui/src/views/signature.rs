@@ -0,0 +1,98 @@
+use crate::Sizes;
+use virus_editor::lsp::Signature;
+use virus_graphics::{
+    backend::Layer,
+    text::{Context, FontFamilyKey, FontWeight, Line, Styles},
+    types::{Position, Rectangle, Rgba},
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                         SignatureView                                          //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// A floating panel above the caret, showing a signature with its active parameter.
+pub struct SignatureView {
+    family: FontFamilyKey,
+    foreground: Rgba,
+    active: Rgba,
+    background: Rgba,
+}
+
+impl SignatureView {
+    pub fn new(family: FontFamilyKey, foreground: Rgba, active: Rgba, background: Rgba) -> Self {
+        Self {
+            family,
+            foreground,
+            active,
+            background,
+        }
+    }
+
+    /// Renders `signature` above `caret` (below if there is no room), in `layer`.
+    pub fn render(
+        &mut self,
+        context: &mut Context,
+        sizes: Sizes,
+        mut layer: Layer,
+        caret: Position,
+        signature: &Signature,
+    ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
+        let styles = |foreground, weight| Styles {
+            weight,
+            style: Default::default(),
+            foreground,
+            background: Default::default(),
+            underline: false,
+            strike: false,
+            features: Default::default(),
+        };
+
+        let mut shaper = Line::shaper(
+            &signature.label,
+            0,
+            styles(self.foreground, Default::default()),
+        );
+
+        if let Some(active) = signature.active_parameter() {
+            for cluster in shaper.clusters_mut() {
+                if active.contains(&cluster.range().start) {
+                    *cluster.styles_mut() = styles(self.active, FontWeight::Bold);
+                }
+            }
+        }
+
+        let line = shaper.shape(context, self.family, font_size, None, None);
+        let padding = line_height / 4;
+        let width = (line.advance().ceil() as u32 + 2 * padding).min(layer.size().width);
+        let height = line_height + 2 * padding;
+        let panel = Rectangle {
+            top: if caret.top >= height as i32 {
+                caret.top - height as i32
+            } else {
+                caret.top + line_height as i32
+            },
+            left: caret
+                .left
+                .min(layer.size().width as i32 - width as i32)
+                .max(0),
+            width,
+            height,
+        };
+
+        layer.draw(panel, 0).rectangle(None, self.background);
+        layer.draw(panel, 0).glyphs(
+            context,
+            Position {
+                top: padding as i32,
+                left: padding as i32,
+            },
+            &line,
+            line_height,
+        );
+    }
+}
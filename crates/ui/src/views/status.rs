@@ -0,0 +1,80 @@
+use crate::Sizes;
+use virus_graphics::{
+    backend::Layer,
+    text::{Context, FontFamilyKey, Line, Styles},
+    types::{Position, Rectangle, Rgba},
+};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+//                                           StatusView                                           //
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
+
+/// Status lines in the bottom right corner, e.g. language server progress.
+pub struct StatusView {
+    family: FontFamilyKey,
+    foreground: Rgba,
+    background: Rgba,
+}
+
+impl StatusView {
+    pub fn new(family: FontFamilyKey, foreground: Rgba, background: Rgba) -> Self {
+        Self {
+            family,
+            foreground,
+            background,
+        }
+    }
+
+    /// Renders `lines` right-aligned at the bottom of `layer`, the last one at the bottom.
+    pub fn render(
+        &mut self,
+        context: &mut Context,
+        sizes: Sizes,
+        mut layer: Layer,
+        lines: &[String],
+    ) {
+        let Sizes {
+            font_size,
+            line_height,
+        } = sizes;
+
+        let styles = Styles {
+            weight: Default::default(),
+            style: Default::default(),
+            foreground: self.foreground,
+            background: Default::default(),
+            underline: false,
+            strike: false,
+            features: Default::default(),
+        };
+        let padding = line_height / 4;
+        let size = layer.size();
+
+        for (i, line) in lines.iter().rev().enumerate() {
+            let line =
+                Line::shaper(line, 0, styles).shape(context, self.family, font_size, None, None);
+            let width = (line.advance().ceil() as u32 + 2 * padding).min(size.width);
+            let panel = Rectangle {
+                top: size.height as i32 - ((i + 1) as u32 * line_height) as i32,
+                left: size.width as i32 - width as i32,
+                width,
+                height: line_height,
+            };
+
+            if panel.top < 0 {
+                break;
+            }
+
+            layer.draw(panel, 0).rectangle(None, self.background);
+            layer.draw(panel, 0).glyphs(
+                context,
+                Position {
+                    top: 0,
+                    left: padding as i32,
+                },
+                &line,
+                line_height,
+            );
+        }
+    }
+}
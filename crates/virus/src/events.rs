@@ -27,6 +27,7 @@ pub enum Key<'event> {
 pub enum Event<'event> {
     Key(Key<'event>),
     Resized,
+    ScaleFactorChanged,
     Redraw,
     Close,
     Closed,
@@ -67,6 +68,7 @@ impl Events {
     pub fn update<'event>(&mut self, event: &'event WindowEvent) -> Option<Event<'event>> {
         match event {
             WindowEvent::Resized(_) => Some(Event::Resized),
+            WindowEvent::ScaleFactorChanged { .. } => Some(Event::ScaleFactorChanged),
             WindowEvent::CloseRequested => Some(Event::Close),
             WindowEvent::Destroyed => Some(Event::Closed),
             WindowEvent::KeyboardInput {
@@ -5,17 +5,20 @@
 use crate::events::{Event, Events, Key};
 use std::{
     ops::Range,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 use tokio::{process::Command, sync::mpsc::unbounded_channel};
 use virus_editor::{
     async_actor::AsyncActor,
+    document::Selection,
     editor::{Editor, EventLoopMessage},
     fuzzy::Fuzzy,
+    lsp::{CodeAction, Location, Signature, Symbol},
 };
-use virus_ui::{theme::Theme, tween::Tween, ui::Ui};
+use virus_lsp::LspServerConfig;
+use virus_ui::{theme::Theme, tween::Tween, ui::Ui, FontDiscoveryError};
 use winit::{
     application::ApplicationHandler,
     event::WindowEvent,
@@ -49,9 +52,13 @@ impl ApplicationHandler<EventLoopMessage> for Handler {
             .expect("Cannot create window");
         window.set_cursor_visible(false);
 
-        *self = Handler::Initialized {
-            virus: Virus::new(window, editor.take().unwrap()),
-        };
+        match Virus::new(window, editor.take().unwrap()) {
+            Ok(virus) => *self = Handler::Initialized { virus },
+            Err(error) => {
+                eprintln!("{error}");
+                event_loop.exit();
+            }
+        }
     }
 
     fn window_event(
@@ -73,13 +80,30 @@ impl ApplicationHandler<EventLoopMessage> for Handler {
         match event {
             Event::Key(key) => virus.on_key(key, event_loop),
             Event::Resized => virus.on_resized(event_loop),
+            Event::ScaleFactorChanged => virus.on_scale_factor_changed(event_loop),
             Event::Redraw => virus.on_redraw(event_loop),
             Event::Close => virus.on_close(),
             Event::Closed => virus.on_closed(),
         }
     }
 
-    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _event: EventLoopMessage) {}
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: EventLoopMessage) {
+        let virus = match self {
+            // Servers may send messages before the window is resumed, or if it failed to
+            Handler::Uninitialized { .. } => return,
+            Handler::Initialized { virus } => virus,
+        };
+
+        match event {
+            EventLoopMessage::References(references) => virus.on_references(references),
+            EventLoopMessage::CodeActions(actions) => virus.on_code_actions(actions),
+            EventLoopMessage::Rename(placeholder) => virus.on_rename(placeholder),
+            EventLoopMessage::SignatureHelp(signature) => virus.on_signature_help(signature),
+            EventLoopMessage::Symbols(symbols) => virus.on_symbols(false, symbols),
+            EventLoopMessage::WorkspaceSymbols(symbols) => virus.on_symbols(true, symbols),
+            EventLoopMessage::Redraw => virus.ui.request_redraw(),
+        }
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ //
@@ -110,6 +134,101 @@ impl Default for Mode {
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
+/// The references picker, previewing the selected reference in the document view.
+pub struct References {
+    references: Vec<(Location, String)>,
+    selected: usize,
+    /// Where to go back to when cancelling.
+    restore: (PathBuf, Selection),
+    /// Documents opened for previews, to close when cancelling.
+    opened: Vec<PathBuf>,
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// The code actions picker.
+pub struct CodeActions {
+    actions: Vec<CodeAction>,
+    titles: Vec<String>,
+    selected: usize,
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
+/// The symbols picker, of the active document or of the workspace.
+pub struct Symbols {
+    /// Searched by the server as the needle changes, or filtered here.
+    workspace: bool,
+    needle: String,
+    symbols: Vec<Symbol>,
+    /// Indices in `symbols` of the `haystacks`.
+    ranked: Vec<usize>,
+    haystacks: Vec<(String, isize, Vec<Range<usize>>)>,
+    selected: usize,
+}
+
+impl Symbols {
+    fn new(workspace: bool) -> Self {
+        Self {
+            workspace,
+            needle: String::new(),
+            symbols: Vec::new(),
+            ranked: Vec::new(),
+            haystacks: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Reranks the symbols' names against the needle, labelled with their container (and path
+    /// relative to `root` in the workspace).
+    fn rerank(&mut self, root: &Path) {
+        let mut fuzzy = Fuzzy::new_file_search(&self.needle);
+        let mut ranked = self
+            .symbols
+            .iter()
+            .enumerate()
+            .filter_map(|(index, symbol)| {
+                if self.needle.is_empty() {
+                    Some((index, 0, Vec::new()))
+                } else {
+                    fuzzy
+                        .score(&symbol.name)
+                        .map(|(score, ranges)| (index, score, ranges.to_owned()))
+                }
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by_key(|(_, score, _)| -score);
+
+        self.selected = 0;
+        self.ranked = ranked.iter().map(|(index, _, _)| *index).collect();
+        self.haystacks = ranked
+            .into_iter()
+            .map(|(index, score, ranges)| {
+                let symbol = &self.symbols[index];
+                let mut label = symbol.name.clone();
+
+                if let Some(container) = &symbol.container {
+                    label.push_str("  ");
+                    label.push_str(container);
+                }
+
+                if self.workspace {
+                    let path = &symbol.location.path;
+                    label.push_str(&format!(
+                        "  {}:{}",
+                        path.strip_prefix(root).unwrap_or(path).display(),
+                        symbol.location.range.start.0 + 1
+                    ));
+                }
+
+                (label, score, ranges)
+            })
+            .collect();
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────────────────── //
+
 pub struct Virus {
     events: Events,
     editor: Arc<Mutex<Editor>>,
@@ -122,6 +241,13 @@ pub struct Virus {
         Vec<(String, isize, Vec<Range<usize>>)>,
         usize,
     )>,
+    references: Option<References>,
+    symbols: Option<Symbols>,
+    code_actions: Option<CodeActions>,
+    /// The new name of the symbol under the head.
+    rename: Option<String>,
+    /// The signature of the call around the head, with the start of its arguments.
+    signature: Option<(usize, Signature)>,
 }
 
 impl Virus {
@@ -138,7 +264,6 @@ impl Virus {
                 .unwrap_or_else(|| std::env::current_dir().expect("Current directory").into());
             let mut editor = Editor::new(
                 root,
-                (Command::new("rust-analyzer"),),
                 async_actor_sender,
                 Box::new(move |message| {
                     event_loop_proxy
@@ -147,6 +272,22 @@ impl Virus {
                 }),
             );
 
+            editor.add_language_server(
+                "rust",
+                LspServerConfig {
+                    name: String::from("rust-analyzer"),
+                    transport: Command::new("rust-analyzer").into(),
+                    initialization_options: Some(serde_json::json!({
+                        "rustfmt": {
+                            "rangeFormatting": {
+                                "enable": true, // Requires nightly...
+                            },
+                        },
+                    })),
+                    root_markers: vec![String::from("Cargo.lock")],
+                },
+            );
+            editor.set_format_on_save(Some(Duration::from_secs(1)));
             editor.open(file).unwrap();
 
             Arc::new(Mutex::new(editor))
@@ -168,9 +309,12 @@ impl Virus {
         event_loop.set_control_flow(ControlFlow::Wait);
         event_loop
             .run_app(&mut Handler::Uninitialized {
-                editor: Some(editor),
+                editor: Some(editor.clone()),
             })
             .unwrap();
+
+        let shutdown = editor.lock().unwrap().shutdown(Self::SHUTDOWN_TIMEOUT);
+        let _ = shutdown.recv_timeout(2 * Self::SHUTDOWN_TIMEOUT);
     }
 }
 
@@ -179,8 +323,9 @@ impl Virus {
     const TITLE: &'static str = "Virus";
     const FRAMES_PER_SECOND: u8 = 60;
     const MILLIS_PER_FRAME: u128 = 1000 / Virus::FRAMES_PER_SECOND as u128;
+    const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
 
-    fn new(window: Window, editor: Arc<Mutex<Editor>>) -> Self {
+    fn new(window: Window, editor: Arc<Mutex<Editor>>) -> Result<Self, FontDiscoveryError> {
         let events = Events::new();
         let ui = Ui::new(Arc::new(window), {
             let catppuccin = virus_ui::Catppuccin::default();
@@ -190,8 +335,22 @@ impl Virus {
 
             Theme {
                 syntax: virus_editor::syntax::Theme::catppuccin(),
+                font_family: String::from("Victor Mono"),
+                font_fallbacks: [
+                    "Symbols Nerd Font Mono",
+                    "DejaVu Sans Mono",
+                    "Noto Sans Mono",
+                    "Noto Sans Mono CJK SC",
+                    "Noto Sans Math",
+                    "Noto Sans Symbols",
+                    "Noto Sans Symbols 2",
+                ]
+                .map(String::from)
+                .to_vec(),
                 font_size: 20,
                 line_height: 25,
+                text_gamma: 1.4,
+                text_contrast: 0.5,
                 scrollbar_color: catppuccin.surface1.solid(),
                 scroll_duration: Duration::from_millis(500),
                 scroll_tween: Tween::ExpoOut,
@@ -222,16 +381,47 @@ impl Virus {
                 selection_select_mode_color: select_mode.solid().transparent(255 / 2),
                 selection_insert_mode_color: insert_mode.solid().transparent(255 / 2),
             }
-        });
+        })?;
 
-        Self {
+        Ok(Self {
             events,
             editor,
             mode: Mode::default(),
             ui,
             last_render: None,
             search: None,
+            references: None,
+            symbols: None,
+            code_actions: None,
+            rename: None,
+            signature: None,
+        })
+    }
+}
+
+/// Pickers.
+impl Virus {
+    /// Opens the document at `location`, with the head at its start.
+    fn go_to(ui: &mut Ui, editor: &mut Editor, location: &Location) {
+        if editor.open(location.path.clone()).is_err() {
+            return;
         }
+
+        let (line, column) = location.range.start;
+        let document = editor.active_document_mut();
+        let cursor = document.cursor_at(line, column);
+        document.set_selection(cursor.into());
+        ui.ensure_visibility(editor.active_document().head_line());
+    }
+
+    fn preview_reference(ui: &mut Ui, editor: &mut Editor, references: &mut References) {
+        let (location, _) = &references.references[references.selected];
+
+        if editor.document(&location.path).is_none() {
+            references.opened.push(location.path.clone());
+        }
+
+        Self::go_to(ui, editor, location);
     }
 }
 
@@ -239,6 +429,13 @@ impl Virus {
 impl Virus {
     fn on_key(&mut self, key: Key, event_loop: &ActiveEventLoop) {
         let mut editor = self.editor.lock().unwrap();
+        let trigger_signature_help = self.search.is_none()
+            && self.symbols.is_none()
+            && self.rename.is_none()
+            && self.code_actions.is_none()
+            && self.references.is_none()
+            && matches!(self.mode, Mode::Insert)
+            && matches!(key, Key::Str("(" | ","));
 
         if let Some((needle, files, haystacks, selected)) = &mut self.search {
             match key {
@@ -291,6 +488,127 @@ impl Virus {
                     self.search = None;
                 }
             }
+        } else if let Some(symbols) = &mut self.symbols {
+            let mut needle_changed = false;
+
+            match key {
+                Key::Str("i") if self.events.command() => {
+                    symbols.selected = symbols
+                        .selected
+                        .checked_sub(1)
+                        .unwrap_or(symbols.haystacks.len().saturating_sub(1));
+                }
+                Key::Str("k") if self.events.command() => {
+                    if symbols.selected + 1 >= symbols.haystacks.len() {
+                        symbols.selected = 0;
+                    } else {
+                        symbols.selected += 1;
+                    }
+                }
+                Key::Str(str) => {
+                    symbols.needle.push_str(str);
+                    needle_changed = true;
+                }
+                Key::Tab => {}
+                Key::Space => {
+                    symbols.needle.push(' ');
+                    needle_changed = true;
+                }
+                Key::Backspace => {
+                    symbols.needle.pop();
+                    needle_changed = true;
+                }
+                Key::Enter => {
+                    if let Some(&index) = symbols.ranked.get(symbols.selected) {
+                        let location = symbols.symbols[index].location.clone();
+                        Self::go_to(&mut self.ui, &mut editor, &location);
+                    }
+
+                    self.symbols = None;
+                }
+                Key::Escape => {
+                    self.symbols = None;
+                }
+            }
+
+            if let Some(symbols) = self.symbols.as_mut().filter(|_| needle_changed) {
+                symbols.rerank(editor.root());
+
+                if symbols.workspace {
+                    editor.workspace_symbols(symbols.needle.clone());
+                }
+            }
+        } else if let Some(rename) = &mut self.rename {
+            match key {
+                Key::Str(str) => rename.push_str(str),
+                Key::Space => rename.push(' '),
+                Key::Backspace => {
+                    rename.pop();
+                }
+                Key::Enter => {
+                    if !rename.is_empty() {
+                        editor.rename(std::mem::take(rename));
+                    }
+
+                    self.rename = None;
+                }
+                Key::Escape => self.rename = None,
+                Key::Tab => {}
+            }
+        } else if let Some(code_actions) = &mut self.code_actions {
+            match key {
+                Key::Str("i") => {
+                    code_actions.selected = code_actions
+                        .selected
+                        .checked_sub(1)
+                        .unwrap_or(code_actions.actions.len() - 1);
+                }
+                Key::Str("k") => {
+                    code_actions.selected =
+                        (code_actions.selected + 1) % code_actions.actions.len();
+                }
+                Key::Enter => {
+                    let action = code_actions.actions.swap_remove(code_actions.selected);
+                    editor.apply_code_action(action);
+                    self.code_actions = None;
+                }
+                Key::Escape => self.code_actions = None,
+                _ => {}
+            }
+        } else if let Some(references) = &mut self.references {
+            match key {
+                Key::Str("i") => {
+                    references.selected = references
+                        .selected
+                        .checked_sub(1)
+                        .unwrap_or(references.references.len() - 1);
+                    Self::preview_reference(&mut self.ui, &mut editor, references);
+                }
+                Key::Str("k") => {
+                    references.selected = (references.selected + 1) % references.references.len();
+                    Self::preview_reference(&mut self.ui, &mut editor, references);
+                }
+                Key::Enter => {
+                    self.references = None;
+                }
+                Key::Escape => {
+                    let (path, selection) = references.restore.clone();
+
+                    for opened in &references.opened {
+                        editor.close(opened);
+                    }
+
+                    // Unless deleted meanwhile
+                    if editor.open(path).is_ok() {
+                        editor.active_document_mut().set_selection(selection);
+                        self.ui
+                            .ensure_visibility(editor.active_document().head_line());
+                    }
+
+                    self.references = None;
+                }
+                _ => {}
+            }
         } else {
             match &mut self.mode {
                 Mode::Normal { select_mode } => match key {
@@ -343,6 +661,18 @@ impl Virus {
                         self.ui
                             .ensure_visibility(editor.active_document().head_line());
                     }
+                    Key::Str("r") => editor.references(),
+                    Key::Str("R") => editor.prepare_rename(),
+                    Key::Str("a") => editor.code_actions(),
+                    Key::Str("o") => {
+                        self.symbols = Some(Symbols::new(false));
+                        editor.document_symbols();
+                    }
+                    Key::Str("O") => {
+                        self.symbols = Some(Symbols::new(true));
+                        editor.workspace_symbols(String::new());
+                    }
+                    Key::Str("f") => editor.format(),
                     Key::Str("c") if self.events.command() => editor.paste(),
                     Key::Str("c") => editor.copy(),
                     Key::Str("y") => {
@@ -371,9 +701,7 @@ impl Virus {
                         *select_mode = None;
                         editor.active_document_mut().move_anchor_to_head();
                     }
-                    Key::Str("s") if self.events.command() => {
-                        editor.active_document_mut().save().unwrap()
-                    }
+                    Key::Str("s") if self.events.command() => editor.save().unwrap(),
                     Key::Str("/") => {
                         let files = editor
                             .files(true, false)
@@ -410,9 +738,91 @@ impl Virus {
 
         // TODO handle that better
         editor.active_document_mut().parse();
+        editor.flush_changes();
+
+        // Follow the call around the head, or leave it
+        match editor.active_document().call_at_head() {
+            Some((start, argument)) => match &mut self.signature {
+                Some((call, signature)) if *call == start => {
+                    signature.active_parameter = argument;
+                }
+                Some(_) => {
+                    self.signature = None;
+                    editor.signature_help();
+                }
+                None if trigger_signature_help => editor.signature_help(),
+                None => {}
+            },
+            None => self.signature = None,
+        }
 
         // TODO handle that better
-        self.ui.window().request_redraw();
+        self.ui.request_redraw();
+    }
+
+    fn on_references(&mut self, references: Vec<(Location, String)>) {
+        if references.is_empty() {
+            return;
+        }
+
+        let mut editor = self.editor.lock().unwrap();
+        let restore = {
+            let document = editor.active_document();
+            (document.path().to_owned(), document.selection().clone())
+        };
+        let mut references = References {
+            references,
+            selected: 0,
+            restore,
+            opened: Vec::new(),
+        };
+
+        Self::preview_reference(&mut self.ui, &mut editor, &mut references);
+        self.references = Some(references);
+        self.ui.request_redraw();
+    }
+
+    fn on_code_actions(&mut self, actions: Vec<CodeAction>) {
+        if actions.is_empty() {
+            return;
+        }
+
+        self.code_actions = Some(CodeActions {
+            titles: actions.iter().map(|action| action.title.clone()).collect(),
+            actions,
+            selected: 0,
+        });
+        self.ui.request_redraw();
+    }
+
+    fn on_symbols(&mut self, workspace: bool, symbols: Vec<Symbol>) {
+        let editor = self.editor.lock().unwrap();
+
+        // The picker may have been closed, or switched, in the meantime
+        if let Some(picker) = self
+            .symbols
+            .as_mut()
+            .filter(|picker| picker.workspace == workspace)
+        {
+            picker.symbols = symbols;
+            picker.rerank(editor.root());
+            self.ui.request_redraw();
+        }
+    }
+
+    fn on_rename(&mut self, placeholder: String) {
+        self.rename = Some(placeholder);
+        self.ui.request_redraw();
+    }
+
+    fn on_signature_help(&mut self, signature: Signature) {
+        let editor = self.editor.lock().unwrap();
+
+        // The head may have left the call in the meantime
+        if let Some((start, _)) = editor.active_document().call_at_head() {
+            self.signature = Some((start, signature));
+            self.ui.request_redraw();
+        }
     }
 
     fn on_resized(&mut self, event_loop: &ActiveEventLoop) {
@@ -420,12 +830,17 @@ impl Virus {
         self.on_redraw(event_loop);
     }
 
+    fn on_scale_factor_changed(&mut self, event_loop: &ActiveEventLoop) {
+        self.ui.rescale();
+        self.on_redraw(event_loop);
+    }
+
     fn on_redraw(&mut self, _event_loop: &ActiveEventLoop) {
         let now = Instant::now();
         let delta = if let Some(delta) = self.last_render.map(|last_render| now - last_render) {
             if delta.as_millis() < Self::MILLIS_PER_FRAME {
                 // TODO better frame scheduling
-                self.ui.window().request_redraw();
+                self.ui.request_redraw();
                 return;
             }
 
@@ -441,6 +856,9 @@ impl Virus {
         let outline_select_mode_colors = &self.ui.theme().outline_select_mode_colors.clone();
         let outline_insert_mode_colors = &self.ui.theme().outline_insert_mode_colors.clone();
         let mut editor = self.editor.lock().unwrap();
+        let root = editor.root().to_owned();
+        let progress = editor.progress();
+        editor.inlay_hints(self.ui.visible_lines());
         self.ui.render(
             editor.active_document_mut(),
             matches!(
@@ -481,11 +899,37 @@ impl Virus {
                 .as_ref()
                 .map(|(needle, _, haystacks, selected)| {
                     (needle.as_str(), haystacks.as_slice(), *selected)
+                })
+                .or_else(|| {
+                    self.symbols.as_ref().map(|symbols| {
+                        (
+                            symbols.needle.as_str(),
+                            symbols.haystacks.as_slice(),
+                            symbols.selected,
+                        )
+                    })
                 }),
+            self.references.as_ref().map(|references| {
+                (
+                    root.as_path(),
+                    references.references.as_slice(),
+                    references.selected,
+                )
+            }),
+            self.code_actions.as_ref().map(|code_actions| {
+                (
+                    "Code actions",
+                    code_actions.titles.as_slice(),
+                    code_actions.selected,
+                )
+            }),
+            self.rename.as_deref().map(|rename| ("Rename", rename)),
+            self.signature.as_ref().map(|(_, signature)| signature),
+            &progress,
         );
 
         if self.ui.is_animating() {
-            self.ui.window().request_redraw();
+            self.ui.request_redraw();
         }
     }
 
@@ -1,11 +1,14 @@
-use super::super::{
-    generated::schema::{MessageDirection, Notification, Request, Type},
-    utils::{comment_box, docs, ident, pascal, pretty, snake},
-    Model, Quote, NOTIFICATIONS, REQUESTS,
+use super::{
+    super::{
+        generated::schema::{MessageDirection, Notification, Request, Type},
+        utils::{comment_box, docs, ident, pascal, pretty, snake},
+        Model, Quote, NOTIFICATIONS, REQUESTS,
+    },
+    server,
 };
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::Ident;
+use syn::{Ident, Lifetime};
 
 fn usage(type_: &Type, name: &str, model: &Model) -> TokenStream {
     let (_, _, usage, _) = Quote(type_).quote(name, model).unwrap();
@@ -76,38 +79,64 @@ pub fn quote(model: &Model) -> String {
 
     pretty(uses)
         + "\n"
-        + &notification(model, notifications)
+        + &server::notification(
+            "ClientNotification",
+            "Parameters of a client-to-server notification.",
+            model,
+            &notifications,
+        )
+        + "\n"
+        + &server::request(
+            "ClientRequest",
+            "Parameters of a client-to-server request.",
+            model,
+            &requests
+                .clone()
+                .into_iter()
+                .filter(|(request, _, _, _)| {
+                    request.message_direction != MessageDirection::ServerToClient
+                })
+                .collect::<Vec<_>>(),
+        )
+        + "\n"
+        + &notification("client", model, &notifications)
         + "\n"
         + &request(
+            "client",
             model,
-            requests
+            &requests
                 .clone()
                 .into_iter()
                 .filter(|(request, _, _, _)| {
                     request.message_direction != MessageDirection::ServerToClient
                 })
-                .collect(),
+                .collect::<Vec<_>>(),
         )
         + "\n"
         + &response(
+            "client",
             model,
-            requests
-                .clone()
+            &requests
                 .into_iter()
                 .filter(|(request, _, _, _)| {
                     request.message_direction != MessageDirection::ClientToServer
                 })
-                .collect(),
+                .collect::<Vec<_>>(),
         )
 }
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-fn notification(
+/// Quotes the methods for `peer` (`client` or `server`) to send notifications.
+pub fn notification(
+    peer: &str,
     model: &Model,
-    notifications: Vec<(Notification, Option<Type>, Ident, Ident)>,
+    notifications: &[(Notification, Option<Type>, Ident, Ident)],
 ) -> String {
-    let comment_box = comment_box("LspClientNotification");
+    let name = pascal(&format!("lsp_{peer}_notification"));
+    let lifetime = Lifetime::new(&format!("'{peer}"), Span::call_site());
+    let peer = ident(peer);
+    let comment_box = comment_box(&name.to_string());
     let methods = notifications
         .iter()
         .map(|(_, params, pascal_method, snake_method)| {
@@ -121,20 +150,20 @@ fn notification(
                 quote! {
                     #[doc = #documentation]
                     pub async fn #snake_method(&mut self, params: #usage) -> std::io::Result<()> {
-                        self.client.send_notification::<#pascal_method>(params).await
+                        self.#peer.send_notification::<#pascal_method>(params).await
                     }
                 }
             } else {
                 quote! {
                     #[doc = #documentation]
                     pub async fn #snake_method(&mut self) -> std::io::Result<()> {
-                        self.client.send_notification::<#pascal_method>(()).await
+                        self.#peer.send_notification::<#pascal_method>(()).await
                     }
                 }
             }
         });
     let implementation = quote! {
-        impl<'client, W: AsyncWrite + Unpin> super::LspClientNotification<'client, W> {
+        impl<#lifetime, W: AsyncWrite + Unpin> super::#name<#lifetime, W> {
             #(#methods)*
         }
     };
@@ -144,23 +173,22 @@ fn notification(
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident, Ident)>) -> String {
-    let comment_box = comment_box("LspClientRequest");
+/// Quotes the methods for `peer` (`client` or `server`) to send requests.
+pub fn request(
+    peer: &str,
+    model: &Model,
+    requests: &[(Request, Option<Type>, Ident, Ident)],
+) -> String {
+    let name = pascal(&format!("lsp_{peer}_request"));
+    let lifetime = Lifetime::new(&format!("'{peer}"), Span::call_site());
+    let peer = ident(peer);
+    let comment_box = comment_box(&name.to_string());
     let methods = requests
         .iter()
-        .map(|(request, params, pascal_method, snake_method)| {
+        .map(|(_, params, pascal_method, snake_method)| {
             let documentation =
                 format!(" @see [`{pascal_method}`](super::{REQUESTS}::{pascal_method}).");
-            let ok = usage(&request.result, &format!("{pascal_method}Result"), model);
-            let err = match &request.error_data {
-                Some(type_) => usage(type_, &format!("{pascal_method}Error"), model),
-                None => quote! { () },
-            };
-            let return_type = quote! {
-                std::io::Result<
-                    impl '_ + futures::Future<Output = std::io::Result<Result<#ok, Error<#err>>>>
-                >
-            };
+            let return_type = quote! { std::io::Result<PendingResponse<#pascal_method>> };
 
             if let Some(params) = params {
                 assert!(matches!(params, Type::ReferenceType(_)));
@@ -169,20 +197,20 @@ fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident, Ident)>)
                 quote! {
                     #[doc = #documentation]
                     pub async fn #snake_method(&mut self, params: #usage) -> #return_type {
-                        self.client.send_request::<#pascal_method>(params).await
+                        self.#peer.send_request::<#pascal_method>(params).await
                     }
                 }
             } else {
                 quote! {
                     #[doc = #documentation]
                     pub async fn #snake_method(&mut self) -> #return_type {
-                        self.client.send_request::<#pascal_method>(()).await
+                        self.#peer.send_request::<#pascal_method>(()).await
                     }
                 }
             }
         });
     let implementation = quote! {
-        impl<'client, W: AsyncWrite + Unpin> super::LspClientRequest<'client, W> {
+        impl<#lifetime, W: AsyncWrite + Unpin> super::#name<#lifetime, W> {
             #(#methods)*
         }
     };
@@ -192,8 +220,16 @@ fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident, Ident)>)
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-fn response(model: &Model, requests: Vec<(Request, Option<Type>, Ident, Ident)>) -> String {
-    let comment_box = comment_box("LspClientResponse");
+/// Quotes the methods for `peer` (`client` or `server`) to send responses.
+pub fn response(
+    peer: &str,
+    model: &Model,
+    requests: &[(Request, Option<Type>, Ident, Ident)],
+) -> String {
+    let name = pascal(&format!("lsp_{peer}_response"));
+    let lifetime = Lifetime::new(&format!("'{peer}"), Span::call_site());
+    let peer = ident(peer);
+    let comment_box = comment_box(&name.to_string());
     let methods = requests
         .iter()
         .map(|(request, _, pascal_method, snake_method)| {
@@ -212,12 +248,12 @@ fn response(model: &Model, requests: Vec<(Request, Option<Type>, Ident, Ident)>)
                     id: Option<Id>,
                     data: Result<#ok, Error<#err>>,
                 ) -> std::io::Result<()> {
-                    self.client.send_response::<#pascal_method>(id, data).await
+                    self.#peer.send_response::<#pascal_method>(id, data).await
                 }
             }
         });
     let implementation = quote! {
-        impl<'client, W: AsyncWrite + Unpin> super::LspClientResponse<'client, W> {
+        impl<#lifetime, W: AsyncWrite + Unpin> super::#name<#lifetime, W> {
             #(#methods)*
         }
     };
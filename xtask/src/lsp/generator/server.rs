@@ -1,7 +1,10 @@
-use super::super::{
-    generated::schema::{MessageDirection, Notification, Request, Type},
-    utils::{comment_box, docs, ident, pascal, pretty},
-    Model, Quote, NOTIFICATIONS, REQUESTS,
+use super::{
+    super::{
+        generated::schema::{MessageDirection, Notification, Request, Type},
+        utils::{comment_box, docs, ident, pascal, pretty, snake},
+        Model, Quote, NOTIFICATIONS, REQUESTS,
+    },
+    client,
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -31,9 +34,11 @@ pub fn quote(model: &Model) -> String {
                 assert!(params.subtype_1.is_none());
                 params.subtype_0.clone()
             });
-            let pascal_method = pascal(&request.method.trim_start_matches("$/").replace('/', "_"));
+            let method = request.method.trim_start_matches("$/").replace('/', "_");
+            let pascal_method = pascal(&method);
+            let snake_method = snake(&method);
 
-            (request, params, pascal_method)
+            (request, params, pascal_method, snake_method)
         })
         .collect::<Vec<_>>();
     let notifications = model
@@ -52,16 +57,16 @@ pub fn quote(model: &Model) -> String {
                 assert!(params.subtype_1.is_none());
                 params.subtype_0.clone()
             });
-            let pascal_method = pascal(
-                &notification
-                    .method
-                    .trim_start_matches("$/")
-                    .replace('/', "_"),
-            );
-
-            (notification, params, pascal_method)
+            let method = notification
+                .method
+                .trim_start_matches("$/")
+                .replace('/', "_");
+            let pascal_method = pascal(&method);
+            let snake_method = snake(&method);
+
+            (notification, params, pascal_method, snake_method)
         })
-        .collect();
+        .collect::<Vec<_>>();
     let uses = {
         let notifications_mod_name = ident(NOTIFICATIONS);
         let requests_mod_name = ident(REQUESTS);
@@ -74,27 +79,65 @@ pub fn quote(model: &Model) -> String {
 
     pretty(uses)
         + "\n"
-        + &notification(model, notifications)
+        + &notification(
+            "ServerNotification",
+            "Parameters of a server-to-client notification.",
+            model,
+            &notifications,
+        )
         + "\n"
         + &request(
+            "ServerRequest",
+            "Parameters of a server-to-client request.",
+            model,
+            &requests
+                .clone()
+                .into_iter()
+                .filter(|(request, _, _, _)| {
+                    request.message_direction != MessageDirection::ClientToServer
+                })
+                .collect::<Vec<_>>(),
+        )
+        + "\n"
+        + &client::notification("server", model, &notifications)
+        + "\n"
+        + &client::request(
+            "server",
             model,
-            requests
+            &requests
                 .clone()
                 .into_iter()
-                .filter(|(request, _, _)| {
+                .filter(|(request, _, _, _)| {
                     request.message_direction != MessageDirection::ClientToServer
                 })
-                .collect(),
+                .collect::<Vec<_>>(),
+        )
+        + "\n"
+        + &client::response(
+            "server",
+            model,
+            &requests
+                .into_iter()
+                .filter(|(request, _, _, _)| {
+                    request.message_direction != MessageDirection::ServerToClient
+                })
+                .collect::<Vec<_>>(),
         )
 }
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-fn notification(model: &Model, notifications: Vec<(Notification, Option<Type>, Ident)>) -> String {
-    let name = pascal("ServerNotification");
+/// Quotes the `name` enumeration of `notifications`, with its `deserialize` function.
+pub fn notification(
+    name: &str,
+    documentation: &str,
+    model: &Model,
+    notifications: &[(Notification, Option<Type>, Ident, Ident)],
+) -> String {
+    let name = pascal(name);
     let comment_box = comment_box(&name.to_string());
-    let documentation = docs(None, "", "Parameters of a server-to-client notification.").unwrap();
-    let variants = notifications.iter().map(|(_, params, pascal_method)| {
+    let documentation = docs(None, "", documentation).unwrap();
+    let variants = notifications.iter().map(|(_, params, pascal_method, _)| {
         let documentation =
             format!(" @see [`{pascal_method}`](super::{NOTIFICATIONS}::{pascal_method}).");
 
@@ -122,7 +165,7 @@ fn notification(model: &Model, notifications: Vec<(Notification, Option<Type>, I
     };
     let deserialize = notifications
         .iter()
-        .map(|(notification, params, pascal_method)| {
+        .map(|(notification, params, pascal_method, _)| {
             let method = &notification.method;
             let deserialize = if params.is_some() {
                 quote! {
@@ -143,8 +186,14 @@ fn notification(model: &Model, notifications: Vec<(Notification, Option<Type>, I
                 #method => { #deserialize },
             }
         });
+    let methods = notifications
+        .iter()
+        .map(|(notification, _, _, _)| &notification.method);
     let implementation = quote! {
         impl #name {
+            /// The methods of this enumeration.
+            pub const METHODS: &'static [&'static str] = &[#(#methods),*];
+
             pub fn deserialize(notification: Notification<Value>) -> std::io::Result<Self> {
                 Ok(match notification.method.as_ref() {
                     #(#deserialize)*
@@ -159,11 +208,17 @@ fn notification(model: &Model, notifications: Vec<(Notification, Option<Type>, I
 
 // ────────────────────────────────────────────────────────────────────────────────────────────── //
 
-fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident)>) -> String {
-    let name = pascal("ServerRequest");
+/// Quotes the `name` enumeration of `requests`, with its `deserialize` function.
+pub fn request(
+    name: &str,
+    documentation: &str,
+    model: &Model,
+    requests: &[(Request, Option<Type>, Ident, Ident)],
+) -> String {
+    let name = pascal(name);
     let comment_box = comment_box(&name.to_string());
-    let documentation = docs(None, "", "Parameters of a server-to-client request.").unwrap();
-    let variants = requests.iter().map(|(_, params, pascal_method)| {
+    let documentation = docs(None, "", documentation).unwrap();
+    let variants = requests.iter().map(|(_, params, pascal_method, _)| {
         let documentation =
             format!(" @see [`{pascal_method}`](super::{REQUESTS}::{pascal_method}).");
 
@@ -189,7 +244,7 @@ fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident)>) -> Stri
             #(#variants)*
         }
     };
-    let deserialize = requests.iter().map(|(request, params, pascal_method)| {
+    let deserialize = requests.iter().map(|(request, params, pascal_method, _)| {
         let method = &request.method;
         let deserialize = if params.is_some() {
             quote! {
@@ -211,8 +266,12 @@ fn request(model: &Model, requests: Vec<(Request, Option<Type>, Ident)>) -> Stri
             #method => { #deserialize },
         }
     });
+    let methods = requests.iter().map(|(request, _, _, _)| &request.method);
     let implementation = quote! {
         impl #name {
+            /// The methods of this enumeration.
+            pub const METHODS: &'static [&'static str] = &[#(#methods),*];
+
             pub fn deserialize(request: Request<Value>) -> std::io::Result<Self> {
                 Ok(match request.method.as_ref() {
                     #(#deserialize)*
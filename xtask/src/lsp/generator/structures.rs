@@ -1,9 +1,10 @@
 use super::super::{
-    generated::schema::Structure,
+    generated::schema::{Property, Structure},
     utils::{comment_box, docs, pascal, pretty, snake},
     Model, Quote,
 };
 use quote::quote;
+use syn::Ident;
 
 impl<'a> Quote<&'a Structure> {
     pub fn quote(self, model: &Model) -> Option<String> {
@@ -14,26 +15,8 @@ impl<'a> Quote<&'a Structure> {
         let structure_name = pascal(&self.0.name);
         let comment_box = comment_box(&structure_name.to_string());
         let documentation = docs(self.0.name.as_ref(), "", self.0.documentation.as_deref())?;
-        let extends = self
-            .0
-            .extends(&model.model.structures)
-            .flat_map(|structure| {
-                if structure.deprecated.is_some() || structure.proposed.is_some() {
-                    return None;
-                }
-
-                let documentation = docs(None, "", structure.documentation.as_deref())?;
-                let snake_name = snake(&structure.name);
-                let pascal_name = pascal(&structure.name);
-
-                Some(quote! {
-                    #documentation
-                    #[serde(flatten)]
-                    pub #snake_name: #pascal_name,
-                })
-            });
         let mut all_dependencies = Vec::new();
-        let properties = self.0.properties.iter().flat_map(|property| {
+        let mut property = |structure_name: &Ident, property: &Property| {
             if property.deprecated.is_some() || property.proposed.is_some() {
                 return None;
             }
@@ -60,7 +43,58 @@ impl<'a> Quote<&'a Structure> {
                 #[serde(rename = #name)]
                 pub #snake_name: #type_,
             })
-        });
+        };
+
+        // Serde cannot flatten a structure whose properties are redefined here (e.g. `kind` in
+        // `CreateFile` and `ResourceOperation`): the redefined properties would never reach the
+        // flattened structure. Such structures are inlined instead, without the redefinitions.
+        let is_redefined = |name: &str| {
+            self.0
+                .properties
+                .iter()
+                .any(|property| property.name == name)
+        };
+        let mut extends = Vec::new();
+        for structure in self.0.extends(&model.model.structures) {
+            if structure.deprecated.is_some() || structure.proposed.is_some() {
+                continue;
+            }
+
+            if structure
+                .properties
+                .iter()
+                .any(|property| is_redefined(&property.name))
+            {
+                assert!(structure.extends.is_empty() && structure.mixins.is_empty());
+
+                extends.extend(
+                    structure
+                        .properties
+                        .iter()
+                        .filter(|property| !is_redefined(&property.name))
+                        .flat_map(|property_| property(&structure_name, property_)),
+                );
+            } else {
+                let Some(documentation) = docs(None, "", structure.documentation.as_deref())
+                else {
+                    continue;
+                };
+                let snake_name = snake(&structure.name);
+                let pascal_name = pascal(&structure.name);
+
+                extends.push(quote! {
+                    #documentation
+                    #[serde(flatten)]
+                    pub #snake_name: #pascal_name,
+                });
+            }
+        }
+        let properties = self
+            .0
+            .properties
+            .iter()
+            .flat_map(|property_| property(&structure_name, property_))
+            .collect::<Vec<_>>();
 
         let structure = pretty(quote! {
             #documentation